@@ -1,17 +1,21 @@
 // Imports
-use crate::{create, export, import, test, thumbnail};
+use crate::import::InputFileType;
+use crate::{create, diff, export, import, merge, test, thumbnail, watch};
 use anyhow::Context;
 use clap::Parser;
 use rnote_compose::SplitOrder;
 use rnote_engine::SelectionCollision;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs, SelectionExportFormat,
-    SelectionExportPrefs,
+    DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs, PageSelection,
+    SelectionExportFormat, SelectionExportPrefs,
+};
+use rnote_engine::engine::import::{
+    PdfImportPageSpacing, PdfImportPagesType, PdfImportPrefs, XoppImportPrefs,
 };
-use rnote_engine::engine::import::XoppImportPrefs;
 use smol::fs::File;
 use smol::io::{AsyncReadExt, AsyncWriteExt};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tracing::debug;
 
@@ -28,33 +32,58 @@ pub(crate) struct Cli {
 
 impl Cli {
     pub(crate) const THUMBNAIL_TIMEOUT_DEFAULT: u64 = 5;
+    /// The path that stands for stdin when reading and stdout when writing.
+    pub(crate) const STDIO_PATH: &str = "-";
 }
 
+/// Set when the output is written to stdout, the status messages are then printed to stderr.
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
 #[derive(clap::Subcommand, Debug, Clone)]
 pub(crate) enum Command {
-    /// Tests if the specified files can be opened and are valid rnote files.
+    /// Tests if the specified files can be opened and are valid rnote files.{n}
+    /// Prints a validation report with the format version, the stroke counts by type and the found problems:{n}
+    /// orphaned or missing components, NaN or infinite coordinates, empty paths and undecodable images.
     Test {
         /// The rnote files.
         rnote_files: Vec<PathBuf>,
+        /// The format of the validation report.
+        #[arg(long, default_value = "text")]
+        format: ReportFormat,
+        /// Salvages what can be recovered from invalid files and writes a fixed copy next to them,{n}
+        /// named `<file name>.repaired.rnote`.{n}
+        /// Truncated data and stroke entries that can't be loaded are dropped,{n}
+        /// as well as strokes with NaN or infinite coordinates and undecodable images.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        repair: bool,
     },
-    /// Imports the specified input file and saves it as a rnote save file.{n}
-    /// Currently only `.xopp` files can be imported.
+    /// Imports the specified input files and saves them as a rnote save file.{n}
+    /// `.xopp`, `.pdf`, `.svg`, `.png`, `.jpg`, `.jpeg` and `.txt` files can be imported.{n}
+    /// Multiple input files are appended one after another, each starting on a new page.{n}
+    /// A `.xopp` file replaces the document, so it can only be the first input file.{n}
+    /// "-" reads an input file from stdin or writes the rnote save file to stdout.
     Import {
         /// The rnote save file.
         rnote_file: PathBuf,
-        /// The import input file.
-        #[arg(short = 'i', long)]
-        input_file: PathBuf,
+        /// The import input file(s).
+        #[arg(short = 'i', long = "input-file", required = true, num_args = 1..)]
+        input_files: Vec<PathBuf>,
+        /// The format of the input file that is read from stdin.
+        #[arg(long)]
+        input_format: Option<InputFileType>,
         /// When importing a .xopp file, the import dpi can be specified.
         #[arg(long, default_value_t = XoppImportPrefs::default().dpi)]
         xopp_dpi: f64,
+        #[command(flatten)]
+        pdf_args: PdfImportArgs,
     },
     /// Exports the Rnote file(s) and saves it/them in the desired format.{n}
+    /// "-" as rnote file reads it from stdin, "-" as output file writes the export to stdout.{n}
     /// See sub-commands for usage.
     Export {
         #[command(subcommand)]
         export_command: ExportCommand,
-        /// The rnote save file.
+        /// The rnote save file(s).
         #[arg(global = true)]
         rnote_files: Vec<PathBuf>,
         /// The action that will be performed if the to be exported file(s) already exist(s).
@@ -74,7 +103,8 @@ pub(crate) enum Command {
         #[arg(long, action = clap::ArgAction::SetTrue, global = true)]
         open: bool,
     },
-    /// Generate a thumbail from a given rnote file.
+    /// Generate a thumbail from a given rnote file.{n}
+    /// "-" reads the rnote file from stdin or writes the thumbnail to stdout.
     Thumbnail {
         /// The rnote file for which a thumbnail will be created.
         rnote_file: PathBuf,
@@ -87,12 +117,107 @@ pub(crate) enum Command {
         timeout: Option<u64>,
         /// Output path for the thumbnail.
         output: PathBuf,
+        /// Generate the thumbnail from the selected pages, for example "1" or "1-2". Page numbers start with 1.
+        #[arg(long)]
+        pages: Option<PageSelection>,
     },
     /// Create a new (empty) rnote file.
     Create {
         /// The new rnote file path.
         rnote_file: PathBuf,
     },
+    /// Reports the added, removed, moved and modified strokes between two rnote files.
+    Diff {
+        /// The first (old) rnote file.
+        first_file: PathBuf,
+        /// The second (new) rnote file.
+        second_file: PathBuf,
+        /// Renders a visual diff with the changes highlighted to the given Svg file.
+        #[arg(long)]
+        svg: Option<PathBuf>,
+        /// Prints the report as JSON.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Merges the strokes that were added in one rnote file into another, relative to their common ancestor.{n}
+    /// Other changes are taken from the "ours" file. Fails if the "theirs" file removed or changed strokes{n}
+    /// which are still untouched in the "ours" file.{n}
+    /// Can be used as git merge driver with: `rnote-cli merge %O %A %B`.
+    Merge {
+        /// The rnote file of the common ancestor.
+        base_file: PathBuf,
+        /// The rnote file of the current branch.
+        ours_file: PathBuf,
+        /// The rnote file of the other branch, whose added strokes are merged.
+        theirs_file: PathBuf,
+        /// The merged output file. If not set, the "ours" file is overwritten.
+        #[arg(short = 'o', long)]
+        output_file: Option<PathBuf>,
+    },
+    /// Keeps the exports of a directory tree of rnote files up to date.{n}
+    /// The output directory mirrors the directory tree, with each note exported as Pdf or as Svg or Png pages.{n}
+    /// Only notes whose modification time and content changed are exported again,{n}
+    /// the exports of deleted notes are removed.
+    Watch {
+        /// The directory with the rnote files.
+        input_dir: PathBuf,
+        /// The directory the exports are written to.
+        #[arg(short = 'o', long)]
+        output_dir: PathBuf,
+        /// The export format.
+        #[arg(short = 'f', long, default_value = "pdf")]
+        export_format: WatchExportFormat,
+        /// Export without background.
+        #[arg(short = 'b', long, action = clap::ArgAction::SetTrue)]
+        no_background: bool,
+        /// Export without background pattern.
+        #[arg(short = 'p', long, action = clap::ArgAction::SetTrue)]
+        no_pattern: bool,
+        /// Optimize the background and stroke colors for printing.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        optimize_printing: bool,
+        /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into
+        /// pages.
+        #[arg(long, default_value_t = Default::default())]
+        page_order: SplitOrder,
+        /// The bitmap scale-factor in relation to the actual size on the document when exporting Png pages.
+        #[arg(long, default_value_t = DocPagesExportPrefs::default().bitmap_scalefactor)]
+        bitmap_scalefactor: f64,
+        /// Update the exports once and exit instead of watching for changes.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        once: bool,
+    },
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ReportFormat {
+    #[default]
+    /// Human readable text.
+    Text,
+    /// JSON, printed as an array with a report for every file.
+    Json,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ConcatSeparator {
+    #[default]
+    /// No separator page.
+    None,
+    /// A blank page between the documents.
+    Blank,
+    /// A page with the file name of the document, which is also added to the Pdf outline.
+    Title,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum WatchExportFormat {
+    #[default]
+    /// Export each note as Pdf document.
+    Pdf,
+    /// Export the pages of each note as Svg images.
+    Svg,
+    /// Export the pages of each note as Png images.
+    Png,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, Default)]
@@ -120,7 +245,8 @@ pub(crate) enum ExportCommand {
     /// When using "--output-file", only a single input file can be specified.{n}
     /// The export format will be recognized from the file extension of the output file.{n}
     /// When using "--output-format", the file name and path of the rnote file is used with the extension changed.{n}
-    /// "--output-file and "--output-format" are mutually exclusive and specifying one of them is required.
+    /// "--output-file and "--output-format" are mutually exclusive and specifying one of them is required,{n}
+    /// except for writing to stdout with "--output-file -", which requires the format as well.
     Doc {
         #[command(flatten)]
         file_args: FileArgs<DocExportFormat>,
@@ -128,6 +254,21 @@ pub(crate) enum ExportCommand {
         /// pages.
         #[arg(long, default_value_t = Default::default())]
         page_order: SplitOrder,
+        /// Export without the outline and page labels that are generated from page titles and section headings.{n}
+        /// Only applies to the Pdf export format.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        no_outline: bool,
+        /// The pages that are exported, for example "1,3-5" or "2-". Page numbers start with 1.{n}
+        /// When exporting as Svg, the area spanned by the selected pages is exported.
+        #[arg(long)]
+        pages: Option<PageSelection>,
+        /// Concatenate the pages of all rnote files into a single Pdf.{n}
+        /// Requires "--output-file" with a Pdf file extension.
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        concat: bool,
+        /// The page that is inserted before every concatenated document.
+        #[arg(long, default_value = "none", requires = "concat")]
+        concat_separator: ConcatSeparator,
     },
     /// Export each page of the document(s) individually.{n}
     /// Both "--output-dir" and "--output-format" need to be set.
@@ -151,12 +292,16 @@ pub(crate) enum ExportCommand {
         /// The quality of the generated image(s) when Jpeg is used as export format.
         #[arg(long, default_value_t = DocPagesExportPrefs::default().jpeg_quality)]
         jpeg_quality: u8,
+        /// The pages that are exported, for example "1,3-5" or "2-". Page numbers start with 1.
+        #[arg(long)]
+        pages: Option<PageSelection>,
     },
     /// Export a selection in a document.{n}
     /// When using "--output-file", only a single input file can be specified.{n}
     /// The export format is then recognized from the file extension of the output file.{n}
     /// When using "--output-format", the file name and path of the rnote file is used with the extension changed.{n}
-    /// "--output-file and "--output-format" are mutually exclusive and specifying one of them is required.
+    /// "--output-file and "--output-format" are mutually exclusive and specifying one of them is required,{n}
+    /// except for writing to stdout with "--output-file -", which requires the format as well.
     Selection {
         #[command(flatten)]
         file_args: FileArgs<SelectionExportFormat>,
@@ -198,12 +343,42 @@ pub(crate) enum SelectionCommand {
 }
 
 #[derive(clap::Args, Debug, Clone)]
-#[group(required = true, multiple = false)]
+pub(crate) struct PdfImportArgs {
+    /// The first page that is imported from Pdf files, starting with 1.
+    #[arg(long)]
+    pub(crate) pdf_page_start: Option<usize>,
+    /// The last page that is imported from Pdf files.
+    #[arg(long)]
+    pub(crate) pdf_page_end: Option<usize>,
+    /// The password for encrypted Pdf files.
+    #[arg(long)]
+    pub(crate) pdf_password: Option<String>,
+    /// Whether the Pdf pages are imported as bitmap or vector images.
+    #[arg(long, value_enum, default_value_t = PdfImportPrefs::default().pages_type)]
+    pub(crate) pdf_pages_type: PdfImportPagesType,
+    /// The spacing between the imported Pdf pages.
+    #[arg(long, value_enum, default_value_t = PdfImportPrefs::default().page_spacing)]
+    pub(crate) pdf_page_spacing: PdfImportPageSpacing,
+    /// The width of the imported Pdf pages in percentage to the document format width.
+    #[arg(long, default_value_t = PdfImportPrefs::default().page_width_perc)]
+    pub(crate) pdf_page_width_perc: f64,
+    /// The bitmap scale-factor when importing the Pdf pages as bitmap images.
+    #[arg(long, default_value_t = PdfImportPrefs::default().bitmap_scalefactor)]
+    pub(crate) pdf_bitmap_scalefactor: f64,
+    /// Adjust the document format and layout to the Pdf, so that each Pdf page becomes a document page.{n}
+    /// Only applies when the Pdf is the first input file.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub(crate) pdf_adjust_document: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+#[group(required = true, multiple = true)]
 pub(crate) struct FileArgs<T: clap::ValueEnum + 'static + Send + Sync> {
-    /// The export output file. Exclusive with "--output-format".
+    /// The export output file. Exclusive with "--output-format",{n}
+    /// except for "-" which writes to stdout and requires the format.
     #[arg(short = 'o', long, global = true)]
     pub(crate) output_file: Option<PathBuf>,
-    /// The export output format. Exclusive with "--output-file".
+    /// The export output format. Exclusive with "--output-file", except when writing to stdout.
     #[arg(short = 'f', long, global = true)]
     pub(crate) output_format: Option<T>,
 }
@@ -231,21 +406,36 @@ pub(crate) async fn run() -> anyhow::Result<()> {
         eprintln!("Failure initializing tracing: {err:?}");
     };
     let cli = Cli::parse();
+    if cli.command.writes_to_stdout() {
+        STATUS_TO_STDERR.store(true, Ordering::Relaxed);
+    }
 
     match cli.command {
-        Command::Test { rnote_files } => {
-            println!("Testing..");
-            test::run_test(&rnote_files).await?;
-            println!("Tests finished successfully!");
+        Command::Test {
+            rnote_files,
+            format,
+            repair,
+        } => {
+            // Only the report is printed as JSON, to keep the output parsable
+            if format == ReportFormat::Text {
+                println!("Testing..");
+            }
+            test::run_test(&rnote_files, format, repair).await?;
+            if format == ReportFormat::Text {
+                println!("Tests finished successfully!");
+            }
         }
         Command::Import {
             rnote_file,
-            input_file,
+            input_files,
+            input_format,
             xopp_dpi,
+            pdf_args,
         } => {
-            println!("Importing..");
-            import::run_import(&rnote_file, &input_file, xopp_dpi).await?;
-            println!("Import finished!");
+            print_status("Importing..");
+            import::run_import(&rnote_file, &input_files, input_format, xopp_dpi, &pdf_args)
+                .await?;
+            print_status("Import finished!");
         }
         Command::Export {
             rnote_files,
@@ -256,7 +446,7 @@ pub(crate) async fn run() -> anyhow::Result<()> {
             open,
             export_command,
         } => {
-            println!("Exporting..");
+            print_status("Exporting..");
             export::run_export(
                 rnote_files,
                 no_background,
@@ -267,22 +457,23 @@ pub(crate) async fn run() -> anyhow::Result<()> {
                 export_command,
             )
             .await?;
-            println!("Export finished!");
+            print_status("Export finished!");
         }
         Command::Thumbnail {
             rnote_file,
             size,
             timeout,
             output,
+            pages,
         } => {
-            println!("Thumbnail...");
+            print_status("Thumbnail...");
             let timeout = timeout.unwrap_or(Cli::THUMBNAIL_TIMEOUT_DEFAULT);
             let timeout = if timeout == 0 {
                 None
             } else {
                 Some(Duration::from_secs(timeout))
             };
-            thumbnail::run_thumbnail(rnote_file, size, output, timeout).await?;
+            thumbnail::run_thumbnail(rnote_file, size, output, timeout, pages.as_ref()).await?;
         }
         Command::Create {
             rnote_file: new_rnote_file,
@@ -291,11 +482,80 @@ pub(crate) async fn run() -> anyhow::Result<()> {
             create::run_create(&new_rnote_file).await?;
             println!("File creation finished!");
         }
+        Command::Diff {
+            first_file,
+            second_file,
+            svg,
+            json,
+        } => {
+            diff::run_diff(&first_file, &second_file, svg, json).await?;
+        }
+        Command::Merge {
+            base_file,
+            ours_file,
+            theirs_file,
+            output_file,
+        } => {
+            println!("Merging..");
+            let output_file = output_file.unwrap_or_else(|| ours_file.clone());
+            merge::run_merge(&base_file, &ours_file, &theirs_file, &output_file).await?;
+            println!("Merge finished!");
+        }
+        Command::Watch {
+            input_dir,
+            output_dir,
+            export_format,
+            no_background,
+            no_pattern,
+            optimize_printing,
+            page_order,
+            bitmap_scalefactor,
+            once,
+        } => {
+            watch::run_watch(
+                &input_dir,
+                &output_dir,
+                export_format,
+                no_background,
+                no_pattern,
+                optimize_printing,
+                page_order,
+                bitmap_scalefactor,
+                once,
+            )
+            .await?;
+        }
     }
 
     Ok(())
 }
 
+impl Command {
+    /// Whether the output of the command is written to stdout.
+    fn writes_to_stdout(&self) -> bool {
+        match self {
+            Self::Import { rnote_file, .. } => is_stdio(rnote_file),
+            Self::Export {
+                export_command,
+                rnote_files,
+                ..
+            } => match export_command {
+                ExportCommand::Doc { file_args, .. } => match &file_args.output_file {
+                    Some(output_file) => is_stdio(output_file),
+                    None => rnote_files.iter().any(|file| is_stdio(file)),
+                },
+                ExportCommand::Selection { file_args, .. } => match &file_args.output_file {
+                    Some(output_file) => is_stdio(output_file),
+                    None => rnote_files.iter().any(|file| is_stdio(file)),
+                },
+                ExportCommand::DocPages { .. } => false,
+            },
+            Self::Thumbnail { output, .. } => is_stdio(output),
+            _ => false,
+        }
+    }
+}
+
 fn initialize_tracing() -> anyhow::Result<()> {
     let timer = tracing_subscriber::fmt::time::Uptime::default();
 
@@ -309,30 +569,86 @@ fn initialize_tracing() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Prints a status message, to stderr when the output is written to stdout.
+pub(crate) fn print_status(msg: impl std::fmt::Display) {
+    if STATUS_TO_STDERR.load(Ordering::Relaxed) {
+        eprintln!("{msg}");
+    } else {
+        println!("{msg}");
+    }
+}
+
 pub(crate) fn new_progressbar(message: String) -> indicatif::ProgressBar {
     let pb = indicatif::ProgressBar::new_spinner().with_message(message);
-    pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
+    if STATUS_TO_STDERR.load(Ordering::Relaxed) {
+        pb.set_draw_target(indicatif::ProgressDrawTarget::stderr());
+    } else {
+        pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
+    }
     pb.enable_steady_tick(Duration::from_millis(8));
     pb
 }
 
+/// Whether the path is "-", which stands for stdin or stdout.
+pub(crate) fn is_stdio(path: impl AsRef<Path>) -> bool {
+    path.as_ref().as_os_str() == Cli::STDIO_PATH
+}
+
+/// Reads the file, or stdin when the path is "-".
 pub(crate) async fn read_bytes_from_file(file_path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
     let mut bytes = vec![];
+    if is_stdio(&file_path) {
+        smol::Unblock::new(std::io::stdin())
+            .read_to_end(&mut bytes)
+            .await
+            .context("Failed to read from stdin.")?;
+        return Ok(bytes);
+    }
     let mut fh = File::open(file_path).await?;
     fh.read_to_end(&mut bytes).await?;
     Ok(bytes)
 }
 
+/// Creates or overwrites the file, or writes to stdout when the path is "-".
 pub(crate) async fn create_overwrite_file_w_bytes(
     output_file: impl AsRef<Path>,
     bytes: &[u8],
 ) -> anyhow::Result<()> {
+    if is_stdio(&output_file) {
+        let mut stdout = smol::Unblock::new(std::io::stdout());
+        stdout
+            .write_all(bytes)
+            .await
+            .context("Failed to write to stdout.")?;
+        stdout.flush().await?;
+        return Ok(());
+    }
     let mut fh = File::create(output_file).await?;
     fh.write_all(bytes).await?;
     fh.sync_all().await?;
     Ok(())
 }
 
+/// The file name of the path, or the fallback when reading from stdin or writing to stdout.
+pub(crate) fn file_name_or_stdio(
+    file_path: impl AsRef<Path>,
+    stdio_file_name: &str,
+) -> anyhow::Result<String> {
+    if is_stdio(&file_path) {
+        return Ok(stdio_file_name.to_string());
+    }
+    file_path
+        .as_ref()
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Failed to get file name from \"{}\".",
+                file_path.as_ref().display()
+            )
+        })
+}
+
 pub(crate) fn open_file_default_app(file_path: impl AsRef<Path>) -> anyhow::Result<()> {
     open::that_detached(file_path.as_ref()).with_context(|| {
         format!(
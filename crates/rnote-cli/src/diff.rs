@@ -0,0 +1,464 @@
+// Imports
+use crate::{cli, validators};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_engine::Svg;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::store::StrokeKey;
+use rnote_engine::strokes::{Content, Stroke};
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// The margin around the content of the visual diff.
+const DIFF_SVG_MARGIN: f64 = 12.0;
+/// The opacity of removed strokes in the visual diff.
+const DIFF_SVG_REMOVED_OPACITY: f64 = 0.35;
+const DIFF_SVG_ADDED_COLOR: &str = "#2ec27e";
+const DIFF_SVG_REMOVED_COLOR: &str = "#e01b24";
+const DIFF_SVG_MOVED_COLOR: &str = "#3584e4";
+const DIFF_SVG_MODIFIED_COLOR: &str = "#ff7800";
+/// The minimum overlap of the bounds, relative to their union, for two strokes of the same type to be paired as modified.
+const DIFF_MODIFIED_MIN_OVERLAP: f64 = 0.5;
+/// The relative tolerance when comparing the coordinates of strokes that are paired as moved.
+const DIFF_MOVED_TOLERANCE: f64 = 1e-6;
+
+/// The stroke-level differences between two snapshots.
+///
+/// Strokes are matched by their content first. Keys are not compared, because they are only meaningful
+/// between snapshots with a common history. Of the remaining strokes, those of the same type that are equal
+/// apart from their position are paired as moved, and those whose bounds overlap for the most part as modified.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SnapshotDiff {
+    /// Keys of strokes in the second snapshot that are not present in the first.
+    pub(crate) added: Vec<StrokeKey>,
+    /// Keys of strokes in the first snapshot that are not present in the second.
+    pub(crate) removed: Vec<StrokeKey>,
+    /// Keys of strokes that were moved, as (key in first, key in second).
+    pub(crate) moved: Vec<(StrokeKey, StrokeKey)>,
+    /// Keys of strokes that were modified, as (key in first, key in second).
+    pub(crate) modified: Vec<(StrokeKey, StrokeKey)>,
+}
+
+impl SnapshotDiff {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.modified.is_empty()
+    }
+}
+
+pub(crate) async fn run_diff(
+    first_file: &Path,
+    second_file: &Path,
+    svg_file: Option<PathBuf>,
+    json: bool,
+) -> anyhow::Result<()> {
+    validators::file_has_ext(first_file, "rnote")?;
+    validators::file_has_ext(second_file, "rnote")?;
+    if let Some(svg_file) = &svg_file {
+        validators::path_has_ext(svg_file, "svg")?;
+    }
+    let first = load_snapshot(first_file).await?;
+    let second = load_snapshot(second_file).await?;
+    let diff = diff_snapshots(&first, &second)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diff_to_json(&diff, &first, &second))?
+        );
+    } else {
+        print_diff(&diff, &first, &second);
+    }
+    if let Some(svg_file) = svg_file {
+        let svg = gen_diff_svg(&diff, &first, &second)?;
+        cli::create_overwrite_file_w_bytes(&svg_file, svg.svg_data.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn load_snapshot(rnote_file: impl AsRef<Path>) -> anyhow::Result<EngineSnapshot> {
+    let rnote_bytes = cli::read_bytes_from_file(&rnote_file).await?;
+    EngineSnapshot::load_from_rnote_bytes(rnote_bytes).await
+}
+
+/// A hash of the serialized stroke, used to match strokes with identical content.
+pub(crate) fn stroke_fingerprint(stroke: &Stroke) -> anyhow::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(stroke)?.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Computes the stroke-level differences from the first to the second snapshot.
+pub(crate) fn diff_snapshots(
+    first: &EngineSnapshot,
+    second: &EngineSnapshot,
+) -> anyhow::Result<SnapshotDiff> {
+    let mut second_fingerprints = HashMap::<u64, Vec<StrokeKey>>::new();
+    for (key, stroke) in second.stroke_components.iter() {
+        second_fingerprints
+            .entry(stroke_fingerprint(stroke)?)
+            .or_default()
+            .push(key);
+    }
+
+    let mut first_unmatched = Vec::new();
+    let mut second_matched = HashSet::new();
+    for (key, stroke) in first.stroke_components.iter() {
+        let matching = second_fingerprints
+            .get_mut(&stroke_fingerprint(stroke)?)
+            .and_then(|keys| {
+                // prefer the stroke with the same key, if there are multiple ones with the same content
+                let i = keys.iter().position(|&k| k == key).unwrap_or(0);
+                (!keys.is_empty()).then(|| keys.swap_remove(i))
+            });
+        match matching {
+            Some(second_key) => {
+                second_matched.insert(second_key);
+            }
+            None => first_unmatched.push(key),
+        }
+    }
+    let mut second_unmatched = second
+        .stroke_components
+        .keys()
+        .filter(|key| !second_matched.contains(key))
+        .collect::<Vec<StrokeKey>>();
+
+    let mut diff = SnapshotDiff::default();
+    let mut first_remaining = Vec::new();
+    for first_key in first_unmatched {
+        let first_stroke = &first.stroke_components[first_key];
+        let mut moved_to = None;
+        for (i, &second_key) in second_unmatched.iter().enumerate() {
+            if strokes_equal_translated(first_stroke, &second.stroke_components[second_key])? {
+                moved_to = Some(i);
+                break;
+            }
+        }
+        match moved_to {
+            Some(i) => diff
+                .moved
+                .push((first_key, second_unmatched.swap_remove(i))),
+            None => first_remaining.push(first_key),
+        }
+    }
+    for first_key in first_remaining {
+        let first_stroke = &first.stroke_components[first_key];
+        let first_bounds = first_stroke.bounds();
+        let modified_to = second_unmatched
+            .iter()
+            .enumerate()
+            .filter(|&(_, &second_key)| {
+                std::mem::discriminant(first_stroke.as_ref())
+                    == std::mem::discriminant(second.stroke_components[second_key].as_ref())
+            })
+            .map(|(i, &second_key)| {
+                (
+                    i,
+                    bounds_overlap(first_bounds, second.stroke_components[second_key].bounds()),
+                )
+            })
+            .filter(|&(_, overlap)| overlap >= DIFF_MODIFIED_MIN_OVERLAP)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i);
+        match modified_to {
+            Some(i) => diff
+                .modified
+                .push((first_key, second_unmatched.swap_remove(i))),
+            None => diff.removed.push(first_key),
+        }
+    }
+    diff.added = second_unmatched;
+
+    Ok(diff)
+}
+
+/// Whether the strokes are equal apart from their position.
+fn strokes_equal_translated(first: &Stroke, second: &Stroke) -> anyhow::Result<bool> {
+    if std::mem::discriminant(first) != std::mem::discriminant(second) {
+        return Ok(false);
+    }
+    let first_bounds = first.bounds();
+    let second_bounds = second.bounds();
+    if (first_bounds.extents() - second_bounds.extents()).norm() >= 1e-3 {
+        return Ok(false);
+    }
+    let mut translated = first.clone();
+    translated.translate(second_bounds.mins.coords - first_bounds.mins.coords);
+    Ok(json_approx_eq(
+        &serde_json::to_value(&translated)?,
+        &serde_json::to_value(second)?,
+    ))
+}
+
+/// Compares JSON values, with a tolerance for numbers to account for rounding errors of the translation.
+fn json_approx_eq(first: &serde_json::Value, second: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (first, second) {
+        (Value::Number(first), Value::Number(second)) => match (first.as_f64(), second.as_f64()) {
+            (Some(first), Some(second)) => {
+                (first - second).abs()
+                    <= DIFF_MOVED_TOLERANCE * first.abs().max(second.abs()).max(1.0)
+            }
+            _ => first == second,
+        },
+        (Value::Array(first), Value::Array(second)) => {
+            first.len() == second.len()
+                && first
+                    .iter()
+                    .zip(second.iter())
+                    .all(|(first, second)| json_approx_eq(first, second))
+        }
+        (Value::Object(first), Value::Object(second)) => {
+            first.len() == second.len()
+                && first.iter().all(|(key, first)| {
+                    second
+                        .get(key)
+                        .is_some_and(|second| json_approx_eq(first, second))
+                })
+        }
+        _ => first == second,
+    }
+}
+
+/// The area of the intersection of the bounds relative to the area of their union.
+fn bounds_overlap(first: Aabb, second: Aabb) -> f64 {
+    let Some(intersection) = first.intersection(&second) else {
+        return 0.0;
+    };
+    let area = |bounds: Aabb| bounds.extents()[0] * bounds.extents()[1];
+    let union_area = area(first) + area(second) - area(intersection);
+    if union_area <= 0.0 {
+        // Degenerate bounds, like the ones of straight horizontal or vertical lines
+        return if first.mins == second.mins && first.maxs == second.maxs {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    area(intersection) / union_area
+}
+
+pub(crate) fn stroke_type_name(stroke: &Stroke) -> &'static str {
+    match stroke {
+        Stroke::BrushStroke(_) => "brushstroke",
+        Stroke::ShapeStroke(_) => "shapestroke",
+        Stroke::TextStroke(_) => "textstroke",
+        Stroke::VectorImage(_) => "vectorimage",
+        Stroke::BitmapImage(_) => "bitmapimage",
+    }
+}
+
+fn bounds_disp(bounds: Aabb) -> String {
+    format!(
+        "[{:.1}, {:.1}] ({:.1} x {:.1})",
+        bounds.mins[0],
+        bounds.mins[1],
+        bounds.extents()[0],
+        bounds.extents()[1]
+    )
+}
+
+fn print_diff(diff: &SnapshotDiff, first: &EngineSnapshot, second: &EngineSnapshot) {
+    if diff.is_empty() {
+        println!("No stroke differences.");
+        return;
+    }
+    for &key in diff.added.iter() {
+        let stroke = &second.stroke_components[key];
+        println!(
+            "+ {} at {}",
+            stroke_type_name(stroke),
+            bounds_disp(stroke.bounds())
+        );
+    }
+    for &key in diff.removed.iter() {
+        let stroke = &first.stroke_components[key];
+        println!(
+            "- {} at {}",
+            stroke_type_name(stroke),
+            bounds_disp(stroke.bounds())
+        );
+    }
+    for &(first_key, second_key) in diff.moved.iter() {
+        let stroke = &second.stroke_components[second_key];
+        println!(
+            "> {} moved from {} to {}",
+            stroke_type_name(stroke),
+            bounds_disp(first.stroke_components[first_key].bounds()),
+            bounds_disp(stroke.bounds())
+        );
+    }
+    for &(first_key, second_key) in diff.modified.iter() {
+        let stroke = &second.stroke_components[second_key];
+        println!(
+            "~ {} modified from {} to {}",
+            stroke_type_name(stroke),
+            bounds_disp(first.stroke_components[first_key].bounds()),
+            bounds_disp(stroke.bounds())
+        );
+    }
+    println!(
+        "{} added, {} removed, {} moved, {} modified",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.moved.len(),
+        diff.modified.len()
+    );
+}
+
+fn diff_to_json(
+    diff: &SnapshotDiff,
+    first: &EngineSnapshot,
+    second: &EngineSnapshot,
+) -> serde_json::Value {
+    let bounds_json = |bounds: Aabb| {
+        serde_json::json!({
+            "x": bounds.mins[0],
+            "y": bounds.mins[1],
+            "width": bounds.extents()[0],
+            "height": bounds.extents()[1],
+        })
+    };
+    let stroke_json = |stroke: &Stroke| {
+        serde_json::json!({
+            "type": stroke_type_name(stroke),
+            "bounds": bounds_json(stroke.bounds()),
+        })
+    };
+    let changed_json = |&(first_key, second_key): &(StrokeKey, StrokeKey)| {
+        let stroke = &second.stroke_components[second_key];
+        serde_json::json!({
+            "type": stroke_type_name(stroke),
+            "bounds_before": bounds_json(first.stroke_components[first_key].bounds()),
+            "bounds_after": bounds_json(stroke.bounds()),
+        })
+    };
+
+    serde_json::json!({
+        "added": diff.added.iter().map(|&key| stroke_json(&second.stroke_components[key])).collect::<Vec<_>>(),
+        "removed": diff.removed.iter().map(|&key| stroke_json(&first.stroke_components[key])).collect::<Vec<_>>(),
+        "moved": diff.moved.iter().map(changed_json).collect::<Vec<_>>(),
+        "modified": diff.modified.iter().map(changed_json).collect::<Vec<_>>(),
+    })
+}
+
+fn rect_svg_data(bounds: Aabb, color: &str, dashed: bool) -> String {
+    format!(
+        "<rect x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\"{}/>",
+        bounds.mins[0],
+        bounds.mins[1],
+        bounds.extents()[0],
+        bounds.extents()[1],
+        if dashed {
+            " stroke-dasharray=\"6 4\""
+        } else {
+            ""
+        }
+    )
+}
+
+/// Generates a visual diff, drawing the content of the second snapshot together with the removed strokes of the
+/// first one, and highlighting all changes.
+pub(crate) fn gen_diff_svg(
+    diff: &SnapshotDiff,
+    first: &EngineSnapshot,
+    second: &EngineSnapshot,
+) -> anyhow::Result<Svg> {
+    let mut second_keys = second.stroke_components.keys().collect::<Vec<StrokeKey>>();
+    second_keys.sort_unstable_by_key(|&key| second.chrono_components.get(key).map(|c| **c));
+
+    let mut bounds = Aabb::new_invalid();
+    let mut svg_data = String::new();
+
+    for &key in diff.removed.iter() {
+        let stroke_svg = first.stroke_components[key].gen_svg()?;
+        bounds.merge(&stroke_svg.bounds);
+        svg_data += &format!(
+            "<g opacity=\"{DIFF_SVG_REMOVED_OPACITY}\">{}</g>\n",
+            stroke_svg.svg_data
+        );
+    }
+    for key in second_keys {
+        let stroke_svg = second.stroke_components[key].gen_svg()?;
+        bounds.merge(&stroke_svg.bounds);
+        svg_data += &stroke_svg.svg_data;
+        svg_data.push('\n');
+    }
+
+    let highlights = diff
+        .added
+        .iter()
+        .map(|&key| {
+            (
+                second.stroke_components[key].bounds(),
+                DIFF_SVG_ADDED_COLOR,
+                false,
+            )
+        })
+        .chain(diff.removed.iter().map(|&key| {
+            (
+                first.stroke_components[key].bounds(),
+                DIFF_SVG_REMOVED_COLOR,
+                false,
+            )
+        }))
+        .chain(diff.moved.iter().flat_map(|&(first_key, second_key)| {
+            [
+                (
+                    first.stroke_components[first_key].bounds(),
+                    DIFF_SVG_MOVED_COLOR,
+                    true,
+                ),
+                (
+                    second.stroke_components[second_key].bounds(),
+                    DIFF_SVG_MOVED_COLOR,
+                    false,
+                ),
+            ]
+        }))
+        .chain(diff.modified.iter().flat_map(|&(first_key, second_key)| {
+            [
+                (
+                    first.stroke_components[first_key].bounds(),
+                    DIFF_SVG_MODIFIED_COLOR,
+                    true,
+                ),
+                (
+                    second.stroke_components[second_key].bounds(),
+                    DIFF_SVG_MODIFIED_COLOR,
+                    false,
+                ),
+            ]
+        }));
+    for (highlight_bounds, color, dashed) in highlights {
+        bounds.merge(&highlight_bounds);
+        svg_data += &rect_svg_data(highlight_bounds, color, dashed);
+        svg_data.push('\n');
+    }
+
+    if bounds.extents().iter().any(|e| !e.is_finite() || *e <= 0.0) {
+        bounds = Aabb::new(na::point![0.0, 0.0], na::point![1.0, 1.0]);
+    }
+    let bounds = bounds.loosened(DIFF_SVG_MARGIN);
+    let background = format!(
+        "<rect x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\" fill=\"#ffffff\"/>\n",
+        bounds.mins[0],
+        bounds.mins[1],
+        bounds.extents()[0],
+        bounds.extents()[1],
+    );
+
+    let mut svg = Svg {
+        svg_data: background + &svg_data,
+        bounds,
+    };
+    svg.wrap_svg_root(Some(bounds), Some(bounds), true);
+    svg.add_xml_header();
+    Ok(svg)
+}
@@ -1,11 +1,11 @@
 // Imports
-use crate::cli::{self, OnConflict};
+use crate::cli::{self, ConcatSeparator, OnConflict};
 use crate::validators;
 use anyhow::Context;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::SplitOrder;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, PageSelection,
     SelectionExportFormat, SelectionExportPrefs,
 };
 use rnote_engine::engine::{EngineConfigShared, EngineSnapshot};
@@ -28,6 +28,16 @@ pub(crate) async fn run_export(
             "There must be at least one rnote file specified for exporting."
         ));
     }
+    if rnote_files
+        .iter()
+        .filter(|file| cli::is_stdio(file))
+        .count()
+        > 1
+    {
+        return Err(anyhow::anyhow!(
+            "Stdin can only be read once, \"-\" can't be specified for multiple rnote files."
+        ));
+    }
 
     let config = EngineConfigShared::default();
     let mut engine = Engine::default();
@@ -47,6 +57,17 @@ pub(crate) async fn run_export(
             None
         }
     };
+    let exporting_doc_pages = matches!(export_command, cli::ExportCommand::DocPages { .. });
+    // Exports of rnote files read from stdin are written to stdout when no output file is given
+    let writes_to_stdout = match output_file {
+        Some(output_file) => cli::is_stdio(output_file),
+        None => !exporting_doc_pages && rnote_files.iter().any(cli::is_stdio),
+    };
+    if open && writes_to_stdout {
+        return Err(anyhow::anyhow!(
+            "The option \"--open\" can't be used when writing to stdout."
+        ));
+    }
 
     apply_export_prefs(
         &config,
@@ -57,6 +78,58 @@ pub(crate) async fn run_export(
         optimize_printing,
     )?;
 
+    if let cli::ExportCommand::Doc {
+        concat: true,
+        concat_separator,
+        pages,
+        ..
+    } = &export_command
+    {
+        let Some(output_file) = output_file else {
+            return Err(anyhow::anyhow!(
+                "The option \"--concat\" requires \"--output-file\"."
+            ));
+        };
+        let output_file = get_output_file_path(
+            output_file,
+            on_conflict,
+            &mut on_conflict_overwrite,
+            &export_command,
+        )?;
+        let output_file_disp = output_file.display().to_string();
+        let progressbar = cli::new_progressbar(format!(
+            "Exporting {} files concatenated to: \"{output_file_disp}\".",
+            rnote_files.len()
+        ));
+
+        if let Err(e) = export_concatenated(
+            &mut engine,
+            &config,
+            &rnote_files,
+            &output_file,
+            *concat_separator,
+            pages.as_ref(),
+            open,
+        )
+        .await
+        {
+            let abandon_msg =
+                format!("Export concatenated to: \"{output_file_disp}\" failed, Err: {e:?}");
+            if progressbar.is_hidden() {
+                cli::print_status(abandon_msg.as_str())
+            }
+            progressbar.abandon_with_message(abandon_msg);
+            return Err(e);
+        } else {
+            let finish_msg = format!("Export concatenated to: \"{output_file_disp}\" succeeded.");
+            if progressbar.is_hidden() {
+                cli::print_status(finish_msg.as_str())
+            }
+            progressbar.finish_with_message(finish_msg);
+        }
+        return Ok(());
+    }
+
     match output_file {
         Some(output_file) => {
             let Some(rnote_file) = rnote_files.first() else {
@@ -65,7 +138,7 @@ pub(crate) async fn run_export(
                 ));
             };
 
-            validators::file_has_ext(rnote_file, "rnote")?;
+            validators::file_has_ext_or_stdin(rnote_file, "rnote")?;
             let output_file = get_output_file_path(
                 output_file,
                 on_conflict,
@@ -99,7 +172,7 @@ pub(crate) async fn run_export(
                     "Export \"{rnote_file_disp}\" to: \"{output_file_disp}\" failed, Err: {e:?}"
                 );
                 if progressbar.is_hidden() {
-                    println!("{abandon_msg}")
+                    cli::print_status(abandon_msg.as_str())
                 }
                 progressbar.abandon_with_message(abandon_msg);
                 return Err(e);
@@ -107,17 +180,19 @@ pub(crate) async fn run_export(
                 let finish_msg =
                     format!("Export \"{rnote_file_disp}\" to: \"{output_file_disp}\" succeeded.");
                 if progressbar.is_hidden() {
-                    println!("{finish_msg}")
+                    cli::print_status(finish_msg.as_str())
                 }
                 progressbar.finish_with_message(finish_msg);
             }
         }
         None => {
-            let exporting_doc_pages = matches!(export_command, cli::ExportCommand::DocPages { .. });
             let output_ext = file_ext_from_export_command(&config, &export_command);
             let output_files = rnote_files
                 .iter()
                 .map(|file| {
+                    if cli::is_stdio(file) {
+                        return PathBuf::from(cli::Cli::STDIO_PATH);
+                    }
                     let mut output = file.clone();
                     output.set_extension(&output_ext);
                     output
@@ -125,7 +200,7 @@ pub(crate) async fn run_export(
                 .collect::<Vec<PathBuf>>();
 
             for (rnote_file, output_file) in rnote_files.iter().zip(output_files.iter()) {
-                validators::file_has_ext(rnote_file, "rnote")?;
+                validators::file_has_ext_or_stdin(rnote_file, "rnote")?;
                 let output_file = match get_output_file_path(
                     output_file,
                     on_conflict,
@@ -134,7 +209,9 @@ pub(crate) async fn run_export(
                 ) {
                     Ok(file) => file,
                     Err(e) => {
-                        println!("Failed to generate output file path, Err: {e:?}");
+                        cli::print_status(format!(
+                            "Failed to generate output file path, Err: {e:?}"
+                        ));
                         continue;
                     }
                 };
@@ -164,7 +241,7 @@ pub(crate) async fn run_export(
                         ),
                     };
                     if progressbar.is_hidden() {
-                        println!("{abandon_msg}")
+                        cli::print_status(abandon_msg.as_str())
                     }
                     progressbar.abandon_with_message(abandon_msg);
                     return Err(e);
@@ -176,7 +253,7 @@ pub(crate) async fn run_export(
                         true => format!("Export \"{rnote_file_disp}\" succeeded."),
                     };
                     if progressbar.is_hidden() {
-                        println!("{finish_msg}")
+                        cli::print_status(finish_msg.as_str())
                     }
                     progressbar.finish_with_message(finish_msg);
                 }
@@ -199,6 +276,8 @@ fn apply_export_prefs(
         cli::ExportCommand::Doc {
             file_args,
             page_order,
+            no_outline,
+            ..
         } => {
             config.write().export_prefs.doc_export_prefs = create_doc_export_prefs_from_args(
                 output_file,
@@ -207,6 +286,7 @@ fn apply_export_prefs(
                 no_pattern,
                 optimize_printing,
                 *page_order,
+                !no_outline,
             )?;
         }
         cli::ExportCommand::DocPages {
@@ -283,8 +363,15 @@ pub(crate) fn create_doc_export_prefs_from_args(
     no_pattern: bool,
     optimize_printing: bool,
     page_order: SplitOrder,
+    with_outline: bool,
 ) -> anyhow::Result<DocExportPrefs> {
     let format = match (output_file, output_format) {
+        (Some(file), Some(out_format)) if cli::is_stdio(&file) => out_format,
+        (Some(file), None) if cli::is_stdio(&file) => {
+            return Err(anyhow::anyhow!(
+                "\"--output-format\" is required when writing to stdout."
+            ));
+        }
         (Some(file), None) => match file.as_ref().extension().and_then(|ext| ext.to_str()) {
             Some(extension) => doc_export_format_from_ext_str(extension)?,
             None => {
@@ -295,7 +382,6 @@ pub(crate) fn create_doc_export_prefs_from_args(
             }
         },
         (None, Some(out_format)) => out_format,
-        // should be unreachable because the arguments are exclusive (clap conflicts_with)
         (Some(_), Some(_)) => {
             return Err(anyhow::anyhow!(
                 "\"--output-file\" and \"--output-format\" are mutually exclusive."
@@ -315,6 +401,7 @@ pub(crate) fn create_doc_export_prefs_from_args(
         with_pattern: !no_pattern,
         optimize_printing,
         page_order,
+        with_outline,
     };
 
     Ok(prefs)
@@ -363,6 +450,12 @@ pub(crate) fn create_selection_export_prefs_from_args(
     margin: f64,
 ) -> anyhow::Result<SelectionExportPrefs> {
     let format = match (output_file, output_format) {
+        (Some(file), Some(out_format)) if cli::is_stdio(&file) => out_format,
+        (Some(file), None) if cli::is_stdio(&file) => {
+            return Err(anyhow::anyhow!(
+                "\"--output-format\" is required when writing to stdout."
+            ));
+        }
         (Some(file), None) => match file.as_ref().extension().and_then(|ext| ext.to_str()) {
             Some(extension) => get_selection_export_format(extension)?,
             None => {
@@ -373,7 +466,6 @@ pub(crate) fn create_selection_export_prefs_from_args(
             }
         },
         (None, Some(out_format)) => out_format,
-        // should be unreachable because the arguments are exclusive (clap conflicts_with)
         (Some(_), Some(_)) => {
             return Err(anyhow::anyhow!(
                 "\"--output-file\" and \"--output-format\" are mutually exclusive."
@@ -417,6 +509,9 @@ pub(crate) fn get_output_file_path(
     on_conflict_overwrite: &mut Option<OnConflict>,
     export_command: &cli::ExportCommand,
 ) -> anyhow::Result<PathBuf> {
+    if cli::is_stdio(initial_output_file) {
+        return Ok(initial_output_file.to_path_buf());
+    }
     match export_command {
         // output file will be ignored when parsing output file
         cli::ExportCommand::DocPages { .. } => Ok(initial_output_file.to_path_buf()),
@@ -561,18 +656,16 @@ pub(crate) async fn export_to_file(
                 cli::open_file_default_app(output_file)?;
             }
         }
-        cli::ExportCommand::Doc { .. } => {
-            let Some(export_file_name) = output_file
-                .as_ref()
-                .file_name()
-                .map(|s| s.to_string_lossy().to_string())
-            else {
-                return Err(anyhow::anyhow!(
-                    "Failed to get file name from output-file \"{}\".",
-                    output_file.as_ref().display()
-                ));
+        cli::ExportCommand::Doc { pages, .. } => {
+            // The export that is written to stdout is named after the rnote file
+            let export_file_name = if cli::is_stdio(&output_file) {
+                cli::file_name_or_stdio(rnote_file.as_ref().with_extension(""), "stdin")?
+            } else {
+                cli::file_name_or_stdio(&output_file, "")?
             };
-            let export_bytes = engine.export_doc(export_file_name, None).await??;
+            let export_bytes = engine
+                .export_doc_w_page_selection(export_file_name, None, pages.as_ref())
+                .await??;
             cli::create_overwrite_file_w_bytes(&output_file, &export_bytes).await?;
             if open {
                 cli::open_file_default_app(output_file)?;
@@ -582,13 +675,23 @@ pub(crate) async fn export_to_file(
             output_dir,
             output_file_stem,
             export_format: output_format,
+            page_order,
+            pages,
             ..
         } => {
             validators::path_is_dir(output_dir)?;
             // The output file cannot be set with this subcommand
             drop(output_file);
 
-            let pages_export_bytes = engine.export_doc_pages(None).await??;
+            let pages_export_bytes = engine
+                .export_doc_pages_w_page_selection(None, pages.as_ref())
+                .await??;
+            // The exported pages keep their page numbers
+            let pages_amount = engine.pages_bounds_w_content(*page_order).len();
+            let pages_indices = match pages {
+                Some(pages) => pages.indices(pages_amount),
+                None => (0..pages_amount).collect(),
+            };
             let out_ext = output_format.file_ext();
             let output_file_stem = match output_file_stem {
                 Some(o) => o.clone(),
@@ -602,8 +705,7 @@ pub(crate) async fn export_to_file(
                     }
                 },
             };
-            let pages_amount = pages_export_bytes.len();
-            for (page_i, bytes) in pages_export_bytes.into_iter().enumerate() {
+            for (page_i, bytes) in pages_indices.into_iter().zip(pages_export_bytes) {
                 let output_file = doc_page_determine_output_file(
                     page_i,
                     pages_amount,
@@ -628,6 +730,66 @@ pub(crate) async fn export_to_file(
     Ok(())
 }
 
+/// Exports the pages of all rnote files concatenated into a single Pdf.
+///
+/// The page selection applies to each of the documents.
+async fn export_concatenated(
+    engine: &mut Engine,
+    config: &EngineConfigShared,
+    rnote_files: &[PathBuf],
+    output_file: &Path,
+    concat_separator: ConcatSeparator,
+    page_selection: Option<&PageSelection>,
+    open: bool,
+) -> anyhow::Result<()> {
+    let doc_export_prefs = config.read().export_prefs.doc_export_prefs;
+    if doc_export_prefs.export_format != DocExportFormat::Pdf {
+        return Err(anyhow::anyhow!(
+            "Concatenating is only supported when exporting as Pdf."
+        ));
+    }
+    let output_file_name = cli::file_name_or_stdio(output_file, "concatenated")?;
+
+    let mut pages = Vec::new();
+    for (i, rnote_file) in rnote_files.iter().enumerate() {
+        validators::file_has_ext_or_stdin(rnote_file, "rnote")?;
+        let rnote_bytes = cli::read_bytes_from_file(rnote_file).await?;
+        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_bytes).await?;
+        let _ = engine.load_snapshot(engine_snapshot);
+
+        match concat_separator {
+            ConcatSeparator::None => {}
+            ConcatSeparator::Blank => {
+                if i > 0 {
+                    pages.push((engine.extract_title_page_content(None), None));
+                }
+            }
+            ConcatSeparator::Title => {
+                let title = cli::file_name_or_stdio(rnote_file.with_extension(""), "stdin")?;
+                pages.push((engine.extract_title_page_content(Some(&title)), Some(title)));
+            }
+        }
+        pages.extend(
+            engine
+                .extract_pdf_pages(doc_export_prefs.page_order, page_selection)
+                .with_context(|| {
+                    format!(
+                        "Extracting the pages of rnote file \"{}\" failed.",
+                        rnote_file.display()
+                    )
+                })?,
+        );
+    }
+
+    let export_bytes =
+        Engine::export_pdf_pages(output_file_name, pages, doc_export_prefs).await??;
+    cli::create_overwrite_file_w_bytes(output_file, &export_bytes).await?;
+    if open {
+        cli::open_file_default_app(output_file)?;
+    }
+    Ok(())
+}
+
 fn select_strokes_for_selection_args(
     engine: &mut Engine,
     selection: &cli::SelectionCommand,
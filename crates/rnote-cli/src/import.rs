@@ -1,72 +1,244 @@
 // Imports
 use crate::{cli, validators};
-use rnote_engine::Engine;
 use rnote_engine::engine::{EngineConfigShared, EngineSnapshot};
-use std::path::Path;
+use rnote_engine::strokes::Stroke;
+use rnote_engine::{Camera, Engine};
+use std::path::{Path, PathBuf};
+
+/// The kinds of files that can be imported.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InputFileType {
+    /// Xournal++ file.
+    Xopp,
+    /// Pdf file.
+    Pdf,
+    /// Svg image.
+    #[value(name = "svg")]
+    VectorImage,
+    /// Png or Jpeg image.
+    #[value(name = "bitmap", alias = "png", alias = "jpg", alias = "jpeg")]
+    BitmapImage,
+    /// Plain text file.
+    #[value(name = "txt", alias = "text")]
+    Text,
+}
+
+impl InputFileType {
+    /// Determines the type from the file extension, or for stdin ("-") from the given input format.
+    fn from_path(path: &Path, input_format: Option<Self>) -> anyhow::Result<Self> {
+        if cli::is_stdio(path) {
+            return input_format.ok_or_else(|| {
+                anyhow::anyhow!("\"--input-format\" is required when importing from stdin.")
+            });
+        }
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        match ext.as_deref() {
+            // Xopp files don't require file extensions
+            None | Some("xopp") => Ok(Self::Xopp),
+            Some("pdf") => Ok(Self::Pdf),
+            Some("svg") => Ok(Self::VectorImage),
+            Some("png" | "jpg" | "jpeg") => Ok(Self::BitmapImage),
+            Some("txt") => Ok(Self::Text),
+            Some(ext) => Err(anyhow::anyhow!(
+                "Unsupported input file extension \"{ext}\", file \"{}\".",
+                path.display()
+            )),
+        }
+    }
+}
 
 pub(crate) async fn run_import(
     rnote_file: &Path,
-    input_file: &Path,
+    input_files: &[PathBuf],
+    input_format: Option<InputFileType>,
     xopp_dpi: f64,
+    pdf_args: &cli::PdfImportArgs,
 ) -> anyhow::Result<()> {
-    validators::file_has_ext(rnote_file, "rnote")?;
-    // Xopp files don't require file extensions
-    validators::path_is_file(input_file)?;
+    if !cli::is_stdio(rnote_file) {
+        validators::path_has_ext(rnote_file, "rnote")?;
+    }
+    let rnote_file_name = cli::file_name_or_stdio(rnote_file, "stdout.rnote")?;
+    if input_files
+        .iter()
+        .filter(|file| cli::is_stdio(file))
+        .count()
+        > 1
+    {
+        return Err(anyhow::anyhow!(
+            "Stdin can only be read once, \"-\" can't be specified for multiple input files."
+        ));
+    }
+    let input_file_types = input_files
+        .iter()
+        .map(|input_file| {
+            if !cli::is_stdio(input_file) {
+                validators::path_is_file(input_file)?;
+            }
+            InputFileType::from_path(input_file, input_format)
+        })
+        .collect::<anyhow::Result<Vec<InputFileType>>>()?;
+    if input_file_types
+        .iter()
+        .skip(1)
+        .any(|file_type| *file_type == InputFileType::Xopp)
+    {
+        return Err(anyhow::anyhow!(
+            "Xopp files replace the document, so they can only be the first input file."
+        ));
+    }
 
     let config = EngineConfigShared::default();
     let mut engine = Engine::default();
     let _ = engine.install_config(&config, None);
 
-    apply_import_prefs(&config, xopp_dpi)?;
+    apply_import_prefs(&config, xopp_dpi, pdf_args)?;
 
     let rnote_file_disp = rnote_file.display().to_string();
-    let input_file_disp = input_file.display().to_string();
-    let progressbar = cli::new_progressbar(format!(
-        "Importing \"{input_file_disp}\" to: \"{rnote_file_disp}\""
-    ));
+    for (i, (input_file, input_file_type)) in input_files.iter().zip(input_file_types).enumerate() {
+        let input_file_disp = input_file.display().to_string();
+        let progressbar = cli::new_progressbar(format!(
+            "Importing \"{input_file_disp}\" to: \"{rnote_file_disp}\""
+        ));
 
-    if let Err(e) = import_file(&mut engine, &config, input_file, rnote_file).await {
-        let abandon_msg =
-            format!("Import \"{input_file_disp}\" to \"{rnote_file_disp}\" failed, Err: {e:?}");
-        if progressbar.is_hidden() {
-            println!("{abandon_msg}");
+        if i > 0 {
+            // Only a Pdf that is the first input can adjust the document
+            config.write().import_prefs.pdf_import_prefs.adjust_document = false;
         }
-        progressbar.abandon_with_message(abandon_msg);
-        return Err(e);
-    } else {
-        let finish_msg = format!("Import \"{input_file_disp}\" to \"{rnote_file_disp}\" succeeded");
-        if progressbar.is_hidden() {
-            println!("{finish_msg}");
+
+        if let Err(e) = import_file(
+            &mut engine,
+            &config,
+            input_file,
+            input_file_type,
+            pdf_args,
+            i == 0,
+        )
+        .await
+        {
+            let abandon_msg =
+                format!("Import \"{input_file_disp}\" to \"{rnote_file_disp}\" failed, Err: {e:?}");
+            if progressbar.is_hidden() {
+                cli::print_status(abandon_msg.as_str());
+            }
+            progressbar.abandon_with_message(abandon_msg);
+            return Err(e);
+        } else {
+            let finish_msg =
+                format!("Import \"{input_file_disp}\" to \"{rnote_file_disp}\" succeeded");
+            if progressbar.is_hidden() {
+                cli::print_status(finish_msg.as_str());
+            }
+            progressbar.finish_with_message(finish_msg);
         }
-        progressbar.finish_with_message(finish_msg);
     }
 
+    let _ = engine.doc_resize_to_fit_content();
+    let rnote_bytes = engine.save_as_rnote_bytes(rnote_file_name).await??;
+    cli::create_overwrite_file_w_bytes(&rnote_file, &rnote_bytes).await?;
+
     Ok(())
 }
 
-pub(crate) fn apply_import_prefs(config: &EngineConfigShared, xopp_dpi: f64) -> anyhow::Result<()> {
-    config.write().import_prefs.xopp_import_prefs.dpi = xopp_dpi;
+pub(crate) fn apply_import_prefs(
+    config: &EngineConfigShared,
+    xopp_dpi: f64,
+    pdf_args: &cli::PdfImportArgs,
+) -> anyhow::Result<()> {
+    let mut config = config.write();
+    config.import_prefs.xopp_import_prefs.dpi = xopp_dpi;
+    let pdf_import_prefs = &mut config.import_prefs.pdf_import_prefs;
+    pdf_import_prefs.pages_type = pdf_args.pdf_pages_type;
+    pdf_import_prefs.page_spacing = pdf_args.pdf_page_spacing;
+    pdf_import_prefs.page_width_perc = pdf_args.pdf_page_width_perc;
+    pdf_import_prefs.bitmap_scalefactor = pdf_args.pdf_bitmap_scalefactor;
+    pdf_import_prefs.adjust_document = pdf_args.pdf_adjust_document;
     Ok(())
 }
 
-pub(crate) async fn import_file(
+/// Imports the input file into the engine.
+///
+/// Everything except the first input is inserted below the existing content, starting on a new page.
+async fn import_file(
     engine: &mut Engine,
     config: &EngineConfigShared,
     input_file: &Path,
-    rnote_file: &Path,
+    input_file_type: InputFileType,
+    pdf_args: &cli::PdfImportArgs,
+    first: bool,
 ) -> anyhow::Result<()> {
-    let Some(rnote_file_name) = rnote_file
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-    else {
-        return Err(anyhow::anyhow!("Failed to get filename from rnote_file"));
-    };
     let input_bytes = cli::read_bytes_from_file(&input_file).await?;
-    let xopp_import_prefs = config.read().import_prefs.xopp_import_prefs;
-    let snapshot = EngineSnapshot::load_from_xopp_bytes(input_bytes, xopp_import_prefs).await?;
-    let _ = engine.load_snapshot(snapshot);
-    let rnote_bytes = engine.save_as_rnote_bytes(rnote_file_name).await??;
-    cli::create_overwrite_file_w_bytes(&rnote_file, &rnote_bytes).await?;
+    let insert_pos = if first {
+        na::Vector2::<f64>::zeros()
+    } else {
+        na::vector![
+            0.0,
+            engine
+                .bounds_w_content_extended()
+                .map(|bounds| bounds.maxs[1])
+                .unwrap_or(0.0)
+        ]
+    };
+    // Imported images are fit into the viewport, so it is moved to span the page at the insert position
+    engine.camera = Camera::default()
+        .with_size(engine.document.config.format.size())
+        .with_offset(insert_pos);
+
+    match input_file_type {
+        InputFileType::Xopp => {
+            let xopp_import_prefs = config.read().import_prefs.xopp_import_prefs;
+            let snapshot = EngineSnapshot::load_from_xopp_bytes(
+                input_bytes,
+                xopp_import_prefs,
+                (!cli::is_stdio(input_file)).then(|| input_file.to_path_buf()),
+            )
+            .await?;
+            let _ = engine.load_snapshot(snapshot);
+        }
+        InputFileType::Pdf => {
+            // Page numbers start with 1, the end is clamped to the last page of the Pdf
+            if let (Some(start), Some(end)) = (pdf_args.pdf_page_start, pdf_args.pdf_page_end)
+                && start > end
+            {
+                return Err(anyhow::anyhow!(
+                    "\"--pdf-page-start\" ({start}) must not be after \"--pdf-page-end\" ({end})."
+                ));
+            }
+            let page_range = pdf_args.pdf_page_start.unwrap_or(1).saturating_sub(1)
+                ..pdf_args.pdf_page_end.unwrap_or(usize::MAX);
+            let adjust_document = config.read().import_prefs.pdf_import_prefs.adjust_document;
+            let strokes = engine
+                .generate_pdf_pages_from_bytes(
+                    input_bytes,
+                    insert_pos,
+                    Some(page_range),
+                    pdf_args.pdf_password.clone(),
+                )
+                .await??;
+            let _ = engine.import_generated_content(strokes, adjust_document);
+        }
+        InputFileType::VectorImage => {
+            let vectorimage = engine
+                .generate_vectorimage_from_bytes(insert_pos, input_bytes, true)
+                .await??;
+            let _ = engine
+                .import_generated_content(vec![(Stroke::VectorImage(vectorimage), None)], false);
+        }
+        InputFileType::BitmapImage => {
+            let bitmapimage = engine
+                .generate_bitmapimage_from_bytes(insert_pos, input_bytes, true)
+                .await??;
+            let _ = engine
+                .import_generated_content(vec![(Stroke::BitmapImage(bitmapimage), None)], false);
+        }
+        InputFileType::Text => {
+            let text = String::from_utf8(input_bytes)?;
+            let _ = engine.insert_text(text, Some(insert_pos));
+            // Finish editing the inserted text
+            let _ = engine.reinstall_pen_current_style();
+        }
+    }
 
     Ok(())
 }
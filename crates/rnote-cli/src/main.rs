@@ -5,11 +5,14 @@
 // Modules
 pub(crate) mod cli;
 pub(crate) mod create;
+pub(crate) mod diff;
 pub(crate) mod export;
 pub(crate) mod import;
+pub(crate) mod merge;
 pub(crate) mod test;
 pub(crate) mod thumbnail;
 pub(crate) mod validators;
+pub(crate) mod watch;
 
 // Renames
 extern crate nalgebra as na;
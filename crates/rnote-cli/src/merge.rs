@@ -0,0 +1,109 @@
+// Imports
+use crate::diff::{self, stroke_type_name};
+use crate::{cli, validators};
+use rnote_engine::Engine;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::store::StrokeKey;
+use rnote_engine::store::chrono_comp::StrokeLayer;
+use rnote_engine::strokes::Stroke;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Merges the stroke additions of `theirs_file` into `ours_file`, relative to the common ancestor `base_file`.
+///
+/// Everything else is taken from `ours_file`. The merge fails if `theirs_file` removed or modified strokes that
+/// are still present in `ours_file`, because those changes can't be merged without conflicts.
+pub(crate) async fn run_merge(
+    base_file: &Path,
+    ours_file: &Path,
+    theirs_file: &Path,
+    output_file: &Path,
+) -> anyhow::Result<()> {
+    validators::file_has_ext(base_file, "rnote")?;
+    validators::file_has_ext(ours_file, "rnote")?;
+    validators::file_has_ext(theirs_file, "rnote")?;
+    validators::path_has_ext(output_file, "rnote")?;
+    let Some(output_file_name) = output_file
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+    else {
+        return Err(anyhow::anyhow!(
+            "Failed to get filename from output_file '{}'",
+            output_file.display()
+        ));
+    };
+
+    let base = diff::load_snapshot(base_file).await?;
+    let ours = diff::load_snapshot(ours_file).await?;
+    let theirs = diff::load_snapshot(theirs_file).await?;
+
+    let additions = merge_additions(&base, &ours, &theirs)?;
+    let n_additions = additions.len();
+
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(ours);
+    let _ = engine.import_generated_content(additions, false);
+    let rnote_bytes = engine.save_as_rnote_bytes(output_file_name).await??;
+    cli::create_overwrite_file_w_bytes(output_file, &rnote_bytes).await?;
+
+    println!("Merged {n_additions} added stroke(s).");
+    Ok(())
+}
+
+/// Collects the strokes that were added in `theirs` relative to `base` and are not yet present in `ours`.
+fn merge_additions(
+    base: &EngineSnapshot,
+    ours: &EngineSnapshot,
+    theirs: &EngineSnapshot,
+) -> anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>> {
+    let theirs_diff = diff::diff_snapshots(base, theirs)?;
+    let ours_diff = diff::diff_snapshots(base, ours)?;
+
+    // Strokes of the base that were changed by theirs, but are left untouched in ours are conflicts.
+    let ours_changed = ours_diff
+        .removed
+        .iter()
+        .chain(ours_diff.moved.iter().map(|(base_key, _)| base_key))
+        .chain(ours_diff.modified.iter().map(|(base_key, _)| base_key))
+        .copied()
+        .collect::<HashSet<StrokeKey>>();
+    let conflicts = theirs_diff
+        .removed
+        .iter()
+        .chain(theirs_diff.moved.iter().map(|(base_key, _)| base_key))
+        .chain(theirs_diff.modified.iter().map(|(base_key, _)| base_key))
+        .filter(|base_key| !ours_changed.contains(base_key))
+        .map(|&base_key| stroke_type_name(&base.stroke_components[base_key]))
+        .collect::<Vec<&str>>();
+    if !conflicts.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Can't merge, {} stroke(s) of the base were removed or changed in the other file ({}). Only stroke additions can be merged.",
+            conflicts.len(),
+            conflicts.join(", ")
+        ));
+    }
+
+    // Strokes that were added in both files are only merged once.
+    let ours_fingerprints = ours
+        .stroke_components
+        .values()
+        .map(|stroke| diff::stroke_fingerprint(stroke))
+        .collect::<anyhow::Result<HashSet<u64>>>()?;
+    let mut theirs_added = theirs_diff.added;
+    theirs_added.sort_unstable_by_key(|&key| theirs.chrono_components.get(key).map(|c| **c));
+
+    let mut additions = Vec::with_capacity(theirs_added.len());
+    for theirs_key in theirs_added {
+        let stroke = &theirs.stroke_components[theirs_key];
+        if ours_fingerprints.contains(&diff::stroke_fingerprint(stroke)?) {
+            continue;
+        }
+        let layer = theirs
+            .chrono_components
+            .get(theirs_key)
+            .map(|chrono_comp| chrono_comp.layer);
+        additions.push((stroke.as_ref().clone(), layer));
+    }
+
+    Ok(additions)
+}
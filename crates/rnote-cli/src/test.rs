@@ -1,36 +1,307 @@
 // Imports
+use crate::cli::ReportFormat;
+use crate::diff::stroke_type_name;
 use crate::{cli, validators};
+use rnote_compose::shapes::{Shape, Shapeable};
+use rnote_engine::Engine;
 use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::store::StrokeKey;
+use rnote_engine::strokes::Stroke;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
-pub(crate) async fn run_test(rnote_files: &[PathBuf]) -> anyhow::Result<()> {
+/// The validation report of a rnote file.
+#[derive(Debug, Default)]
+pub(crate) struct ValidationReport {
+    /// The format version the file was saved with.
+    pub(crate) version: Option<String>,
+    /// The error when loading the file failed.
+    pub(crate) load_error: Option<String>,
+    /// What needed to be repaired or dropped to salvage the file.
+    pub(crate) repairs: Vec<String>,
+    /// The number of strokes by their type.
+    pub(crate) stroke_counts: BTreeMap<&'static str, usize>,
+    /// Chrono components without a stroke.
+    pub(crate) orphaned_chrono_components: Vec<StrokeKey>,
+    /// Group components without a stroke.
+    pub(crate) orphaned_group_components: Vec<StrokeKey>,
+    /// Strokes without a chrono component.
+    pub(crate) missing_chrono_components: Vec<StrokeKey>,
+    /// Strokes with NaN or infinite coordinates.
+    pub(crate) non_finite_strokes: Vec<StrokeKey>,
+    /// Strokes with paths that consist of only a start position.
+    ///
+    /// These are reported as warnings, they are valid but render as dots or not at all.
+    pub(crate) empty_paths: Vec<StrokeKey>,
+    /// Images whose embedded data can't be decoded, with the error.
+    pub(crate) undecodable_images: Vec<(StrokeKey, String)>,
+}
+
+impl ValidationReport {
+    /// Whether the file is valid. Warnings are not taken into account.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.load_error.is_none()
+            && self.repairs.is_empty()
+            && self.orphaned_chrono_components.is_empty()
+            && self.orphaned_group_components.is_empty()
+            && self.missing_chrono_components.is_empty()
+            && self.non_finite_strokes.is_empty()
+            && self.undecodable_images.is_empty()
+    }
+
+    fn print(&self, file_disp: &str) {
+        let keys_disp = |keys: &[StrokeKey]| {
+            keys.iter()
+                .map(|key| format!("{key:?}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        println!("Report for file \"{file_disp}\":");
+        println!(
+            "  format version: {}",
+            self.version.as_deref().unwrap_or("unknown")
+        );
+        if let Some(load_error) = &self.load_error {
+            println!("  loading failed: {load_error}");
+        }
+        for repair in self.repairs.iter() {
+            println!("  salvage: {repair}");
+        }
+        println!("  strokes: {}", self.stroke_counts.values().sum::<usize>());
+        for (stroke_type, count) in self.stroke_counts.iter() {
+            println!("    {stroke_type}: {count}");
+        }
+        let keys_sections = [
+            (
+                "orphaned chrono components",
+                &self.orphaned_chrono_components,
+            ),
+            ("orphaned group components", &self.orphaned_group_components),
+            ("missing chrono components", &self.missing_chrono_components),
+            ("NaN or infinite coordinates", &self.non_finite_strokes),
+            ("empty paths (warning)", &self.empty_paths),
+        ];
+        for (name, keys) in keys_sections {
+            if !keys.is_empty() {
+                println!("  {name}: {} [{}]", keys.len(), keys_disp(keys));
+            }
+        }
+        for (key, e) in self.undecodable_images.iter() {
+            println!("  undecodable image {key:?}: {e}");
+        }
+        println!(
+            "  result: {}",
+            if self.is_valid() { "valid" } else { "invalid" }
+        );
+    }
+
+    fn to_json(&self, file_disp: &str) -> serde_json::Value {
+        let keys_json = |keys: &[StrokeKey]| {
+            keys.iter()
+                .map(|key| format!("{key:?}"))
+                .collect::<Vec<String>>()
+        };
+        serde_json::json!({
+            "file": file_disp,
+            "valid": self.is_valid(),
+            "version": self.version,
+            "load_error": self.load_error,
+            "repairs": self.repairs,
+            "stroke_counts": self.stroke_counts,
+            "orphaned_chrono_components": keys_json(&self.orphaned_chrono_components),
+            "orphaned_group_components": keys_json(&self.orphaned_group_components),
+            "missing_chrono_components": keys_json(&self.missing_chrono_components),
+            "non_finite_strokes": keys_json(&self.non_finite_strokes),
+            "empty_paths": keys_json(&self.empty_paths),
+            "undecodable_images": self.undecodable_images.iter().map(|(key, e)| serde_json::json!({
+                "stroke": format!("{key:?}"),
+                "error": e,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+pub(crate) async fn run_test(
+    rnote_files: &[PathBuf],
+    format: ReportFormat,
+    repair: bool,
+) -> anyhow::Result<()> {
+    let mut reports_json = Vec::new();
+    let mut n_invalid = 0;
+
     for rnote_file in rnote_files.iter() {
         validators::file_has_ext(rnote_file, "rnote")?;
         let file_disp = rnote_file.display().to_string();
-        let progressbar = cli::new_progressbar(format!("Testing file \"{file_disp}\""));
+        // The JSON report is the only output, so no progress is shown.
+        let progressbar = (format == ReportFormat::Text)
+            .then(|| cli::new_progressbar(format!("Testing file \"{file_disp}\"")));
+
+        let (report, snapshot) = test_file(rnote_file).await?;
+        let repaired_file = match snapshot {
+            Some(snapshot) if repair && !report.is_valid() => {
+                Some(repair_file(rnote_file, snapshot, &report).await?)
+            }
+            _ => None,
+        };
+        if !report.is_valid() {
+            n_invalid += 1;
+        }
 
-        if let Err(e) = test_file(rnote_file).await {
-            let abandon_msg = format!("Test failed, Err: {e:?}");
-            if progressbar.is_hidden() {
-                println!("{abandon_msg}");
+        match format {
+            ReportFormat::Text => {
+                let msg = if report.is_valid() {
+                    format!("Test succeeded for file \"{file_disp}\"")
+                } else {
+                    format!("Test failed for file \"{file_disp}\"")
+                };
+                if let Some(progressbar) = progressbar {
+                    if progressbar.is_hidden() {
+                        println!("{msg}");
+                    }
+                    if report.is_valid() {
+                        progressbar.finish_with_message(msg);
+                    } else {
+                        progressbar.abandon_with_message(msg);
+                    }
+                }
+                report.print(&file_disp);
+                if let Some(repaired_file) = &repaired_file {
+                    println!("  repaired copy: \"{}\"", repaired_file.display());
+                }
             }
-            progressbar.abandon_with_message(abandon_msg);
-            return Err(e);
-        } else {
-            let finish_msg = format!("Test succeeded for file \"{file_disp}\"");
-            if progressbar.is_hidden() {
-                println!("{finish_msg}");
+            ReportFormat::Json => {
+                let mut report_json = report.to_json(&file_disp);
+                report_json["repaired_file"] =
+                    serde_json::json!(repaired_file.map(|file| file.display().to_string()));
+                reports_json.push(report_json);
             }
-            progressbar.finish_with_message(finish_msg);
         }
     }
 
+    if format == ReportFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&reports_json)?);
+    }
+    if n_invalid > 0 {
+        return Err(anyhow::anyhow!(
+            "{n_invalid} of {} file(s) are invalid.",
+            rnote_files.len()
+        ));
+    }
+
     Ok(())
 }
 
-pub(crate) async fn test_file(rnote_file: impl AsRef<Path>) -> anyhow::Result<()> {
+/// Validates the file and creates the report.
+///
+/// Returns the snapshot that could be salvaged from the file as well, which is used for repairing it.
+pub(crate) async fn test_file(
+    rnote_file: impl AsRef<Path>,
+) -> anyhow::Result<(ValidationReport, Option<EngineSnapshot>)> {
     let rnote_bytes = cli::read_bytes_from_file(&rnote_file).await?;
-    let _ = EngineSnapshot::load_from_rnote_bytes(rnote_bytes).await?;
-    // Loading a valid snapshot into the engine can't fail, so we skip it.
-    Ok(())
+    let mut report = ValidationReport::default();
+
+    if let Err(e) = EngineSnapshot::load_from_rnote_bytes(rnote_bytes.clone()).await {
+        report.load_error = Some(format!("{e:?}"));
+    }
+    // Salvaging a valid file results in the same snapshot, so the checks always run on the salvaged one.
+    let salvaged = match EngineSnapshot::salvage_from_rnote_bytes(rnote_bytes).await {
+        Ok(salvaged) => salvaged,
+        Err(e) => {
+            report
+                .repairs
+                .push(format!("nothing could be salvaged, Err: {e:?}"));
+            return Ok((report, None));
+        }
+    };
+    report.version = Some(salvaged.version.to_string());
+    report.repairs = salvaged.repairs;
+    let snapshot = salvaged.snapshot;
+
+    for (key, stroke) in snapshot.stroke_components.iter() {
+        *report
+            .stroke_counts
+            .entry(stroke_type_name(stroke))
+            .or_default() += 1;
+
+        let bounds = stroke.bounds();
+        if !bounds.mins.coords.iter().all(|c| c.is_finite())
+            || !bounds.maxs.coords.iter().all(|c| c.is_finite())
+            || !stroke.outline_path().is_finite()
+        {
+            report.non_finite_strokes.push(key);
+        }
+        let empty_path = match stroke.as_ref() {
+            Stroke::BrushStroke(brushstroke) => brushstroke.path.segments.is_empty(),
+            Stroke::ShapeStroke(shapestroke) => match &shapestroke.shape {
+                Shape::Polyline(polyline) => polyline.path.is_empty(),
+                Shape::Polygon(polygon) => polygon.path.is_empty(),
+                _ => false,
+            },
+            _ => false,
+        };
+        if empty_path {
+            report.empty_paths.push(key);
+        }
+        let image_valid = match stroke.as_ref() {
+            Stroke::VectorImage(vectorimage) => vectorimage.assert_valid(),
+            Stroke::BitmapImage(bitmapimage) => bitmapimage.image.assert_valid(),
+            _ => Ok(()),
+        };
+        if let Err(e) = image_valid {
+            report.undecodable_images.push((key, e.to_string()));
+        }
+        if !snapshot.chrono_components.contains_key(key) {
+            report.missing_chrono_components.push(key);
+        }
+    }
+    report.orphaned_chrono_components = snapshot
+        .chrono_components
+        .keys()
+        .filter(|&key| !snapshot.stroke_components.contains_key(key))
+        .collect();
+    report.orphaned_group_components = snapshot
+        .group_components
+        .keys()
+        .filter(|&key| !snapshot.stroke_components.contains_key(key))
+        .collect();
+
+    Ok((report, Some(snapshot)))
+}
+
+/// Writes a repaired copy of the file next to it, named `<file name>.repaired.rnote`.
+///
+/// Strokes with NaN or infinite coordinates and undecodable images are removed
+/// and the chrono and group components are made consistent with the remaining strokes.
+async fn repair_file(
+    rnote_file: &Path,
+    mut snapshot: EngineSnapshot,
+    report: &ValidationReport,
+) -> anyhow::Result<PathBuf> {
+    let repaired_file = rnote_file.with_extension("repaired.rnote");
+    let Some(repaired_file_name) = repaired_file
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+    else {
+        return Err(anyhow::anyhow!(
+            "Failed to get filename from repaired file '{}'",
+            repaired_file.display()
+        ));
+    };
+
+    let remove_keys = report
+        .non_finite_strokes
+        .iter()
+        .copied()
+        .chain(report.undecodable_images.iter().map(|(key, _)| *key))
+        .collect::<Vec<StrokeKey>>();
+    snapshot.remove_strokes(&remove_keys);
+    snapshot.repair_components();
+
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(snapshot);
+    let rnote_bytes = engine.save_as_rnote_bytes(repaired_file_name).await??;
+    cli::create_overwrite_file_w_bytes(&repaired_file, &rnote_bytes).await?;
+
+    Ok(repaired_file)
 }
@@ -1,13 +1,13 @@
 // Inspired by: https://github.com/ayykamp/rnote-thumbnailer/blob/main/src/main.rs
 // Author: ayykamp <kamp@ayyy.dev>
 
+use crate::cli;
 use anyhow::{Context, anyhow};
-use async_fs::File;
 use core::time::Duration;
-use futures::{AsyncReadExt, AsyncWriteExt, FutureExt, select};
+use futures::{FutureExt, select};
 use rnote_engine::Engine;
 use rnote_engine::engine::EngineSnapshot;
-use rnote_engine::engine::export::SelectionExportFormat;
+use rnote_engine::engine::export::{PageSelection, SelectionExportFormat};
 use smol::Timer;
 use std::path::PathBuf;
 
@@ -16,12 +16,10 @@ pub(crate) async fn run_thumbnail(
     size: u32,
     output: PathBuf,
     timeout: Option<Duration>,
+    page_selection: Option<&PageSelection>,
 ) -> anyhow::Result<()> {
     let mut engine = Engine::default();
-    let mut rnote_file_bytes = vec![];
-
-    let mut fh = File::open(rnote_file).await?;
-    fh.read_to_end(&mut rnote_file_bytes).await?;
+    let rnote_file_bytes = cli::read_bytes_from_file(rnote_file).await?;
     let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(rnote_file_bytes).await?;
 
     // We dont care about the return values of these functions
@@ -32,15 +30,13 @@ pub(crate) async fn run_thumbnail(
         Timer::never().fuse()
     };
     let mut export_op = engine
-        .generate_thumbnail(size, SelectionExportFormat::Png)
+        .generate_thumbnail(size, SelectionExportFormat::Png, page_selection)
         .fuse();
     let export_bytes = select! {
         res = export_op => res??.context("Generating thumbnail failed, empty document.")?,
         _ = timeout => return Err(anyhow!("Timeout reached"))
     };
-    let mut fh = File::create(output).await?;
-    fh.write_all(&export_bytes).await?;
-    fh.sync_all().await?;
+    cli::create_overwrite_file_w_bytes(output, &export_bytes).await?;
 
     Ok(())
 }
@@ -1,3 +1,4 @@
+use crate::cli;
 use std::path::Path;
 
 pub(crate) fn path_is_dir(path: &Path) -> anyhow::Result<()> {
@@ -22,6 +23,19 @@ pub(crate) fn path_is_file(path: &Path) -> anyhow::Result<()> {
 
 pub(crate) fn file_has_ext(path: &Path, expected_ext: &str) -> anyhow::Result<()> {
     path_is_file(path)?;
+    path_has_ext(path, expected_ext)
+}
+
+/// Like `file_has_ext()`, but also accepts "-" for reading from stdin.
+pub(crate) fn file_has_ext_or_stdin(path: &Path, expected_ext: &str) -> anyhow::Result<()> {
+    if cli::is_stdio(path) {
+        return Ok(());
+    }
+    file_has_ext(path, expected_ext)
+}
+
+/// Checks only the extension, so the path may not exist yet.
+pub(crate) fn path_has_ext(path: &Path, expected_ext: &str) -> anyhow::Result<()> {
     match path.extension() {
         Some(ext) if ext == expected_ext => Ok(()),
         Some(ext) => Err(anyhow::anyhow!(
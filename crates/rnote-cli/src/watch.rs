@@ -0,0 +1,419 @@
+// Imports
+use crate::cli::{self, WatchExportFormat};
+use crate::export::{create_doc_export_prefs_from_args, create_doc_pages_export_prefs_from_args};
+use crate::validators;
+use futures::StreamExt;
+use notify_debouncer_full::notify;
+use rnote_compose::SplitOrder;
+use rnote_engine::Engine;
+use rnote_engine::engine::export::{DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs};
+use rnote_engine::engine::{EngineConfigShared, EngineSnapshot};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use tracing::error;
+
+/// The file in the output directory that records the exported notes.
+const MANIFEST_FILE_NAME: &str = ".rnote-watch.json";
+
+/// An exported note.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "exported_note")]
+struct ExportedNote {
+    /// The modification time of the note in milliseconds since the Unix epoch.
+    #[serde(rename = "mtime")]
+    mtime: u64,
+    /// The CRC32 checksum of the note file content, which stays the same across builds and platforms.
+    #[serde(rename = "hash")]
+    hash: u32,
+    /// The export files, relative to the output directory.
+    #[serde(rename = "exports")]
+    exports: Vec<PathBuf>,
+}
+
+/// Records the exported notes, so that unchanged notes are skipped and the exports of deleted notes can be removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "watch_manifest")]
+struct Manifest {
+    /// The export settings the notes were exported with. When they change, all notes are exported again.
+    #[serde(rename = "export_settings")]
+    export_settings: String,
+    /// The exported notes, keyed by their path relative to the input directory.
+    #[serde(rename = "notes")]
+    notes: BTreeMap<PathBuf, ExportedNote>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_watch(
+    input_dir: &Path,
+    output_dir: &Path,
+    export_format: WatchExportFormat,
+    no_background: bool,
+    no_pattern: bool,
+    optimize_printing: bool,
+    page_order: SplitOrder,
+    bitmap_scalefactor: f64,
+    once: bool,
+) -> anyhow::Result<()> {
+    validators::path_is_dir(input_dir)?;
+    smol::fs::create_dir_all(output_dir).await?;
+    // Absolute paths are needed to recognize an output directory inside the input directory
+    let input_dir = input_dir.canonicalize()?;
+    let output_dir = output_dir.canonicalize()?;
+
+    let config = EngineConfigShared::default();
+    let mut engine = Engine::default();
+    let _ = engine.install_config(&config, None);
+    {
+        let mut config = config.write();
+        match export_format {
+            WatchExportFormat::Pdf => {
+                config.export_prefs.doc_export_prefs = create_doc_export_prefs_from_args(
+                    None::<PathBuf>,
+                    Some(DocExportFormat::Pdf),
+                    no_background,
+                    no_pattern,
+                    optimize_printing,
+                    page_order,
+                    true,
+                )?;
+            }
+            WatchExportFormat::Svg | WatchExportFormat::Png => {
+                let pages_export_format = match export_format {
+                    WatchExportFormat::Png => DocPagesExportFormat::Png,
+                    _ => DocPagesExportFormat::Svg,
+                };
+                config.export_prefs.doc_pages_export_prefs =
+                    create_doc_pages_export_prefs_from_args(
+                        pages_export_format,
+                        no_background,
+                        no_pattern,
+                        optimize_printing,
+                        page_order,
+                        bitmap_scalefactor,
+                        DocPagesExportPrefs::default().jpeg_quality,
+                    )?;
+            }
+        }
+    }
+    let export_settings = format!(
+        "format: {export_format:?}, background: {}, pattern: {}, optimize printing: {optimize_printing}, page order: {page_order:?}, bitmap scale-factor: {bitmap_scalefactor}",
+        !no_background, !no_pattern
+    );
+
+    let mut watcher = Watcher {
+        engine,
+        config,
+        input_dir,
+        output_dir,
+        export_format,
+        manifest: Manifest::default(),
+    };
+    watcher.load_manifest(export_settings).await?;
+    watcher.sync().await?;
+    if once {
+        return Ok(());
+    }
+
+    let (tx, mut rx) = futures::channel::mpsc::unbounded();
+    let mut debouncer = notify_debouncer_full::new_debouncer(
+        Duration::from_millis(1000),
+        None,
+        move |res| {
+            if let Err(e) = tx.unbounded_send(res) {
+                error!(
+                    "File watcher reported change, but failed to send it through channel. Err: {e:?}"
+                );
+            }
+        },
+    )?;
+    debouncer.watch(&watcher.input_dir, notify::RecursiveMode::Recursive)?;
+    cli::print_status(format!(
+        "Watching \"{}\" for changes..",
+        watcher.input_dir.display()
+    ));
+
+    while let Some(res) = rx.next().await {
+        match res {
+            Ok(events) => {
+                // Writing the exports triggers events when the output directory is inside the input directory
+                if events.iter().all(|event| {
+                    event
+                        .paths
+                        .iter()
+                        .all(|path| path.starts_with(&watcher.output_dir))
+                }) {
+                    continue;
+                }
+                if let Err(e) = watcher.sync().await {
+                    cli::print_status(format!("Updating the exports failed, Err: {e:?}"));
+                }
+            }
+            Err(e) => error!("File watcher sent error message, Err: {e:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+struct Watcher {
+    engine: Engine,
+    config: EngineConfigShared,
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    export_format: WatchExportFormat,
+    manifest: Manifest,
+}
+
+impl Watcher {
+    /// Loads the manifest from the output directory.
+    ///
+    /// When it was created with other export settings, the previous exports are removed so that all notes get exported again.
+    async fn load_manifest(&mut self, export_settings: String) -> anyhow::Result<()> {
+        let manifest_file = self.output_dir.join(MANIFEST_FILE_NAME);
+        if manifest_file.exists() {
+            let manifest_bytes = cli::read_bytes_from_file(&manifest_file).await?;
+            match serde_json::from_slice::<Manifest>(&manifest_bytes) {
+                Ok(manifest) => self.manifest = manifest,
+                Err(e) => cli::print_status(format!(
+                    "Failed to read manifest \"{}\", exporting all notes again. Err: {e:?}",
+                    manifest_file.display()
+                )),
+            }
+        }
+        if self.manifest.export_settings != export_settings {
+            for (_, exported) in std::mem::take(&mut self.manifest.notes) {
+                self.remove_exports(&exported.exports).await;
+            }
+            self.manifest.export_settings = export_settings;
+        }
+        Ok(())
+    }
+
+    async fn save_manifest(&self) -> anyhow::Result<()> {
+        let manifest_bytes = serde_json::to_vec_pretty(&self.manifest)?;
+        cli::create_overwrite_file_w_bytes(
+            self.output_dir.join(MANIFEST_FILE_NAME),
+            &manifest_bytes,
+        )
+        .await
+    }
+
+    /// Exports the new and changed notes and removes the exports of deleted notes.
+    async fn sync(&mut self) -> anyhow::Result<()> {
+        let notes = find_rnote_files(&self.input_dir, &self.output_dir)?;
+        let mut manifest_changed = false;
+
+        let deleted_notes = self
+            .manifest
+            .notes
+            .keys()
+            .filter(|&note| !notes.contains(note))
+            .cloned()
+            .collect::<Vec<PathBuf>>();
+        for note in deleted_notes {
+            if let Some(exported) = self.manifest.notes.remove(&note) {
+                self.remove_exports(&exported.exports).await;
+                cli::print_status(format!(
+                    "Removed the exports of deleted note \"{}\".",
+                    note.display()
+                ));
+            }
+            manifest_changed = true;
+        }
+        for note in notes.iter() {
+            match self.sync_note(note).await {
+                Ok(changed) => manifest_changed |= changed,
+                Err(e) => cli::print_status(format!(
+                    "Checking note \"{}\" failed, Err: {e:?}",
+                    note.display()
+                )),
+            }
+        }
+
+        if manifest_changed {
+            self.save_manifest().await?;
+        }
+        Ok(())
+    }
+
+    /// Exports the note if its modification time and content changed.
+    ///
+    /// Returns whether the manifest was changed.
+    async fn sync_note(&mut self, note: &Path) -> anyhow::Result<bool> {
+        let note_file = self.input_dir.join(note);
+        let mtime = u64::try_from(
+            std::fs::metadata(&note_file)?
+                .modified()?
+                .duration_since(UNIX_EPOCH)?
+                .as_millis(),
+        )?;
+        if self
+            .manifest
+            .notes
+            .get(note)
+            .is_some_and(|exported| exported.mtime == mtime)
+        {
+            return Ok(false);
+        }
+        let note_bytes = cli::read_bytes_from_file(&note_file).await?;
+        let mut crc = flate2::Crc::new();
+        crc.update(&note_bytes);
+        let hash = crc.sum();
+        if let Some(exported) = self.manifest.notes.get_mut(note)
+            && exported.hash == hash
+        {
+            // Only the modification time changed
+            exported.mtime = mtime;
+            return Ok(true);
+        }
+
+        let note_disp = note.display().to_string();
+        let progressbar = cli::new_progressbar(format!("Exporting \"{note_disp}\"."));
+        let exports = match self.export_note(note, note_bytes).await {
+            Ok(exports) => {
+                let finish_msg = format!("Export \"{note_disp}\" succeeded.");
+                if progressbar.is_hidden() {
+                    cli::print_status(finish_msg.as_str());
+                }
+                progressbar.finish_with_message(finish_msg);
+                exports
+            }
+            Err(e) => {
+                // The note is exported again on the next change
+                let abandon_msg = format!("Export \"{note_disp}\" failed, Err: {e:?}");
+                if progressbar.is_hidden() {
+                    cli::print_status(abandon_msg.as_str());
+                }
+                progressbar.abandon_with_message(abandon_msg);
+                return Ok(false);
+            }
+        };
+
+        // For example pages that were removed from the note
+        if let Some(exported) = self.manifest.notes.get(note) {
+            let stale_exports = exported
+                .exports
+                .iter()
+                .filter(|&export| !exports.contains(export))
+                .cloned()
+                .collect::<Vec<PathBuf>>();
+            self.remove_exports(&stale_exports).await;
+        }
+        self.manifest.notes.insert(
+            note.to_path_buf(),
+            ExportedNote {
+                mtime,
+                hash,
+                exports,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Exports the note into the mirrored directory in the output directory.
+    ///
+    /// Returns the export files relative to the output directory.
+    async fn export_note(
+        &mut self,
+        note: &Path,
+        note_bytes: Vec<u8>,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(note_bytes).await?;
+        let _ = self.engine.load_snapshot(engine_snapshot);
+        let export_dir = note.parent().unwrap_or(Path::new(""));
+        let Some(note_file_stem) = note.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            return Err(anyhow::anyhow!(
+                "Failed to get file stem from note \"{}\"",
+                note.display()
+            ));
+        };
+        smol::fs::create_dir_all(self.output_dir.join(export_dir)).await?;
+
+        match self.export_format {
+            WatchExportFormat::Pdf => {
+                let export_file = note.with_extension(DocExportFormat::Pdf.file_ext());
+                let export_bytes = self
+                    .engine
+                    .export_doc(format!("{note_file_stem}.pdf"), None)
+                    .await??;
+                cli::create_overwrite_file_w_bytes(
+                    self.output_dir.join(&export_file),
+                    &export_bytes,
+                )
+                .await?;
+                Ok(vec![export_file])
+            }
+            WatchExportFormat::Svg | WatchExportFormat::Png => {
+                let out_ext = self
+                    .config
+                    .read()
+                    .export_prefs
+                    .doc_pages_export_prefs
+                    .export_format
+                    .file_ext();
+                let pages_export_bytes = self.engine.export_doc_pages(None).await??;
+                // Named the same way as by the "doc-pages" export
+                let leading_zeros = pages_export_bytes.len().to_string().len();
+                let mut exports = Vec::with_capacity(pages_export_bytes.len());
+                for (page_i, bytes) in pages_export_bytes.into_iter().enumerate() {
+                    let export_file = export_dir.join(format!(
+                        "{note_file_stem} - page {number}.{out_ext}",
+                        number = format_args!("{:0fill$}", page_i + 1, fill = leading_zeros)
+                    ));
+                    cli::create_overwrite_file_w_bytes(self.output_dir.join(&export_file), &bytes)
+                        .await?;
+                    exports.push(export_file);
+                }
+                Ok(exports)
+            }
+        }
+    }
+
+    /// Removes the export files and the directories that became empty.
+    async fn remove_exports(&self, exports: &[PathBuf]) {
+        for export in exports {
+            let export_file = self.output_dir.join(export);
+            if let Err(e) = smol::fs::remove_file(&export_file).await
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                cli::print_status(format!(
+                    "Failed to remove export \"{}\", Err: {e:?}",
+                    export_file.display()
+                ));
+            }
+            let mut dir = export_file.parent();
+            while let Some(d) = dir.filter(|&d| d != self.output_dir) {
+                // Fails for directories that are not empty
+                if smol::fs::remove_dir(d).await.is_err() {
+                    break;
+                }
+                dir = d.parent();
+            }
+        }
+    }
+}
+
+/// Finds the rnote files in the directory tree, skipping the output directory.
+///
+/// Returns the paths relative to the input directory.
+fn find_rnote_files(input_dir: &Path, output_dir: &Path) -> anyhow::Result<BTreeSet<PathBuf>> {
+    let mut rnote_files = BTreeSet::new();
+    let mut dirs = vec![input_dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            // Symlinked directories are not followed, they could create cycles
+            if entry.file_type()?.is_dir() {
+                if path != output_dir {
+                    dirs.push(path);
+                }
+            } else if path.is_file() && path.extension().is_some_and(|ext| ext == "rnote") {
+                rnote_files.insert(path.strip_prefix(input_dir)?.to_path_buf());
+            }
+        }
+    }
+    Ok(rnote_files)
+}
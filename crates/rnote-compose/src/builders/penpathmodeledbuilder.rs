@@ -7,8 +7,8 @@ use crate::style::Composer;
 use crate::{Constraints, EventResult};
 use crate::{PenPath, Style};
 use ink_stroke_modeler_rs::{
-    ModelerInput, ModelerInputEventType, ModelerParams, StrokeModeler, error::ElementError,
-    error::ModelerError,
+    ModelerInput, ModelerInputEventType, ModelerParams, ModelerResult, StrokeModeler,
+    error::ElementError, error::ModelerError,
 };
 use once_cell::sync::Lazy;
 use p2d::bounding_volume::Aabb;
@@ -165,19 +165,20 @@ impl PenPathModeledBuilder {
         event_type: ModelerInputEventType,
         now: Instant,
     ) {
+        let input_time = now.duration_since(self.start_time).as_secs_f64();
         let modeler_input = ModelerInput {
             event_type,
             pos: (element.pos[0], element.pos[1]),
-            time: now.duration_since(self.start_time).as_secs_f64(),
+            time: input_time,
             pressure: element.pressure,
         };
 
         match self.stroke_modeler.update(modeler_input) {
-            Ok(results) => self.buffer.extend(results.into_iter().map(|r| {
-                let (x, y) = r.pos;
-                let pressure = r.pressure;
-                Element::new(na::vector![x, y], pressure)
-            })),
+            Ok(results) => self.buffer.extend(
+                results
+                    .into_iter()
+                    .map(|r| element_from_modeler_result(r, &element, input_time)),
+            ),
             Err(e) => {
                 match e {
                     ModelerError::Element {
@@ -223,11 +224,7 @@ impl PenPathModeledBuilder {
             self.prediction_buffer = match self.stroke_modeler.predict() {
                 Ok(results) => results
                     .into_iter()
-                    .map(|r| {
-                        let (x, y) = r.pos;
-                        let pressure = r.pressure;
-                        Element::new(na::vector![x, y], pressure)
-                    })
+                    .map(|r| element_from_modeler_result(r, &element, input_time))
                     .collect::<Vec<Element>>(),
                 Err(e) => {
                     error!("Stroke modeler predict failed, Err: {e:?}");
@@ -255,11 +252,11 @@ impl PenPathModeledBuilder {
             pressure: element.pressure,
         }) {
             Ok(results) => {
-                self.buffer.extend(results.into_iter().map(|r| {
-                    let (x, y) = r.pos;
-                    let pressure = r.pressure;
-                    Element::new(na::vector![x, y], pressure)
-                }));
+                self.buffer.extend(
+                    results
+                        .into_iter()
+                        .map(|r| element_from_modeler_result(r, &element, 0.0)),
+                );
             }
             Err(e) => {
                 error!("Updating stroke modeler failed while restarting, Err: {e:?}")
@@ -267,3 +264,23 @@ impl PenPathModeledBuilder {
         }
     }
 }
+
+/// Creates an element from a modeler result, carrying over the stylus axes of the input element.
+///
+/// The timestamp of the input element is shifted by the time of the result relative to the input time,
+/// because the modeler might produce multiple interpolated results for a single input.
+fn element_from_modeler_result(
+    result: ModelerResult,
+    input_element: &Element,
+    input_time: f64,
+) -> Element {
+    let (x, y) = result.pos;
+    Element {
+        pos: na::vector![x, y],
+        pressure: result.pressure.clamp(0.0, 1.0),
+        time: input_element
+            .time
+            .map(|time| time + result.time - input_time),
+        ..*input_element
+    }
+}
@@ -1,9 +1,12 @@
 // Imports
-use crate::transform::Transformable;
+use crate::transform::{FlipDirection, Transformable};
 use p2d::bounding_volume::Aabb;
 use serde::{Deserialize, Serialize};
 
 /// A pen input element.
+///
+/// The optional stylus axes are skipped when serializing if they are not present,
+/// to not bloat the files for strokes drawn with input devices that don't report them.
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename = "element")]
 pub struct Element {
@@ -13,6 +16,33 @@ pub struct Element {
     #[serde(rename = "pressure", with = "crate::serialize::f64_dp3")]
     /// The pen pressure. The valid range is [0.0, 1.0].
     pub pressure: f64,
+    #[serde(
+        rename = "tilt",
+        with = "crate::serialize::option_na_vector2_f64_dp3",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// The stylus tilt in x and y direction, when it was retrieved from the input.
+    ///
+    /// Each component is in the range [-1.0, 1.0], where 0.0 is upright.
+    pub tilt: Option<na::Vector2<f64>>,
+    #[serde(
+        rename = "rotation",
+        with = "crate::serialize::option_f64_dp3",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// The stylus rotation (azimuth) in radians, when it was retrieved from the input.
+    ///
+    /// The valid range is [0.0, 2π).
+    pub rotation: Option<f64>,
+    #[serde(
+        rename = "time",
+        with = "crate::serialize::option_f64_dp3",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// A monotonic timestamp in seconds, when it was retrieved from the input.
+    ///
+    /// The origin is arbitrary, only differences between timestamps are meaningful.
+    pub time: Option<f64>,
 }
 
 impl Default for Element {
@@ -30,11 +60,29 @@ impl Transformable for Element {
         let mut isometry = na::Isometry2::identity();
         isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
         self.pos = isometry.transform_point(&self.pos.into()).coords;
+        self.tilt = self
+            .tilt
+            .map(|tilt| na::Rotation2::new(angle).transform_vector(&tilt));
+        self.rotation = self
+            .rotation
+            .map(|rotation| (rotation + angle).rem_euclid(std::f64::consts::TAU));
     }
 
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.pos = self.pos.component_mul(&scale);
     }
+
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        self.pos = direction.flip_point(self.pos, center);
+        self.tilt = self.tilt.map(|tilt| tilt.component_mul(&direction.scale()));
+        self.rotation = self.rotation.map(|rotation| {
+            let flipped = match direction {
+                FlipDirection::Horizontal => std::f64::consts::PI - rotation,
+                FlipDirection::Vertical => -rotation,
+            };
+            flipped.rem_euclid(std::f64::consts::TAU)
+        });
+    }
 }
 
 impl Element {
@@ -46,9 +94,30 @@ impl Element {
         Self {
             pos,
             pressure: pressure.clamp(0.0, 1.0),
+            tilt: None,
+            rotation: None,
+            time: None,
         }
     }
 
+    /// Sets the stylus tilt, with components clamped to the range [-1.0, 1.0].
+    pub fn with_tilt(mut self, tilt: na::Vector2<f64>) -> Self {
+        self.tilt = Some(tilt.map(|c| c.clamp(-1.0, 1.0)));
+        self
+    }
+
+    /// Sets the stylus rotation in radians, wrapped into the range [0.0, 2π).
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = Some(rotation.rem_euclid(std::f64::consts::TAU));
+        self
+    }
+
+    /// Sets the monotonic timestamp in seconds.
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = Some(time);
+        self
+    }
+
     /// Sets the pressure, clamped to the range [0.0 - 1.0].
     pub fn set_pressure_clamped(&mut self, pressure: f64) {
         self.pressure = pressure.clamp(0.0, 1.0);
@@ -9,7 +9,7 @@ pub use segment::Segment;
 // Imports
 use crate::ext::{KurboShapeExt, Vector2Ext};
 use crate::shapes::{CubicBezier, Line, QuadraticBezier, Shapeable};
-use crate::transform::Transformable;
+use crate::transform::{FlipDirection, Transformable};
 use kurbo::Shape;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use serde::{Deserialize, Serialize};
@@ -98,6 +98,13 @@ impl Transformable for PenPath {
             segment.scale(scale);
         });
     }
+
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        self.start.flip(direction, center);
+        self.segments.iter_mut().for_each(|segment| {
+            segment.flip(direction, center);
+        });
+    }
 }
 
 impl PenPath {
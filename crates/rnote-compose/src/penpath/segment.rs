@@ -1,6 +1,6 @@
 // Imports
 use super::Element;
-use crate::transform::Transformable;
+use crate::transform::{FlipDirection, Transformable};
 use serde::{Deserialize, Serialize};
 
 /// A single segment, usually of a pen path.
@@ -93,6 +93,23 @@ impl Transformable for Segment {
             }
         }
     }
+
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        match self {
+            Self::LineTo { end } => {
+                end.flip(direction, center);
+            }
+            Self::QuadBezTo { cp, end } => {
+                *cp = direction.flip_point(*cp, center);
+                end.flip(direction, center);
+            }
+            Self::CubBezTo { cp1, cp2, end } => {
+                *cp1 = direction.flip_point(*cp1, center);
+                *cp2 = direction.flip_point(*cp2, center);
+                end.flip(direction, center);
+            }
+        }
+    }
 }
 
 impl Segment {
@@ -36,6 +36,42 @@ pub mod na_vector2_f64_dp3 {
     }
 }
 
+/// (De)Serialize a [`Option<f64>`] rounded to 3 decimal places
+pub mod option_f64_dp3 {
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    /// Serialize a [`Option<f64>`] rounded to 3 decimal places
+    pub fn serialize<S: Serializer>(v: &Option<f64>, s: S) -> Result<S::Ok, S::Error> {
+        const D: f64 = (10_u32.pow(3)) as f64;
+        v.map(|v| (v * D).round() / D).serialize(s)
+    }
+
+    /// Deserialize a [`Option<f64>`]
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f64>, D::Error> {
+        Option::<f64>::deserialize(d)
+    }
+}
+
+/// (De)Serialize a [`Option<na::Vector2<f64>>`] rounded to 3 decimal places
+pub mod option_na_vector2_f64_dp3 {
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    /// Serialize a [`Option<na::Vector2<f64>>`] rounded to 3 decimal places
+    pub fn serialize<S: Serializer>(v: &Option<na::Vector2<f64>>, s: S) -> Result<S::Ok, S::Error> {
+        const D: f64 = (10_u32.pow(3)) as f64;
+        v.map(|v| v.map(|c| (c * D).round() / D)).serialize(s)
+    }
+
+    /// Deserialize a [`Option<na::Vector2<f64>>`]
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<na::Vector2<f64>>, D::Error> {
+        Option::<na::Vector2<f64>>::deserialize(d)
+    }
+}
+
 /// (De)Serialize a [`na::Affine2<f64>`] rounded to 3 decimal places
 pub mod na_affine2_f64_dp3 {
     use serde::{Deserialize, Serialize};
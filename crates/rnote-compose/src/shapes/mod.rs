@@ -13,6 +13,8 @@ pub mod polygon;
 pub mod polyline;
 /// Polyline
 pub mod quadbez;
+/// Shape recognition of hand-drawn paths
+pub mod recognition;
 /// Rectangle
 pub mod rectangle;
 /// Shape
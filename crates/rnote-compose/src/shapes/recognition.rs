@@ -0,0 +1,201 @@
+// Imports
+use super::{Arrow, Ellipse, Line, Polygon, Rectangle, Shape};
+use crate::ext::Vector2Ext;
+use crate::transform::Transform;
+use std::f64::consts::PI;
+
+/// The tolerance of the fits relative to the diagonal of the bounds of the points.
+const RELATIVE_TOLERANCE: f64 = 0.08;
+/// The minimum absolute tolerance of the fits.
+const MIN_TOLERANCE: f64 = 1.0;
+/// Points are considered a closed path when the distance between start and end is less than this,
+/// relative to the diagonal of the bounds.
+const CLOSED_RELATIVE_DISTANCE: f64 = 0.2;
+/// The maximum deviation from a right angle for a corner of a rectangle.
+const RIGHT_ANGLE_TOLERANCE: f64 = PI / 10.0;
+/// The maximum length of the arrow head strokes relative to the stem.
+const ARROW_HEAD_RELATIVE_LEN: f64 = 0.5;
+
+/// Recognizes a line, rectangle, ellipse, triangle or arrow in the given hand-drawn points.
+///
+/// Returns None if none of the candidates fit the points well enough.
+pub fn recognize_shape(points: &[na::Vector2<f64>]) -> Option<Shape> {
+    let (first, last) = (*points.first()?, *points.last()?);
+    let (mins, maxs) = points.iter().fold((first, first), |(mins, maxs), p| {
+        (mins.mins(p), maxs.maxs(p))
+    });
+    let diagonal = (maxs - mins).magnitude();
+    if points.len() < 3 || diagonal < MIN_TOLERANCE * 4.0 {
+        return None;
+    }
+    let tolerance = (diagonal * RELATIVE_TOLERANCE).max(MIN_TOLERANCE);
+
+    if (last - first).magnitude() < diagonal * CLOSED_RELATIVE_DISTANCE {
+        let corners = polygon_corners(points, tolerance);
+        match corners.len() {
+            3 => Some(Shape::Polygon(Polygon {
+                start: corners[0],
+                path: corners[1..].to_vec(),
+            })),
+            4 => recognize_rectangle(&corners).map(Shape::Rectangle),
+            _ => None,
+        }
+        .or_else(|| recognize_ellipse(points, tolerance).map(Shape::Ellipse))
+    } else {
+        let corners = simplify(points, tolerance);
+        match corners.len() {
+            2 => Some(Shape::Line(Line {
+                start: first,
+                end: last,
+            })),
+            _ => recognize_arrow(&corners).map(Shape::Arrow),
+        }
+    }
+}
+
+/// Recognizes an arrow drawn as the stem followed by the strokes of the head, which all end up close to the tip.
+fn recognize_arrow(corners: &[na::Vector2<f64>]) -> Option<Arrow> {
+    let (start, tip) = (*corners.first()?, *corners.get(1)?);
+    let stem = tip - start;
+    let stem_len = stem.magnitude();
+    let head = &corners[2..];
+    if head.is_empty()
+        || head
+            .iter()
+            .any(|p| (p - tip).magnitude() > stem_len * ARROW_HEAD_RELATIVE_LEN)
+    {
+        return None;
+    }
+    // The head strokes point back from the tip, to at least one side of the stem
+    let points_back = head.iter().any(|p| {
+        let v = p - tip;
+        v.magnitude() > 0.0 && v.angle(&-stem) < PI / 2.0
+    });
+    points_back.then(|| Arrow::new(start, tip))
+}
+
+/// Recognizes a rectangle from four corners that enclose roughly right angles.
+fn recognize_rectangle(corners: &[na::Vector2<f64>]) -> Option<Rectangle> {
+    let n = corners.len();
+    let right_angles = (0..n).all(|i| {
+        let prev = corners[(i + n - 1) % n] - corners[i];
+        let next = corners[(i + 1) % n] - corners[i];
+        (prev.angle(&next) - PI / 2.0).abs() < RIGHT_ANGLE_TOLERANCE
+    });
+    if !right_angles {
+        return None;
+    }
+
+    let center = corners.iter().sum::<na::Vector2<f64>>() / n as f64;
+    let width =
+        ((corners[1] - corners[0]).magnitude() + (corners[3] - corners[2]).magnitude()) * 0.5;
+    let height =
+        ((corners[2] - corners[1]).magnitude() + (corners[0] - corners[3]).magnitude()) * 0.5;
+    let angle = na::Vector2::x().angle_ahead(&(corners[1] - corners[0]));
+
+    Some(Rectangle {
+        cuboid: p2d::shape::Cuboid::new(na::vector![width * 0.5, height * 0.5]),
+        transform: Transform::new_w_isometry(na::Isometry2::new(center, angle)),
+    })
+}
+
+/// Recognizes an ellipse by fitting it to the principal axes of the points.
+fn recognize_ellipse(points: &[na::Vector2<f64>], tolerance: f64) -> Option<Ellipse> {
+    let n = points.len() as f64;
+    let center = points.iter().sum::<na::Vector2<f64>>() / n;
+    let covariance = points.iter().fold(na::Matrix2::<f64>::zeros(), |acc, p| {
+        let d = p - center;
+        acc + d * d.transpose()
+    }) / n;
+    let eigen = covariance.symmetric_eigen();
+    let axis = eigen.eigenvectors.column(0).into_owned();
+    let angle = na::Vector2::x().angle_ahead(&axis);
+    // The variance of a point uniformly distributed on the outline is half of the squared radius
+    let radii = eigen.eigenvalues.map(|v| (2.0 * v.max(0.0)).sqrt());
+    if radii.min() < tolerance * 0.5 {
+        return None;
+    }
+
+    let rotation = na::Rotation2::new(-angle);
+    let mean_error = points
+        .iter()
+        .map(|p| {
+            let local = rotation * (p - center);
+            let r = (local[0] / radii[0]).hypot(local[1] / radii[1]);
+            (r - 1.0).abs() * radii.min()
+        })
+        .sum::<f64>()
+        / n;
+    if mean_error > tolerance * 0.5 {
+        return None;
+    }
+
+    Some(Ellipse {
+        radii,
+        transform: Transform::new_w_isometry(na::Isometry2::new(center, angle)),
+    })
+}
+
+/// The corners of a closed path of points.
+///
+/// Corners that are only there because the path started in the middle of an edge are removed.
+fn polygon_corners(points: &[na::Vector2<f64>], tolerance: f64) -> Vec<na::Vector2<f64>> {
+    // The path is split at the point farthest from the start,
+    // because the line between the start and the end that almost coincide has no meaningful direction.
+    let Some(farthest) = (0..points.len()).max_by(|&a, &b| {
+        (points[a] - points[0])
+            .magnitude()
+            .total_cmp(&(points[b] - points[0]).magnitude())
+    }) else {
+        return vec![];
+    };
+    let mut corners = simplify(&points[..=farthest], tolerance);
+    corners.pop();
+    corners.extend(simplify(&points[farthest..], tolerance));
+    // The last corner closes the path, so it coincides with the first
+    corners.pop();
+
+    let mut i = 0;
+    while corners.len() > 3 && i < corners.len() {
+        let n = corners.len();
+        let (prev, next) = (corners[(i + n - 1) % n], corners[(i + 1) % n]);
+        if distance_to_line(corners[i], prev, next) < tolerance {
+            corners.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    corners
+}
+
+/// Simplifies the points into corners with the Ramer-Douglas-Peucker algorithm.
+fn simplify(points: &[na::Vector2<f64>], tolerance: f64) -> Vec<na::Vector2<f64>> {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let farthest = points
+        .iter()
+        .enumerate()
+        .skip(1)
+        .take(points.len().saturating_sub(2))
+        .map(|(i, &p)| (i, distance_to_line(p, first, last)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    match farthest {
+        Some((i, dist)) if dist > tolerance => {
+            let mut corners = simplify(&points[..=i], tolerance);
+            corners.pop();
+            corners.extend(simplify(&points[i..], tolerance));
+            corners
+        }
+        _ => vec![first, last],
+    }
+}
+
+/// The distance of the point to the line through `a` and `b`, or to `a` if they coincide.
+fn distance_to_line(p: na::Vector2<f64>, a: na::Vector2<f64>, b: na::Vector2<f64>) -> f64 {
+    let ab = b - a;
+    let len = ab.magnitude();
+    if len == 0.0 {
+        return (p - a).magnitude();
+    }
+    ab.perp(&(p - a)).abs() / len
+}
@@ -2,8 +2,10 @@
 use super::{
     Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
 };
+use crate::ext::Vector2Ext;
 use crate::transform::Transformable;
-use p2d::bounding_volume::Aabb;
+use kurbo::{ParamCurve, ParamCurveArclen, PathSeg};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use serde::{Deserialize, Serialize};
 
 /// Shape, storing shape variants.
@@ -171,3 +173,189 @@ impl Shapeable for Shape {
         }
     }
 }
+
+impl Shape {
+    /// The points that define the shape, like its end points, corners, centers and curve control points.
+    pub fn control_points(&self) -> Vec<na::Vector2<f64>> {
+        match self {
+            Self::Arrow(arrow) => vec![arrow.start, arrow.tip],
+            Self::Line(line) => vec![line.start, line.end],
+            Self::Rectangle(rectangle) => {
+                let half_extents = rectangle.cuboid.half_extents;
+                [
+                    na::point![0.0, 0.0],
+                    na::point![-half_extents[0], -half_extents[1]],
+                    na::point![half_extents[0], -half_extents[1]],
+                    na::point![half_extents[0], half_extents[1]],
+                    na::point![-half_extents[0], half_extents[1]],
+                ]
+                .into_iter()
+                .map(|point| rectangle.transform.transform_point(point).coords)
+                .collect()
+            }
+            Self::Ellipse(ellipse) => {
+                let radii = ellipse.radii;
+                [
+                    na::point![0.0, 0.0],
+                    na::point![radii[0], 0.0],
+                    na::point![0.0, radii[1]],
+                    na::point![-radii[0], 0.0],
+                    na::point![0.0, -radii[1]],
+                ]
+                .into_iter()
+                .map(|point| ellipse.transform.transform_point(point).coords)
+                .collect()
+            }
+            Self::QuadraticBezier(quadbez) => vec![quadbez.start, quadbez.cp, quadbez.end],
+            Self::CubicBezier(cubbez) => vec![cubbez.start, cubbez.cp1, cubbez.cp2, cubbez.end],
+            Self::Polyline(polyline) => std::iter::once(polyline.start)
+                .chain(polyline.path.iter().copied())
+                .collect(),
+            Self::Polygon(polygon) => std::iter::once(polygon.start)
+                .chain(polygon.path.iter().copied())
+                .collect(),
+        }
+    }
+
+    /// Splits the outline of the shape by removing the parts that collide with the given bounds.
+    ///
+    /// `loosened` loosens the outline by the value, usually half of the stroke width.
+    ///
+    /// Returns None if the outline does not collide with the bounds,
+    /// else the remaining parts as new shapes, which might be empty if the entire outline collides.
+    /// Lines and polylines are split into lines and polylines, curves into partial curves.
+    pub fn split_by_bounds(&self, bounds: &Aabb, loosened: f64) -> Option<Vec<Shape>> {
+        let hit = bounds.loosened(loosened);
+        if !self.bounds().intersects(&hit) {
+            return None;
+        }
+        let is_inside = |seg: &PathSeg, t: f64| {
+            hit.contains_local_point(&na::Vector2::<f64>::from_kurbo_point(seg.eval(t)).into())
+        };
+        // Samples at least twice across the smallest extent of the bounds, so that they can't be skipped.
+        let sample_len = (hit.extents().min() * 0.5).clamp(0.1, 5.0);
+
+        let segs = self.outline_path().segments().collect::<Vec<PathSeg>>();
+        let mut collides = false;
+        // The kept parts of the outline segments as (segment index, parameter range)
+        let mut kept = vec![];
+        for (i, seg) in segs.iter().enumerate() {
+            let n_samples = ((seg.arclen(0.25) / sample_len).ceil() as usize).clamp(1, 10_000);
+            let mut kept_start = None;
+            let mut prev_t = 0.0;
+            for j in 0..=n_samples {
+                let t = j as f64 / n_samples as f64;
+                let inside = is_inside(seg, t);
+                match (inside, kept_start) {
+                    (false, None) => {
+                        kept_start = Some(if j == 0 {
+                            0.0
+                        } else {
+                            bisect_boundary(prev_t, t, |t| is_inside(seg, t))
+                        });
+                    }
+                    (true, Some(start)) => {
+                        let end = bisect_boundary(prev_t, t, |t| is_inside(seg, t));
+                        kept.push((i, start..end));
+                        kept_start = None;
+                    }
+                    _ => {}
+                }
+                collides |= inside;
+                prev_t = t;
+            }
+            if let Some(start) = kept_start {
+                kept.push((i, start..1.0));
+            }
+        }
+        if !collides {
+            return None;
+        }
+
+        // Join the kept parts that continue each other into runs
+        let connects = |a: &(usize, std::ops::Range<f64>), b: &(usize, std::ops::Range<f64>)| {
+            a.1.end == 1.0 && b.1.start == 0.0 && segs[a.0].end() == segs[b.0].start()
+        };
+        let mut runs: Vec<Vec<PathSeg>> = vec![];
+        let mut prev_part: Option<&(usize, std::ops::Range<f64>)> = None;
+        for part in kept.iter() {
+            let subseg = segs[part.0].subsegment(part.1.clone());
+            match (prev_part, runs.last_mut()) {
+                (Some(prev_part), Some(run)) if connects(prev_part, part) => run.push(subseg),
+                _ => runs.push(vec![subseg]),
+            }
+            prev_part = Some(part);
+        }
+        // Closed outlines can wrap around, then the last run continues into the first
+        if runs.len() > 1
+            && let (Some(first), Some(last)) = (kept.first(), kept.last())
+            && connects(last, first)
+        {
+            let mut last_run = runs.pop().unwrap();
+            last_run.append(&mut runs[0]);
+            runs[0] = last_run;
+        }
+
+        Some(
+            runs.into_iter()
+                .flat_map(shapes_from_path_segments)
+                .collect(),
+        )
+    }
+}
+
+/// Finds the boundary between the parameters `a` and `b` through bisection, where exactly one of them is inside.
+///
+/// Returns the parameter next to the boundary that is outside.
+fn bisect_boundary(a: f64, b: f64, is_inside: impl Fn(f64) -> bool) -> f64 {
+    const N_ITERATIONS: usize = 16;
+    let (mut outside, mut inside) = if is_inside(a) { (b, a) } else { (a, b) };
+    for _ in 0..N_ITERATIONS {
+        let mid = (outside + inside) * 0.5;
+        if is_inside(mid) {
+            inside = mid;
+        } else {
+            outside = mid;
+        }
+    }
+    outside
+}
+
+/// Converts a continuous run of path segments into shapes.
+///
+/// Runs of only lines become a polyline, else every segment becomes its own shape.
+fn shapes_from_path_segments(segs: Vec<PathSeg>) -> Vec<Shape> {
+    // Parts that are shorter are dropped.
+    const MIN_PART_LEN: f64 = 0.01;
+    let segs = segs
+        .into_iter()
+        .filter(|seg| seg.arclen(0.25) >= MIN_PART_LEN)
+        .collect::<Vec<PathSeg>>();
+    let to_vec = na::Vector2::<f64>::from_kurbo_point;
+
+    if segs.len() > 1 && segs.iter().all(|seg| matches!(seg, PathSeg::Line(_))) {
+        return vec![Shape::Polyline(Polyline {
+            start: to_vec(segs[0].start()),
+            path: segs.iter().map(|seg| to_vec(seg.end())).collect(),
+        })];
+    }
+    segs.into_iter()
+        .map(|seg| match seg {
+            PathSeg::Line(line) => Shape::Line(Line {
+                start: to_vec(line.p0),
+                end: to_vec(line.p1),
+            }),
+            PathSeg::Quad(quad) => Shape::QuadraticBezier(QuadraticBezier {
+                start: to_vec(quad.p0),
+                cp: to_vec(quad.p1),
+                end: to_vec(quad.p2),
+            }),
+            PathSeg::Cubic(cubic) => Shape::CubicBezier(CubicBezier {
+                start: to_vec(cubic.p0),
+                cp1: to_vec(cubic.p1),
+                cp2: to_vec(cubic.p2),
+                end: to_vec(cubic.p3),
+            }),
+        })
+        .collect()
+}
@@ -16,6 +16,7 @@ use self::smooth::SmoothOptions;
 use self::textured::TexturedOptions;
 
 // Imports
+use crate::penpath::Element;
 use crate::shapes::{
     Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
 };
@@ -367,3 +368,63 @@ impl TryFrom<u32> for PressureCurve {
             .with_context(|| format!("PressureCurve try_from::<u32>() for value {value} failed"))
     }
 }
+
+/// Modulates the width of pen paths with the stylus tilt and the drawing velocity.
+///
+/// Only has an effect on elements that carry the respective stylus axes or timestamps.
+/// The width is only ever thinned, so the composed bounds are not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "stroke_dynamics")]
+pub struct StrokeDynamics {
+    /// How much the width depends on the drawing direction relative to the direction the stylus is tilted towards,
+    /// similar to a calligraphy nib. The valid range is [0.0, 1.0].
+    #[serde(rename = "tilt_sensitivity", with = "crate::serialize::f64_dp3")]
+    pub tilt_sensitivity: f64,
+    /// How much faster drawing thins the width. The valid range is [0.0, 1.0].
+    #[serde(rename = "velocity_sensitivity", with = "crate::serialize::f64_dp3")]
+    pub velocity_sensitivity: f64,
+}
+
+impl StrokeDynamics {
+    /// Minimum sensitivity.
+    pub const SENSITIVITY_MIN: f64 = 0.0;
+    /// Maximum sensitivity.
+    pub const SENSITIVITY_MAX: f64 = 1.0;
+    /// The factor the width is never thinned below.
+    const WIDTH_FACTOR_MIN: f64 = 0.2;
+    /// The velocity in coordinate units per second at which the width is halved with full velocity sensitivity.
+    const VELOCITY_HALF_WIDTH: f64 = 1000.0;
+
+    /// Apply the dynamics to the width at `element`, which is drawn coming from `prev`.
+    pub fn apply(&self, width: f64, prev: &Element, element: &Element) -> f64 {
+        let delta = element.pos - prev.pos;
+        let dist = delta.magnitude();
+        if dist <= 0.0 {
+            return width;
+        }
+        let mut factor = 1.0;
+
+        if self.tilt_sensitivity > 0.0
+            && let Some(tilt) = element.tilt
+        {
+            let tilt_magnitude = tilt.magnitude();
+            if tilt_magnitude > 0.0 {
+                // Drawing perpendicular to the tilt direction results in the full width,
+                // drawing along it thins the width, the more the stylus is tilted.
+                let perpendicularity = (tilt.perp(&delta) / (tilt_magnitude * dist)).abs();
+                factor *= 1.0
+                    - self.tilt_sensitivity * tilt_magnitude.min(1.0) * (1.0 - perpendicularity);
+            }
+        }
+
+        if self.velocity_sensitivity > 0.0
+            && let (Some(prev_time), Some(time)) = (prev.time, element.time)
+            && time > prev_time
+        {
+            let velocity = dist / (time - prev_time);
+            factor /= 1.0 + self.velocity_sensitivity * velocity / Self::VELOCITY_HALF_WIDTH;
+        }
+
+        width * factor.max(Self::WIDTH_FACTOR_MIN)
+    }
+}
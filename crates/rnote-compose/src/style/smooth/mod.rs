@@ -8,7 +8,7 @@ pub use smoothoptions::{LineCap, LineStyle, SmoothOptions};
 use super::Composer;
 use crate::PenPath;
 use crate::ext::Vector2Ext;
-use crate::penpath::{self, Segment};
+use crate::penpath::{self, Element, Segment};
 use crate::shapes::{
     Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
 };
@@ -254,6 +254,7 @@ impl Composer<SmoothOptions> for PenPath {
         let mut full_path = kurbo::BezPath::new();
         let mut single_pos = true;
         let mut prev = self.start;
+        let mut prev_width = None;
 
         cx.save().unwrap();
 
@@ -267,14 +268,8 @@ impl Composer<SmoothOptions> for PenPath {
             let bez_path = {
                 match seg {
                     Segment::LineTo { end } => {
-                        let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
-                        );
+                        let (width_start, width_end) =
+                            segment_widths(&prev, end, &mut prev_width, options);
 
                         let bez_path = compose_lines_variable_width(
                             &[Line {
@@ -290,14 +285,8 @@ impl Composer<SmoothOptions> for PenPath {
                         bez_path
                     }
                     Segment::QuadBezTo { cp, end } => {
-                        let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
-                        );
+                        let (width_start, width_end) =
+                            segment_widths(&prev, end, &mut prev_width, options);
 
                         let quadbez = QuadraticBezier {
                             start: prev.pos,
@@ -316,14 +305,8 @@ impl Composer<SmoothOptions> for PenPath {
                         bez_path
                     }
                     Segment::CubBezTo { cp1, cp2, end } => {
-                        let (width_start, width_end) = (
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, prev.pressure),
-                            options
-                                .pressure_curve
-                                .apply(options.stroke_width, end.pressure),
-                        );
+                        let (width_start, width_end) =
+                            segment_widths(&prev, end, &mut prev_width, options);
 
                         let cubbez = CubicBezier {
                             start: prev.pos,
@@ -369,6 +352,31 @@ impl Composer<SmoothOptions> for PenPath {
     }
 }
 
+/// The start and end width of a pen path segment from `prev` to `end`.
+///
+/// The end width is remembered in `prev_width`, so that the widths of adjacent segments line up
+/// even when the dynamics depend on the drawing direction.
+fn segment_widths(
+    prev: &Element,
+    end: &Element,
+    prev_width: &mut Option<f64>,
+    options: &SmoothOptions,
+) -> (f64, f64) {
+    let element_width = |element: &Element| {
+        options.dynamics.apply(
+            options
+                .pressure_curve
+                .apply(options.stroke_width, element.pressure),
+            prev,
+            end,
+        )
+    };
+    let width_start = prev_width.unwrap_or_else(|| element_width(prev));
+    let width_end = element_width(end);
+    *prev_width = Some(width_end);
+    (width_start, width_end)
+}
+
 impl Composer<SmoothOptions> for crate::Shape {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
         match self {
@@ -60,9 +60,13 @@ impl Composer<TexturedOptions> for PenPath {
 
                     let mut options = options.clone();
 
-                    options.stroke_width = options
-                        .pressure_curve
-                        .apply(options.stroke_width, (prev.pressure + end.pressure) * 0.5);
+                    options.stroke_width = options.dynamics.apply(
+                        options
+                            .pressure_curve
+                            .apply(options.stroke_width, (prev.pressure + end.pressure) * 0.5),
+                        &prev,
+                        end,
+                    );
 
                     let bez_path = compose_textured_line_path(&line, &options);
                     prev = *end;
@@ -76,9 +80,13 @@ impl Composer<TexturedOptions> for PenPath {
 
                     let mut options = options.clone();
 
-                    options.stroke_width = options
-                        .pressure_curve
-                        .apply(options.stroke_width, (prev.pressure + end.pressure) * 0.5);
+                    options.stroke_width = options.dynamics.apply(
+                        options
+                            .pressure_curve
+                            .apply(options.stroke_width, (prev.pressure + end.pressure) * 0.5),
+                        &prev,
+                        end,
+                    );
 
                     let bez_path = compose_textured_line_path(&line, &options);
                     prev = *end;
@@ -92,9 +100,13 @@ impl Composer<TexturedOptions> for PenPath {
 
                     let mut options = options.clone();
 
-                    options.stroke_width = options
-                        .pressure_curve
-                        .apply(options.stroke_width, (prev.pressure + end.pressure) * 0.5);
+                    options.stroke_width = options.dynamics.apply(
+                        options
+                            .pressure_curve
+                            .apply(options.stroke_width, (prev.pressure + end.pressure) * 0.5),
+                        &prev,
+                        end,
+                    );
 
                     let bez_path = compose_textured_line_path(&line, &options);
                     prev = *end;
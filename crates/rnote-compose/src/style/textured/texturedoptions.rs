@@ -1,7 +1,7 @@
 // Imports
 use super::textureddotsdistribution::TexturedDotsDistribution;
 use crate::Color;
-use crate::style::PressureCurve;
+use crate::style::{PressureCurve, StrokeDynamics};
 use serde::{Deserialize, Serialize};
 
 /// Options for shapes that can be drawn in a textured style.
@@ -26,6 +26,9 @@ pub struct TexturedOptions {
     /// Pressure curve.
     #[serde(rename = "pressure_curve")]
     pub pressure_curve: PressureCurve,
+    /// Width dynamics from the stylus tilt and the drawing velocity.
+    #[serde(rename = "dynamics")]
+    pub dynamics: StrokeDynamics,
 }
 
 impl Default for TexturedOptions {
@@ -37,6 +40,7 @@ impl Default for TexturedOptions {
             stroke_color: Some(Color::BLACK),
             distribution: TexturedDotsDistribution::default(),
             pressure_curve: PressureCurve::default(),
+            dynamics: StrokeDynamics::default(),
         }
     }
 }
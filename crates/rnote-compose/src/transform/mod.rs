@@ -2,7 +2,7 @@
 mod transformable;
 
 // Re-exports
-pub use transformable::Transformable;
+pub use transformable::{FlipDirection, Transformable};
 
 // Imports
 use crate::ext::{AabbExt, Affine2Ext};
@@ -1,3 +1,27 @@
+/// The direction in which something is flipped (as in mirrored).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipDirection {
+    /// Mirror along the vertical axis, swapping left and right.
+    Horizontal,
+    /// Mirror along the horizontal axis, swapping top and bottom.
+    Vertical,
+}
+
+impl FlipDirection {
+    /// The scale-factor that mirrors in this direction.
+    pub fn scale(&self) -> na::Vector2<f64> {
+        match self {
+            Self::Horizontal => na::vector![-1.0, 1.0],
+            Self::Vertical => na::vector![1.0, -1.0],
+        }
+    }
+
+    /// Mirror the point along the axis through the center.
+    pub fn flip_point(&self, point: na::Vector2<f64>, center: na::Point2<f64>) -> na::Vector2<f64> {
+        (point - center.coords).component_mul(&self.scale()) + center.coords
+    }
+}
+
 /// Trait for types that can be (geometrically) transformed.
 pub trait Transformable {
     /// Translate (as in moves) by the given offset.
@@ -6,4 +30,13 @@ pub trait Transformable {
     fn rotate(&mut self, angle: f64, center: na::Point2<f64>);
     /// Scale by the given scale-factor.
     fn scale(&mut self, scale: na::Vector2<f64>);
+    /// Flip (as in mirror) in the given direction along the axis through the center.
+    ///
+    /// The default implementation scales with a negative scale-factor,
+    /// types that don't scale purely geometrically (e.g. stroke widths) must override it.
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        self.translate(-center.coords);
+        self.scale(direction.scale());
+        self.translate(center.coords);
+    }
 }
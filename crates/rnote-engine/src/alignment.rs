@@ -0,0 +1,87 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+
+/// The edge or center that bounds are aligned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    /// Align the left edges
+    Left,
+    /// Align the horizontal centers
+    HorizontalCenter,
+    /// Align the right edges
+    Right,
+    /// Align the top edges
+    Top,
+    /// Align the vertical centers
+    VerticalCenter,
+    /// Align the bottom edges
+    Bottom,
+}
+
+impl Alignment {
+    /// The offset that aligns the bounds to the reference bounds.
+    pub fn offset(&self, bounds: Aabb, reference: Aabb) -> na::Vector2<f64> {
+        match self {
+            Self::Left => na::vector![reference.mins[0] - bounds.mins[0], 0.0],
+            Self::HorizontalCenter => na::vector![reference.center()[0] - bounds.center()[0], 0.0],
+            Self::Right => na::vector![reference.maxs[0] - bounds.maxs[0], 0.0],
+            Self::Top => na::vector![0.0, reference.mins[1] - bounds.mins[1]],
+            Self::VerticalCenter => na::vector![0.0, reference.center()[1] - bounds.center()[1]],
+            Self::Bottom => na::vector![0.0, reference.maxs[1] - bounds.maxs[1]],
+        }
+    }
+}
+
+/// What strokes are aligned relative to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlignmentReference {
+    #[default]
+    /// The bounds of the entire selection
+    Selection,
+    /// The page the selection is on
+    Page,
+    /// The stroke that was selected first
+    FirstSelected,
+}
+
+/// The axis along which strokes are distributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /// Equal horizontal gaps between the strokes
+    Horizontal,
+    /// Equal vertical gaps between the strokes
+    Vertical,
+}
+
+impl Distribution {
+    /// The offsets that distribute the bounds with equal gaps between them, in the same order as the bounds.
+    ///
+    /// The first bounds and the total span stay in place.
+    pub fn offsets(&self, bounds: &[Aabb]) -> Vec<na::Vector2<f64>> {
+        let axis = match self {
+            Self::Horizontal => 0,
+            Self::Vertical => 1,
+        };
+        let mut offsets = vec![na::Vector2::<f64>::zeros(); bounds.len()];
+        if bounds.len() < 3 {
+            return offsets;
+        }
+        let mut order = (0..bounds.len()).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b| bounds[a].mins[axis].total_cmp(&bounds[b].mins[axis]));
+
+        let start = bounds[order[0]].mins[axis];
+        let end = order
+            .iter()
+            .map(|&i| bounds[i].maxs[axis])
+            .fold(f64::MIN, f64::max);
+        let extents_sum = bounds.iter().map(|b| b.extents()[axis]).sum::<f64>();
+        let gap = (end - start - extents_sum) / (bounds.len() - 1) as f64;
+
+        let mut pos = start;
+        for i in order {
+            offsets[i][axis] = pos - bounds[i].mins[axis];
+            pos += bounds[i].extents()[axis] + gap;
+        }
+        offsets
+    }
+}
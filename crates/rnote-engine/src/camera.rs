@@ -6,6 +6,7 @@ use crate::tasks::{OneOffTaskError, OneOffTaskHandle};
 use crate::{Document, WidgetFlags};
 use p2d::bounding_volume::Aabb;
 use rnote_compose::ext::AabbExt;
+use rnote_compose::transform::Transform;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::error;
@@ -37,6 +38,9 @@ pub struct Camera {
     /// The temporary zoom. Is used to overlay the "permanent" zoom.
     #[serde(skip)]
     temporary_zoom: f64,
+    /// The rotation of the view in radians, around the center of the surface.
+    #[serde(rename = "rotation")]
+    rotation: f64,
 
     /// The scale factor of the surface, usually 1.0 or 2.0 for high-dpi screens.
     ///
@@ -55,6 +59,7 @@ impl Default for Camera {
             size: na::vector![800.0, 600.0],
             zoom: 1.0,
             temporary_zoom: 1.0,
+            rotation: 0.0,
             scale_factor: 1.0,
             zoom_task_handle: None,
         }
@@ -67,6 +72,7 @@ impl Snapshotable for Camera {
             offset: self.offset,
             size: self.size,
             zoom: self.zoom,
+            rotation: self.rotation,
             ..Default::default()
         }
     }
@@ -97,35 +103,53 @@ impl Camera {
         self
     }
 
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation.rem_euclid(std::f64::consts::TAU);
+        self
+    }
+
     /// The current viewport offset in surface coordinate space.
     pub fn offset(&self) -> na::Vector2<f64> {
         self.offset
     }
 
+    /// Set the viewport offset.
+    ///
+    /// The viewport center is clamped so that the viewport, rotated with the view, stays inside the surface bounds.
     pub fn set_offset(&mut self, offset: na::Vector2<f64>, doc: &Document) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
         let (mins, maxs) = self.surface_mins_maxs(doc);
-        let offset_maxs = na::vector![
-            (maxs.x - self.size.x).max(mins.x),
-            (maxs.y - self.size.y).max(mins.y)
-        ];
+        let half_size = self.size * 0.5;
+        let center = offset + half_size;
+        let center_mins = mins + half_size;
+        let center_maxs = (maxs - half_size).sup(&center_mins);
 
-        self.offset = na::vector![
-            offset.x.clamp(mins.x, offset_maxs.x),
-            offset.y.clamp(mins.y, offset_maxs.y)
-        ];
+        self.offset = center.sup(&center_mins).inf(&center_maxs) - half_size;
 
         widget_flags.view_modified = true;
         widget_flags
     }
 
+    /// The extents of the viewport rotated with the view, in unrotated surface coordinate space.
+    pub fn rotated_viewport_extents(&self) -> na::Vector2<f64> {
+        let (sin, cos) = self.rotation.sin_cos();
+        na::vector![
+            cos.abs() * self.size[0] + sin.abs() * self.size[1],
+            sin.abs() * self.size[0] + cos.abs() * self.size[1]
+        ]
+    }
+
     /// The minimum and maximum surface bounds (document including overshoot) in surface coordinate space.
+    ///
+    /// When the view is rotated, the bounds are adjusted by the difference between the size and the rotated extents
+    /// of the viewport, so that the unrotated viewport offset ranges over them while the rotated viewport
+    /// stays inside the document bounds.
     pub fn surface_mins_maxs(&self, doc: &Document) -> (na::Vector2<f64>, na::Vector2<f64>) {
         let total_zoom = self.total_zoom();
 
         let (h_lower, h_upper) = match doc.config.layout {
-            Layout::FixedSize | Layout::ContinuousVertical => (
+            Layout::FixedSize | Layout::ContinuousVertical | Layout::ContinuousHorizontal => (
                 doc.x * total_zoom - Self::OVERSHOOT_HORIZONTAL,
                 (doc.x + doc.width) * total_zoom + Self::OVERSHOOT_HORIZONTAL,
             ),
@@ -136,7 +160,7 @@ impl Camera {
             Layout::Infinite => (doc.x * total_zoom, (doc.x + doc.width) * total_zoom),
         };
         let (v_lower, v_upper) = match doc.config.layout {
-            Layout::FixedSize | Layout::ContinuousVertical => (
+            Layout::FixedSize | Layout::ContinuousVertical | Layout::ContinuousHorizontal => (
                 doc.y * total_zoom - Self::OVERSHOOT_VERTICAL,
                 (doc.y + doc.height) * total_zoom + Self::OVERSHOOT_VERTICAL,
             ),
@@ -147,7 +171,12 @@ impl Camera {
             Layout::Infinite => (doc.y * total_zoom, (doc.y + doc.height) * total_zoom),
         };
 
-        (na::vector![h_lower, v_lower], na::vector![h_upper, v_upper])
+        let rotation_pad = (self.size - self.rotated_viewport_extents()) * 0.5;
+
+        (
+            na::vector![h_lower, v_lower] - rotation_pad,
+            na::vector![h_upper, v_upper] + rotation_pad,
+        )
     }
 
     /// The current viewport size in surface coordinate space.
@@ -232,6 +261,33 @@ impl Camera {
         self.zoom * self.temporary_zoom
     }
 
+    /// The rotation of the view in radians, around the center of the surface.
+    pub fn rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    /// Set the rotation of the view.
+    pub fn rotate_to(&mut self, rotation: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.rotation = rotation.rem_euclid(std::f64::consts::TAU);
+        widget_flags.view_modified = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Rotate a vector in surface coordinates back by the view rotation,
+    /// into the unrotated surface coordinates the offset is in.
+    pub fn unrotate_surface_vec(&self, vec: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::Rotation2::new(-self.rotation) * vec
+    }
+
+    /// Rotate a position in surface coordinates back by the view rotation around the center of the surface,
+    /// into the unrotated surface coordinates the offset is in.
+    pub fn unrotate_surface_pos(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        let rotation_center = self.size * 0.5;
+        rotation_center + self.unrotate_surface_vec(pos - rotation_center)
+    }
+
     /// The scaling factor for generating bitmap images with the current permanent zoom.
     ///
     /// Takes the scale factor in account
@@ -254,13 +310,23 @@ impl Camera {
     }
 
     /// The viewport in document coordinate space.
+    ///
+    /// When the view is rotated, these are the bounds containing the rotated viewport rectangle.
     pub fn viewport(&self) -> Aabb {
-        let total_zoom = self.total_zoom();
+        self.transform_inv_bounds(Aabb::new_positive(na::point![0.0, 0.0], self.size.into()))
+    }
 
-        Aabb::new_positive(
-            (self.offset / total_zoom).into(),
-            ((self.offset + self.size) / total_zoom).into(),
-        )
+    /// The corners of the viewport rectangle in document coordinate space, clockwise starting with the top left corner
+    /// of the surface.
+    pub fn viewport_corners(&self) -> [na::Vector2<f64>; 4] {
+        let transform_inv = self.transform().inverse();
+        [
+            na::point![0.0, 0.0],
+            na::point![self.size[0], 0.0],
+            na::point![self.size[0], self.size[1]],
+            na::point![0.0, self.size[1]],
+        ]
+        .map(|corner| (transform_inv * corner).coords)
     }
 
     /// The current viewport center in document coordinate space.
@@ -279,13 +345,17 @@ impl Camera {
     }
 
     /// Transform Aabb from document coords to surface coords.
+    ///
+    /// When the view is rotated, the returned bounds contain the rotated bounds.
     pub fn transform_bounds(&self, bounds: Aabb) -> Aabb {
-        bounds.scale(self.total_zoom()).translate(-self.offset)
+        Transform::new(self.transform()).transform_aabb(bounds)
     }
 
     /// Transform Aabb from surface coords to document coords.
+    ///
+    /// When the view is rotated, the returned bounds contain the rotated bounds.
     pub fn transform_inv_bounds(&self, bounds: Aabb) -> Aabb {
-        bounds.translate(self.offset).scale(1.0 / self.total_zoom())
+        Transform::new(self.transform().inverse()).transform_aabb(bounds)
     }
 
     /// The transform from document coords to surface coords.
@@ -293,10 +363,14 @@ impl Camera {
     /// To get the inverse, call `.inverse()`.
     pub fn transform(&self) -> na::Affine2<f64> {
         let total_zoom = self.total_zoom();
+        let rotation_center = self.size * 0.5;
 
         na::try_convert(
             // LHS is applied onto RHS, so the order is scaling by zoom -> Translation by offset
-            na::Translation2::from(-self.offset).to_homogeneous()
+            // -> Rotation around the center of the surface
+            na::Translation2::from(rotation_center).to_homogeneous()
+                * na::Rotation2::new(self.rotation).to_homogeneous()
+                * na::Translation2::from(-rotation_center - self.offset).to_homogeneous()
                 * na::Scale2::from(na::Vector2::from_element(total_zoom)).to_homogeneous(),
         )
         .unwrap()
@@ -305,28 +379,37 @@ impl Camera {
     /// The gsk transform for the GTK snapshot function.
     ///
     /// GTKs transformations are applied on its coordinate system,
-    /// so we need to reverse the transformation order (translate, then scale).
+    /// so we need to reverse the transformation order (rotate, translate, then scale).
     /// To get the inverse, call .invert().
     #[cfg(feature = "ui")]
     pub fn transform_for_gtk_snapshot(&self) -> gtk4::gsk::Transform {
         let total_zoom = self.total_zoom();
+        let rotation_center = self.size * 0.5;
 
         gtk4::gsk::Transform::new()
             .translate(&gtk4::graphene::Point::new(
-                -self.offset[0] as f32,
-                -self.offset[1] as f32,
+                rotation_center[0] as f32,
+                rotation_center[1] as f32,
+            ))
+            .rotate(self.rotation.to_degrees() as f32)
+            .translate(&gtk4::graphene::Point::new(
+                (-rotation_center[0] - self.offset[0]) as f32,
+                (-rotation_center[1] - self.offset[1]) as f32,
             ))
             .scale(total_zoom as f32, total_zoom as f32)
     }
 
     /// Detects if a nudge is needed, meaning: the position is close to an edge of the current viewport.
+    ///
+    /// The directions are relative to the surface, so they follow the rotation of the view.
     pub fn detect_nudge_needed(&self, pos: na::Vector2<f64>) -> Option<NudgeDirection> {
         const NUDGE_VIEWPORT_DIST: f64 = 10.0;
-        let viewport = self.viewport();
-        let nudge_north = pos[1] <= viewport.mins[1] + NUDGE_VIEWPORT_DIST;
-        let nudge_east = pos[0] >= viewport.maxs[0] - NUDGE_VIEWPORT_DIST;
-        let nudge_south = pos[1] >= viewport.maxs[1] - NUDGE_VIEWPORT_DIST;
-        let nudge_west = pos[0] <= viewport.mins[0] + NUDGE_VIEWPORT_DIST;
+        let nudge_dist = NUDGE_VIEWPORT_DIST * self.total_zoom();
+        let surface_pos = (self.transform() * na::Point2::from(pos)).coords;
+        let nudge_north = surface_pos[1] <= nudge_dist;
+        let nudge_east = surface_pos[0] >= self.size[0] - nudge_dist;
+        let nudge_south = surface_pos[1] >= self.size[1] - nudge_dist;
+        let nudge_west = surface_pos[0] <= nudge_dist;
 
         match (nudge_north, nudge_east, nudge_south, nudge_west) {
             (true, false, _, false) => Some(NudgeDirection::North),
@@ -357,7 +440,7 @@ impl Camera {
             NudgeDirection::West => na::vector![-amount, 0.],
             NudgeDirection::NorthWest => na::vector![-amount, -amount],
         };
-        self.set_offset(self.offset() + nudge_offset, doc)
+        self.set_offset(self.offset() + self.unrotate_surface_vec(nudge_offset), doc)
     }
 
     pub fn nudge(&mut self, direction: NudgeDirection, doc: &Document) -> WidgetFlags {
@@ -376,7 +459,7 @@ impl Camera {
 
 #[cfg(test)]
 mod tests {
-    use crate::Camera;
+    use crate::{Camera, Document};
     use approx::assert_relative_eq;
 
     #[test]
@@ -413,4 +496,51 @@ mod tests {
         assert_relative_eq!(viewport.mins, mins);
         assert_relative_eq!(viewport.maxs, maxs);
     }
+
+    #[test]
+    fn rotated_viewport() {
+        let zoom = 2.0;
+        let offset = na::vector![10.0, 10.0];
+        let size = na::vector![20.0, 40.0];
+        let camera = Camera::default()
+            .with_zoom(zoom)
+            .with_offset(offset)
+            .with_size(size)
+            .with_rotation(std::f64::consts::FRAC_PI_2);
+
+        // The rotation is around the viewport center, so it stays in place
+        let center = (offset + size * 0.5) / zoom;
+        assert_relative_eq!(
+            camera.transform().transform_point(&center.into()).coords,
+            size * 0.5
+        );
+
+        // Rotated by a quarter turn, the viewport extents are swapped
+        let viewport = camera.viewport();
+        assert_relative_eq!(viewport.center().coords, center);
+        assert_relative_eq!(viewport.extents(), na::vector![size[1], size[0]] / zoom);
+    }
+
+    #[test]
+    fn rotated_offset_clamped() {
+        let doc = Document::default();
+        let size = na::vector![400.0, 100.0];
+        let mut camera = Camera::default()
+            .with_size(size)
+            .with_rotation(std::f64::consts::FRAC_PI_2);
+        let (mins, maxs) = camera.surface_mins_maxs(&doc);
+        // Undo the adjustment for the rotation, which swaps the viewport extents for a quarter turn
+        let rotation_pad = (size - size.yx()) * 0.5;
+        let (mins_unrotated, maxs_unrotated) = (mins + rotation_pad, maxs - rotation_pad);
+
+        // The rotated viewport stays inside the document bounds including the overshoot
+        for offset in [na::vector![-1.0e6, -1.0e6], na::vector![1.0e6, 1.0e6]] {
+            let _ = camera.set_offset(offset, &doc);
+            let viewport = camera.viewport();
+            assert!(viewport.mins.x >= mins_unrotated.x - 1.0e-6);
+            assert!(viewport.mins.y >= mins_unrotated.y - 1.0e-6);
+            assert!(viewport.maxs.x <= maxs_unrotated.x + 1.0e-6);
+            assert!(viewport.maxs.y <= maxs_unrotated.y + 1.0e-6);
+        }
+    }
 }
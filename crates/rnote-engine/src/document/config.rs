@@ -11,4 +11,7 @@ pub struct DocumentConfig {
     pub background: Background,
     #[serde(rename = "layout", alias = "expand_mode")]
     pub layout: Layout,
+    /// Arranges the pages in rows of two, side by side like an open book. Only used in the fixed-size layout.
+    #[serde(rename = "two_page_spread")]
+    pub two_page_spread: bool,
 }
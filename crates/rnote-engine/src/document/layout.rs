@@ -21,6 +21,8 @@ pub enum Layout {
     FixedSize,
     #[serde(rename = "continuous_vertical", alias = "endless_vertical")]
     ContinuousVertical,
+    #[serde(rename = "continuous_horizontal")]
+    ContinuousHorizontal,
     #[serde(rename = "semi_infinite")]
     SemiInfinite,
     #[serde(rename = "infinite")]
@@ -49,6 +51,7 @@ impl std::str::FromStr for Layout {
         match s {
             "fixed-size" => Ok(Self::FixedSize),
             "continuous-vertical" => Ok(Self::ContinuousVertical),
+            "continuous-horizontal" => Ok(Self::ContinuousHorizontal),
             "semi-infinite" => Ok(Self::SemiInfinite),
             "infinite" => Ok(Self::Infinite),
             s => Err(anyhow::anyhow!(
@@ -63,6 +66,7 @@ impl Display for Layout {
         match self {
             Layout::FixedSize => write!(f, "fixed-size"),
             Layout::ContinuousVertical => write!(f, "continuous-vertical"),
+            Layout::ContinuousHorizontal => write!(f, "continuous-horizontal"),
             Layout::SemiInfinite => write!(f, "semi-infinite"),
             Layout::Infinite => write!(f, "infinite"),
         }
@@ -74,4 +78,12 @@ impl Layout {
     pub fn is_fixed_width(&self) -> bool {
         matches!(self, Layout::FixedSize | Layout::ContinuousVertical)
     }
+
+    /// checks if the document is made up of the individual pages of the document page list
+    pub fn has_pages(&self) -> bool {
+        matches!(
+            self,
+            Layout::FixedSize | Layout::ContinuousVertical | Layout::ContinuousHorizontal
+        )
+    }
 }
@@ -3,6 +3,8 @@ pub mod background;
 pub mod config;
 pub mod format;
 pub mod layout;
+pub mod page;
+pub mod sourcepdf;
 
 // Re-exports
 pub use background::Background;
@@ -10,6 +12,8 @@ pub use config::DocumentConfig;
 pub use format::Format;
 pub use layout::Layout;
 use na::SimdPartialOrd;
+pub use page::Page;
+pub use sourcepdf::{SourcePdf, SourcePdfPage};
 
 // Imports
 use self::background::PatternStyle;
@@ -20,6 +24,7 @@ use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::{Color, SplitOrder};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "document")]
@@ -34,6 +39,12 @@ pub struct Document {
     pub width: f64,
     #[serde(rename = "height", with = "rnote_compose::serialize::f64_dp3")]
     pub height: f64,
+    /// The pages, arranged from the origin as given by the layout. Only used when the layout has pages.
+    #[serde(rename = "pages")]
+    pub pages: Vec<Page>,
+    /// The Pdfs the document was imported from, referenced by the pages that were generated from them.
+    #[serde(rename = "source_pdfs")]
+    pub source_pdfs: Vec<SourcePdf>,
 }
 
 impl Default for Document {
@@ -44,6 +55,8 @@ impl Default for Document {
             y: 0.0,
             width: Format::default().width(),
             height: Format::default().height(),
+            pages: vec![Page::default()],
+            source_pdfs: vec![],
         }
     }
 }
@@ -64,6 +77,9 @@ impl Document {
         a: 0.35,
     };
 
+    /// Tolerance when fitting the pages to an extent, to avoid adding pages because of rounding errors.
+    const PAGES_FIT_TOLERANCE: f64 = 0.5;
+
     pub(crate) fn bounds(&self) -> Aabb {
         Aabb::new(
             na::point![self.x, self.y],
@@ -71,25 +87,212 @@ impl Document {
         )
     }
 
+    /// The format of the page at the given index, falling back to the document format.
+    pub fn page_format(&self, page_i: usize) -> Format {
+        self.pages
+            .get(page_i)
+            .and_then(|page| page.format)
+            .unwrap_or(self.config.format)
+    }
+
+    /// The background of the page at the given index, falling back to the document background.
+    pub fn page_background(&self, page_i: usize) -> Background {
+        self.pages
+            .get(page_i)
+            .and_then(|page| page.background)
+            .unwrap_or(self.config.background)
+    }
+
+    /// The title of the page at the given index, if it has one.
+    pub fn page_title(&self, page_i: usize) -> Option<&str> {
+        self.pages
+            .get(page_i)
+            .and_then(|page| page.title.as_deref())
+    }
+
+    /// The index of the page that contains the given position, if the layout has pages.
+    pub fn page_index_at(&self, pos: na::Vector2<f64>) -> Option<usize> {
+        if !self.config.layout.has_pages() {
+            return None;
+        }
+        self.pages_arranged_bounds()
+            .iter()
+            .position(|bounds| bounds.contains_local_point(&pos.into()))
+    }
+
+    pub fn source_pdf(&self, id: u32) -> Option<&SourcePdf> {
+        self.source_pdfs
+            .iter()
+            .find(|source_pdf| source_pdf.id == id)
+    }
+
+    /// Inserts a source Pdf and returns its id.
+    ///
+    /// When the same Pdf is already present, its id is returned instead.
+    pub(crate) fn insert_source_pdf(&mut self, data: glib::Bytes) -> u32 {
+        if let Some(source_pdf) = self
+            .source_pdfs
+            .iter()
+            .find(|source_pdf| source_pdf.data == data)
+        {
+            return source_pdf.id;
+        }
+        let id = self
+            .source_pdfs
+            .iter()
+            .map(|source_pdf| source_pdf.id + 1)
+            .max()
+            .unwrap_or(0);
+        self.source_pdfs.push(SourcePdf { id, data });
+        id
+    }
+
+    /// Removes the source Pdfs that are not referenced anymore.
+    pub(crate) fn retain_source_pdfs(&mut self, referenced: &HashSet<u32>) {
+        self.source_pdfs
+            .retain(|source_pdf| referenced.contains(&source_pdf.id));
+    }
+
+    /// Whether the pages are arranged in rows of two, which is only possible in the fixed-size layout.
+    pub fn two_page_spread(&self) -> bool {
+        self.config.layout == Layout::FixedSize && self.config.two_page_spread
+    }
+
+    /// The axis along which pages are appended, `0` for horizontal and `1` for vertical.
+    pub(crate) fn pages_main_axis(&self) -> usize {
+        if self.config.layout == Layout::ContinuousHorizontal {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// The bounds of the pages of the page list, arranged from the origin.
+    ///
+    /// The pages are placed side by side in the continuous horizontal layout, in rows of two with the two-page spread
+    /// and else stacked vertically.
+    pub(crate) fn pages_arranged_bounds(&self) -> Vec<Aabb> {
+        let n_pages = self.pages.len();
+        let mut pages_bounds = Vec::with_capacity(n_pages);
+
+        if self.config.layout == Layout::ContinuousHorizontal {
+            let mut x = 0.0;
+            for page_i in 0..n_pages {
+                let size = self.page_format(page_i).size();
+                pages_bounds.push(Aabb::new(
+                    na::point![x, 0.0],
+                    na::point![x + size[0], size[1]],
+                ));
+                x += size[0];
+            }
+        } else if self.two_page_spread() {
+            let mut y = 0.0;
+            for left_i in (0..n_pages).step_by(2) {
+                let left_size = self.page_format(left_i).size();
+                let mut row_height = left_size[1];
+                pages_bounds.push(Aabb::new(
+                    na::point![0.0, y],
+                    na::point![left_size[0], y + left_size[1]],
+                ));
+                if left_i + 1 < n_pages {
+                    let right_size = self.page_format(left_i + 1).size();
+                    row_height = row_height.max(right_size[1]);
+                    pages_bounds.push(Aabb::new(
+                        na::point![left_size[0], y],
+                        na::point![left_size[0] + right_size[0], y + right_size[1]],
+                    ));
+                }
+                y += row_height;
+            }
+        } else {
+            let mut y = 0.0;
+            for page_i in 0..n_pages {
+                let size = self.page_format(page_i).size();
+                pages_bounds.push(Aabb::new(
+                    na::point![0.0, y],
+                    na::point![size[0], y + size[1]],
+                ));
+                y += size[1];
+            }
+        }
+        pages_bounds
+    }
+
     /// Generate bounds for each page for the doc bounds, extended to fit the format.
     ///
+    /// When the layout has pages, these are the bounds of the pages of the page list.
     /// May contain many empty pages (in infinite mode)
-    #[allow(unused)]
     pub(crate) fn pages_bounds(&self, split_order: SplitOrder) -> Vec<Aabb> {
+        self.pages_bounds_w_backgrounds(split_order)
+            .into_iter()
+            .map(|(bounds, _)| bounds)
+            .collect()
+    }
+
+    /// Generate bounds for each page together with the background of the page.
+    ///
+    /// When the layout has pages, they are returned in the order of the page list and the split order is not used.
+    pub(crate) fn pages_bounds_w_backgrounds(
+        &self,
+        split_order: SplitOrder,
+    ) -> Vec<(Aabb, Background)> {
+        if self.config.layout.has_pages() {
+            return self
+                .pages_arranged_bounds()
+                .into_iter()
+                .enumerate()
+                .map(|(page_i, bounds)| (bounds, self.page_background(page_i)))
+                .collect();
+        }
         let doc_bounds = self.bounds();
 
         if self.config.format.height() > 0.0 && self.config.format.width() > 0.0 {
-            doc_bounds.split_extended_origin_aligned(
-                na::vector![self.config.format.width(), self.config.format.height()],
-                split_order,
-            )
+            doc_bounds
+                .split_extended_origin_aligned(
+                    na::vector![self.config.format.width(), self.config.format.height()],
+                    split_order,
+                )
+                .into_iter()
+                .map(|bounds| (bounds, self.config.background))
+                .collect()
         } else {
             vec![]
         }
     }
 
+    /// The bounds of the page that contains the given position.
+    ///
+    /// Positions beside the pages are matched with the page in the same row or column.
+    /// Falls back to the origin aligned format grid when the layout has no pages or the position is outside of them.
+    pub(crate) fn page_bounds_at(&self, pos: na::Vector2<f64>) -> Aabb {
+        if self.config.layout.has_pages() {
+            let axis = self.pages_main_axis();
+            let pages_bounds = self.pages_arranged_bounds();
+            if let Some(bounds) = pages_bounds
+                .iter()
+                .find(|bounds| bounds.contains_local_point(&pos.into()))
+                .or_else(|| {
+                    pages_bounds.iter().find(|bounds| {
+                        pos[axis] >= bounds.mins[axis] && pos[axis] < bounds.maxs[axis]
+                    })
+                })
+            {
+                return *bounds;
+            }
+        }
+        let format_size = self.config.format.size();
+        let mins = pos
+            .component_div(&format_size)
+            .map(|v| v.floor())
+            .component_mul(&format_size);
+        Aabb::new(mins.into(), (mins + format_size).into())
+    }
+
     #[allow(unused)]
     pub(crate) fn calc_n_pages(&self) -> u32 {
+        if self.config.layout.has_pages() {
+            return self.pages.len() as u32;
+        }
         // Avoid div by 0
         if self.config.format.height() > 0.0 && self.config.format.width() > 0.0 {
             (self.width / self.config.format.width()).ceil() as u32
@@ -99,6 +302,116 @@ impl Document {
         }
     }
 
+    /// The end of the row of pages that contains the page at the given index, along the axis pages are appended.
+    ///
+    /// Each page is its own row, except with the two-page spread.
+    pub(crate) fn page_row_end(&self, page_i: usize) -> Option<f64> {
+        let axis = self.pages_main_axis();
+        let pages_bounds = self.pages_arranged_bounds();
+        if page_i >= pages_bounds.len() {
+            return None;
+        }
+        let row = if self.two_page_spread() {
+            let left_i = page_i - page_i % 2;
+            left_i..(left_i + 2).min(pages_bounds.len())
+        } else {
+            page_i..page_i + 1
+        };
+        Some(
+            pages_bounds[row]
+                .iter()
+                .fold(f64::MIN, |row_end, bounds| row_end.max(bounds.maxs[axis])),
+        )
+    }
+
+    /// The size of the arranged pages.
+    fn pages_size(&self) -> na::Vector2<f64> {
+        self.pages_arranged_bounds()
+            .into_iter()
+            .fold(na::Vector2::zeros(), |size, bounds| {
+                size.sup(&bounds.maxs.coords)
+            })
+    }
+
+    /// Appends pages in the document format until the pages cover the given extent along the axis pages are appended,
+    /// and removes trailing pages that are not needed to cover it. At least one page is kept.
+    ///
+    /// Customized pages are never removed, so removing stops at the last customized page.
+    /// With the two-page spread, the last row is always filled up, so that content on its right page is kept.
+    fn fit_pages_to_extent(&mut self, extent: f64) {
+        let axis = self.pages_main_axis();
+        if self.pages.is_empty() {
+            self.pages.push(Page::default());
+        }
+        while self.pages_size()[axis] + Self::PAGES_FIT_TOLERANCE < extent {
+            self.pages.push(Page::default());
+        }
+        while self.pages.len() > 1 && self.pages.last().is_some_and(|last| !last.is_customized()) {
+            let Some(last) = self.pages.pop() else {
+                break;
+            };
+            if self.pages_size()[axis] + Self::PAGES_FIT_TOLERANCE < extent {
+                self.pages.push(last);
+                break;
+            }
+        }
+        if self.two_page_spread() && self.pages.len() % 2 == 1 {
+            self.pages.push(Page::default());
+        }
+    }
+
+    /// Sets the format of the page at the given index. `None` resets it to the document format.
+    ///
+    /// Returns false when the page does not exist.
+    pub(crate) fn set_page_format(&mut self, page_i: usize, format: Option<Format>) -> bool {
+        let Some(page) = self.pages.get_mut(page_i) else {
+            return false;
+        };
+        page.format = format;
+        // The extent along the axis pages are appended is updated when resizing in continuous layouts
+        let pages_size = self.pages_size();
+        match self.config.layout {
+            Layout::FixedSize => {
+                self.width = pages_size[0];
+                self.height = pages_size[1];
+            }
+            Layout::ContinuousVertical => {
+                self.width = pages_size[0];
+            }
+            Layout::ContinuousHorizontal => {
+                self.height = pages_size[1];
+            }
+            Layout::SemiInfinite | Layout::Infinite => {}
+        }
+        true
+    }
+
+    /// Sets the background of the page at the given index. `None` resets it to the document background.
+    ///
+    /// Returns false when the page does not exist.
+    pub(crate) fn set_page_background(
+        &mut self,
+        page_i: usize,
+        background: Option<Background>,
+    ) -> bool {
+        let Some(page) = self.pages.get_mut(page_i) else {
+            return false;
+        };
+        page.background = background;
+        true
+    }
+
+    /// Sets the title of the page at the given index. Empty titles are removed.
+    ///
+    /// Returns false when the page does not exist.
+    pub(crate) fn set_page_title(&mut self, page_i: usize, title: Option<String>) -> bool {
+        let Some(page) = self.pages.get_mut(page_i) else {
+            return false;
+        };
+        page.title = title.filter(|title| !title.trim().is_empty());
+        true
+    }
+
     pub(crate) fn resize_to_fit_content(
         &mut self,
         store: &StrokeStore,
@@ -112,6 +425,9 @@ impl Document {
             Layout::ContinuousVertical => {
                 widget_flags.resize |= self.resize_doc_continuous_vertical_layout(store);
             }
+            Layout::ContinuousHorizontal => {
+                widget_flags.resize |= self.resize_doc_continuous_horizontal_layout(store);
+            }
             Layout::SemiInfinite => {
                 widget_flags.resize |=
                     self.resize_doc_semi_infinite_layout(camera.viewport(), store, true);
@@ -137,6 +453,9 @@ impl Document {
             Layout::ContinuousVertical => {
                 widget_flags.resize |= self.resize_doc_continuous_vertical_layout(store);
             }
+            Layout::ContinuousHorizontal => {
+                widget_flags.resize |= self.resize_doc_continuous_horizontal_layout(store);
+            }
             Layout::SemiInfinite => {
                 widget_flags.resize |=
                     self.resize_doc_semi_infinite_layout(camera.viewport(), store, true);
@@ -156,7 +475,7 @@ impl Document {
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         match self.config.layout {
-            Layout::FixedSize | Layout::ContinuousVertical => {
+            Layout::FixedSize | Layout::ContinuousVertical | Layout::ContinuousHorizontal => {
                 // not resizing in these modes, the size is not dependent on the camera
             }
             Layout::SemiInfinite => {
@@ -173,38 +492,41 @@ impl Document {
         widget_flags
     }
 
-    /// Adds a page when in fixed-size layout.
+    /// Adds a page in the document format when in fixed-size layout.
     ///
     /// Returns false when not in fixed-size layout.
     pub(crate) fn add_page_fixed_size(&mut self) -> bool {
         if self.config.layout != Layout::FixedSize {
             return false;
         }
-        let format_height = self.config.format.height();
-        let new_doc_height = self.height + format_height;
-        self.height = new_doc_height;
+        self.pages.push(Page::default());
+        let pages_size = self.pages_size();
+        self.width = pages_size[0];
+        self.height = pages_size[1];
         true
     }
 
-    /// Removes a page when in fixed-size layout and the size is not the last page.
+    /// Removes the last page when in fixed-size layout and the page is not the only page.
     ///
     /// Returns false when not in fixed-size layout.
     pub(crate) fn remove_page_fixed_size(&mut self) -> bool {
-        if self.config.layout != Layout::FixedSize || self.height <= self.config.format.height() {
+        if self.config.layout != Layout::FixedSize || self.pages.len() <= 1 {
             return false;
         }
-        self.height -= self.config.format.height();
+        self.pages.pop();
+        let pages_size = self.pages_size();
+        self.width = pages_size[0];
+        self.height = pages_size[1];
         true
     }
 
     /// Returns true if a resize happened.
     #[must_use = "Determines if the resize flag should be set"]
     fn resize_doc_fixed_size_layout(&mut self, store: &StrokeStore) -> bool {
-        let format_height = self.config.format.height();
-
-        let new_width = self.config.format.width();
-        // max(1.0) because then 'fraction'.ceil() is at least 1
-        let new_height = ((store.calc_height().max(1.0)) / format_height).ceil() * format_height;
+        // max(1.0) because then at least one page is kept
+        self.fit_pages_to_extent(store.calc_height().max(1.0));
+        let pages_size = self.pages_size();
+        let (new_width, new_height) = (pages_size[0], pages_size[1]);
 
         set_dimensions_checked(
             &mut self.x,
@@ -223,7 +545,28 @@ impl Document {
     fn resize_doc_continuous_vertical_layout(&mut self, store: &StrokeStore) -> bool {
         let padding_bottom = self.config.format.height();
         let new_height = store.calc_height() + padding_bottom;
-        let new_width = self.config.format.width();
+        self.fit_pages_to_extent(new_height);
+        let new_width = self.pages_size()[0];
+
+        set_dimensions_checked(
+            &mut self.x,
+            &mut self.y,
+            &mut self.width,
+            &mut self.height,
+            0.,
+            0.,
+            new_width,
+            new_height,
+        )
+    }
+
+    /// Returns true if a resize happened.
+    #[must_use = "Determines if the resize flag should be set"]
+    fn resize_doc_continuous_horizontal_layout(&mut self, store: &StrokeStore) -> bool {
+        let padding_right = self.config.format.width();
+        let new_width = store.calc_width() + padding_right;
+        self.fit_pages_to_extent(new_width);
+        let new_height = self.pages_size()[1];
 
         set_dimensions_checked(
             &mut self.x,
@@ -265,7 +608,7 @@ impl Document {
         }
 
         if include_content {
-            let keys = store.stroke_keys_as_rendered();
+            let keys = store.stroke_keys_unordered();
             let content_bounds = if let Some(content_bounds) = store.bounds_for_strokes(&keys) {
                 content_bounds.extend_right_and_bottom_by(padding)
             } else {
@@ -315,7 +658,7 @@ impl Document {
         }
 
         if include_content {
-            let keys = store.stroke_keys_as_rendered();
+            let keys = store.stroke_keys_unordered();
             let content_bounds = if let Some(content_bounds) = store.bounds_for_strokes(&keys) {
                 content_bounds.extend_by(padding)
             } else {
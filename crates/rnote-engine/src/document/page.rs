@@ -0,0 +1,40 @@
+// Imports
+use super::{Background, Format};
+use serde::{Deserialize, Serialize};
+
+/// A single page of a document with a page-based layout.
+///
+/// The format and background fall back to the ones of the document config when they are not set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "page")]
+pub struct Page {
+    #[serde(rename = "format")]
+    pub format: Option<Format>,
+    #[serde(rename = "background")]
+    pub background: Option<Background>,
+    /// The title of the page, written as page label and bookmark when exporting as Pdf.
+    #[serde(rename = "title")]
+    pub title: Option<String>,
+}
+
+impl Page {
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    pub fn with_title(mut self, title: String) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// Whether the format, background or title of the page is set.
+    pub fn is_customized(&self) -> bool {
+        self.format.is_some() || self.background.is_some() || self.title.is_some()
+    }
+}
@@ -0,0 +1,39 @@
+// Imports
+use serde::{Deserialize, Serialize};
+
+/// A Pdf that pages of the document were imported from.
+///
+/// The original bytes are kept, so that the strokes can later be overlaid onto the original document when exporting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "source_pdf")]
+pub struct SourcePdf {
+    /// The id that is referenced by [SourcePdfPage].
+    #[serde(rename = "id")]
+    pub id: u32,
+    /// The original bytes of the Pdf.
+    ///
+    /// Is (de)serialized with base64 encoding.
+    #[serde(rename = "data", with = "crate::utils::glib_bytes_base64")]
+    pub data: glib::Bytes,
+}
+
+impl Default for SourcePdf {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            data: glib::Bytes::from_owned(Vec::<u8>::new()),
+        }
+    }
+}
+
+/// A reference to a single page of a [SourcePdf].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename = "source_pdf_page")]
+pub struct SourcePdfPage {
+    /// The id of the source Pdf in the document.
+    #[serde(rename = "pdf_id")]
+    pub pdf_id: u32,
+    /// The index of the page in the source Pdf.
+    #[serde(rename = "page_i")]
+    pub page_i: usize,
+}
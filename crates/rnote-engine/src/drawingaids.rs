@@ -0,0 +1,809 @@
+// Imports
+use crate::Camera;
+use crate::DrawableOnDoc;
+use crate::document::format::MeasureUnit;
+use crate::engine::EngineView;
+use crate::pens::pensconfig::ToolsConfig;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
+use rnote_compose::color;
+use rnote_compose::ext::Vector2Ext;
+use rnote_compose::penevent::PenEvent;
+use std::f64::consts::PI;
+
+/// Snap the angle to the nearest multiple of the step when it is within the tolerance. All values are in radians.
+pub fn snap_angle(angle: f64, step: f64, tolerance: f64) -> f64 {
+    let snapped = (angle / step).round() * step;
+    if (angle - snapped).abs() <= tolerance {
+        snapped
+    } else {
+        angle
+    }
+}
+
+/// Rotations of the drawing aids snap to multiples of this angle.
+const ROTATION_SNAP_STEP: f64 = PI / 12.0;
+/// Rotations of the drawing aids snap when they are within this angle of a snap step.
+const ROTATION_SNAP_TOLERANCE: f64 = PI / 90.0;
+/// The maximum distance to an edge where strokes get constrained to it, in surface coordinates.
+const EDGE_SNAP_DIST: f64 = 24.0;
+const FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[1].with_a8(200);
+const HANDLE_FILL_COLOR: piet::Color = color::GNOME_BLUES[0].with_a8(120);
+const OUTLINE_COLOR: piet::Color = color::GNOME_DARKS[3];
+const OUTLINE_WIDTH: f64 = 1.5;
+
+/// The part of a drawing aid that was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawingAidHit {
+    /// The body, which moves the aid when dragged.
+    Body,
+    /// The rotation handle, which rotates the aid around its center when dragged.
+    RotationHandle,
+}
+
+/// The position and rotation of a drawing aid.
+#[derive(Debug, Clone, Default)]
+pub struct DrawingAidPlacement {
+    /// The center in document coordinates. Follows the center of the viewport until the aid is placed.
+    pub center: Option<na::Vector2<f64>>,
+    /// The angle, in radians.
+    pub angle: f64,
+}
+
+/// A drawing aid that can be placed and rotated on the canvas with its tool.
+///
+/// The local coordinates of an aid have their origin in its center and are rotated by its angle.
+pub trait DrawingAid {
+    /// The period of the angle that is shown while rotating, in degrees.
+    const ANGLE_LABEL_PERIOD: f64;
+
+    fn placement(&self) -> &DrawingAidPlacement;
+    fn placement_mut(&mut self) -> &mut DrawingAidPlacement;
+    /// The aid of this type in the drawing aids.
+    fn get(drawing_aids: &DrawingAids) -> &Self;
+    fn get_mut(drawing_aids: &mut DrawingAids) -> &mut Self;
+    /// Whether the aid is shown, which is part of the tools config.
+    fn shown(tools_config: &ToolsConfig) -> bool;
+    fn set_shown(tools_config: &mut ToolsConfig, shown: bool);
+    /// The part of the aid at the position in local coordinates.
+    fn hit_local(&self, local: na::Vector2<f64>) -> Option<DrawingAidHit>;
+    /// The bounds of the aid in local coordinates.
+    fn bounds_local(&self) -> Aabb;
+    /// Draw the aid in local coordinates.
+    fn draw_local(
+        &self,
+        cx: &mut impl RenderContext,
+        total_zoom: f64,
+        dpi: f64,
+    ) -> anyhow::Result<()>;
+
+    /// The straight edges that brush strokes get constrained to, as start and end in local coordinates.
+    fn edges(&self) -> Vec<(na::Vector2<f64>, na::Vector2<f64>)> {
+        vec![]
+    }
+
+    /// Where the angle is shown while rotating, in document coordinates.
+    fn angle_label_pos(&self, camera: &Camera, _label_size: f64) -> na::Vector2<f64> {
+        self.center(camera)
+    }
+
+    fn center(&self, camera: &Camera) -> na::Vector2<f64> {
+        self.placement()
+            .center
+            .unwrap_or_else(|| camera.viewport_center())
+    }
+
+    fn angle(&self) -> f64 {
+        self.placement().angle
+    }
+
+    /// Transform the position into the local coordinates.
+    fn to_local(&self, pos: na::Vector2<f64>, camera: &Camera) -> na::Vector2<f64> {
+        na::Rotation2::new(-self.angle()) * (pos - self.center(camera))
+    }
+
+    /// The part of the aid at the position.
+    fn hit(&self, pos: na::Vector2<f64>, camera: &Camera) -> Option<DrawingAidHit> {
+        self.hit_local(self.to_local(pos, camera))
+    }
+
+    /// The line along the edge that is closest to the position, if any is close enough.
+    fn edge_close_to(
+        &self,
+        pos: na::Vector2<f64>,
+        camera: &Camera,
+    ) -> Option<DrawingAidConstraint> {
+        let local = self.to_local(pos, camera);
+        let snap_dist = EDGE_SNAP_DIST / camera.total_zoom();
+        let (_, start, direction) = self
+            .edges()
+            .into_iter()
+            .map(|(start, end)| {
+                let direction = (end - start).normalize();
+                let t = direction
+                    .dot(&(local - start))
+                    .clamp(0.0, (end - start).magnitude());
+                (
+                    (local - (start + direction * t)).magnitude(),
+                    start,
+                    direction,
+                )
+            })
+            .filter(|(dist, _, _)| *dist <= snap_dist)
+            .min_by(|(first, _, _), (second, _, _)| first.total_cmp(second))?;
+        let rotation = na::Rotation2::new(self.angle());
+        Some(DrawingAidConstraint::Line {
+            origin: self.center(camera) + rotation * start,
+            direction: rotation * direction,
+        })
+    }
+
+    /// Rotate the aid by the angle, snapping the resulting angle to common angles.
+    fn rotate_from(&mut self, start_angle: f64, angle: f64) {
+        self.placement_mut().angle = snap_angle(
+            start_angle + angle,
+            ROTATION_SNAP_STEP,
+            ROTATION_SNAP_TOLERANCE,
+        );
+    }
+
+    fn bounds(&self, camera: &Camera) -> Aabb {
+        let bounds_local = self.bounds_local();
+        let isometry = na::Isometry2::new(self.center(camera), self.angle());
+        bounds_local
+            .transform_by(&isometry)
+            .loosened(OUTLINE_WIDTH / camera.total_zoom())
+    }
+
+    fn draw(&self, cx: &mut impl RenderContext, camera: &Camera, dpi: f64) -> anyhow::Result<()> {
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(
+            kurbo::Affine::translate(self.center(camera).to_kurbo_vec())
+                * kurbo::Affine::rotate(self.angle()),
+        );
+        self.draw_local(cx, camera.total_zoom(), dpi)?;
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+/// A virtual ruler.
+///
+/// Brush strokes that are started close to one of its long edges are drawn along the edge.
+#[derive(Debug, Clone, Default)]
+pub struct Ruler {
+    pub placement: DrawingAidPlacement,
+}
+
+impl Ruler {
+    /// The length, in document coordinates.
+    pub const LENGTH: f64 = 960.0;
+    /// The width, in document coordinates.
+    pub const WIDTH: f64 = 80.0;
+    /// The length of the rotation handles at both ends, in document coordinates.
+    const ROTATION_HANDLE_LENGTH: f64 = 120.0;
+}
+
+impl DrawingAid for Ruler {
+    const ANGLE_LABEL_PERIOD: f64 = 180.0;
+
+    fn placement(&self) -> &DrawingAidPlacement {
+        &self.placement
+    }
+
+    fn placement_mut(&mut self) -> &mut DrawingAidPlacement {
+        &mut self.placement
+    }
+
+    fn get(drawing_aids: &DrawingAids) -> &Self {
+        &drawing_aids.ruler
+    }
+
+    fn get_mut(drawing_aids: &mut DrawingAids) -> &mut Self {
+        &mut drawing_aids.ruler
+    }
+
+    fn shown(tools_config: &ToolsConfig) -> bool {
+        tools_config.show_ruler
+    }
+
+    fn set_shown(tools_config: &mut ToolsConfig, shown: bool) {
+        tools_config.show_ruler = shown;
+    }
+
+    fn hit_local(&self, local: na::Vector2<f64>) -> Option<DrawingAidHit> {
+        if local[0].abs() > Self::LENGTH * 0.5 || local[1].abs() > Self::WIDTH * 0.5 {
+            None
+        } else if local[0].abs() > Self::LENGTH * 0.5 - Self::ROTATION_HANDLE_LENGTH {
+            Some(DrawingAidHit::RotationHandle)
+        } else {
+            Some(DrawingAidHit::Body)
+        }
+    }
+
+    fn bounds_local(&self) -> Aabb {
+        Aabb::from_half_extents(
+            na::Point2::origin(),
+            na::vector![Self::LENGTH * 0.5, Self::WIDTH * 0.5],
+        )
+    }
+
+    fn edges(&self) -> Vec<(na::Vector2<f64>, na::Vector2<f64>)> {
+        let half_length = Self::LENGTH * 0.5;
+        let half_width = Self::WIDTH * 0.5;
+        vec![
+            (
+                na::vector![-half_length, -half_width],
+                na::vector![half_length, -half_width],
+            ),
+            (
+                na::vector![-half_length, half_width],
+                na::vector![half_length, half_width],
+            ),
+        ]
+    }
+
+    fn draw_local(
+        &self,
+        cx: &mut impl RenderContext,
+        total_zoom: f64,
+        dpi: f64,
+    ) -> anyhow::Result<()> {
+        let half_length = Self::LENGTH * 0.5;
+        let half_width = Self::WIDTH * 0.5;
+
+        let body = kurbo::Rect::new(-half_length, -half_width, half_length, half_width);
+        cx.fill(body, &FILL_COLOR);
+        for sign in [-1.0, 1.0] {
+            let inner = sign * (half_length - Self::ROTATION_HANDLE_LENGTH);
+            cx.fill(
+                kurbo::Rect::new(inner, -half_width, sign * half_length, half_width),
+                &HANDLE_FILL_COLOR,
+            );
+        }
+        cx.stroke(body, &OUTLINE_COLOR, OUTLINE_WIDTH / total_zoom);
+
+        // Millimeter scales along both long edges, with labels for every centimeter along the upper one
+        draw_mm_scale(cx, Self::LENGTH, -half_width, 1.0, true, total_zoom, dpi)?;
+        draw_mm_scale(cx, Self::LENGTH, half_width, -1.0, false, total_zoom, dpi)?;
+        Ok(())
+    }
+}
+
+/// A virtual protractor.
+///
+/// Brush strokes that are started at its center are drawn along rays with snapped angles.
+#[derive(Debug, Clone, Default)]
+pub struct Protractor {
+    /// The center is the center of the baseline, the angle is the angle of the baseline.
+    pub placement: DrawingAidPlacement,
+    /// The angle relative to the baseline that is indicated, usually of the ray that is currently drawn.
+    pub indicated_angle: Option<f64>,
+}
+
+impl Protractor {
+    /// The radius, in document coordinates.
+    pub const RADIUS: f64 = 220.0;
+    /// The width of the rotation handle along the arc, in document coordinates.
+    const ROTATION_HANDLE_WIDTH: f64 = 36.0;
+    /// The height of the strip below the baseline, in document coordinates.
+    const BASE_HEIGHT: f64 = 16.0;
+    /// The maximum distance to the center where strokes get constrained to rays, in surface coordinates.
+    const CENTER_SNAP_DIST: f64 = 20.0;
+    /// Rays snap to multiples of this angle within the tolerance, and to whole degrees otherwise.
+    const RAY_SNAP_STEP: f64 = PI / 12.0;
+    const RAY_SNAP_TOLERANCE: f64 = PI / 90.0;
+    const INDICATOR_COLOR: piet::Color = color::GNOME_REDS[2];
+
+    /// The angle of the ray from the center through the position relative to the baseline, counter-clockwise and snapped.
+    pub fn ray_angle(&self, pos: na::Vector2<f64>, camera: &Camera) -> f64 {
+        let local = self.to_local(pos, camera);
+        let angle = snap_angle(
+            (-local[1]).atan2(local[0]),
+            Self::RAY_SNAP_STEP,
+            Self::RAY_SNAP_TOLERANCE,
+        );
+        // Snapping to whole degrees with a tolerance of half a degree always snaps
+        let degree = 1.0_f64.to_radians();
+        snap_angle(angle, degree, degree * 0.5)
+    }
+
+    /// The unit vector along the ray with the angle relative to the baseline.
+    pub fn ray_direction(&self, ray_angle: f64) -> na::Vector2<f64> {
+        na::Rotation2::new(self.angle()) * na::vector![ray_angle.cos(), -ray_angle.sin()]
+    }
+
+    /// Whether the position is close to the center, where rays start.
+    pub fn center_close_to(&self, pos: na::Vector2<f64>, camera: &Camera) -> bool {
+        (pos - self.center(camera)).magnitude() <= Self::CENTER_SNAP_DIST / camera.total_zoom()
+    }
+}
+
+impl DrawingAid for Protractor {
+    const ANGLE_LABEL_PERIOD: f64 = 360.0;
+
+    fn placement(&self) -> &DrawingAidPlacement {
+        &self.placement
+    }
+
+    fn placement_mut(&mut self) -> &mut DrawingAidPlacement {
+        &mut self.placement
+    }
+
+    fn get(drawing_aids: &DrawingAids) -> &Self {
+        &drawing_aids.protractor
+    }
+
+    fn get_mut(drawing_aids: &mut DrawingAids) -> &mut Self {
+        &mut drawing_aids.protractor
+    }
+
+    fn shown(tools_config: &ToolsConfig) -> bool {
+        tools_config.show_protractor
+    }
+
+    fn set_shown(tools_config: &mut ToolsConfig, shown: bool) {
+        tools_config.show_protractor = shown;
+    }
+
+    fn hit_local(&self, local: na::Vector2<f64>) -> Option<DrawingAidHit> {
+        let dist = local.magnitude();
+        if local[1] > Self::BASE_HEIGHT || local[0].abs() > Self::RADIUS || dist > Self::RADIUS {
+            None
+        } else if dist > Self::RADIUS - Self::ROTATION_HANDLE_WIDTH {
+            Some(DrawingAidHit::RotationHandle)
+        } else {
+            Some(DrawingAidHit::Body)
+        }
+    }
+
+    fn bounds_local(&self) -> Aabb {
+        Aabb::new(
+            na::point![-Self::RADIUS, -Self::RADIUS],
+            na::point![Self::RADIUS, Self::BASE_HEIGHT],
+        )
+    }
+
+    /// The label is shown above the center marker.
+    fn angle_label_pos(&self, camera: &Camera, label_size: f64) -> na::Vector2<f64> {
+        self.center(camera) - na::vector![0.0, label_size * 0.5 / camera.total_zoom()]
+    }
+
+    fn draw_local(
+        &self,
+        cx: &mut impl RenderContext,
+        total_zoom: f64,
+        _dpi: f64,
+    ) -> anyhow::Result<()> {
+        let radius = Self::RADIUS;
+        let handle_radius = radius - Self::ROTATION_HANDLE_WIDTH;
+
+        let mut body = kurbo::BezPath::new();
+        body.move_to((-radius, Self::BASE_HEIGHT));
+        body.line_to((-radius, 0.0));
+        body.extend(kurbo::Arc::new((0.0, 0.0), (radius, radius), PI, PI, 0.0).append_iter(0.1));
+        body.line_to((radius, Self::BASE_HEIGHT));
+        body.close_path();
+        let mut handle = kurbo::BezPath::new();
+        handle.move_to((-radius, 0.0));
+        handle.extend(kurbo::Arc::new((0.0, 0.0), (radius, radius), PI, PI, 0.0).append_iter(0.1));
+        handle.line_to((handle_radius, 0.0));
+        handle.extend(
+            kurbo::Arc::new((0.0, 0.0), (handle_radius, handle_radius), 0.0, -PI, 0.0)
+                .append_iter(0.1),
+        );
+        handle.close_path();
+        cx.fill(&body, &FILL_COLOR);
+        cx.fill(&handle, &HANDLE_FILL_COLOR);
+        cx.stroke(&body, &OUTLINE_COLOR, OUTLINE_WIDTH / total_zoom);
+        cx.stroke(
+            kurbo::Line::new((-radius, 0.0), (radius, 0.0)),
+            &OUTLINE_COLOR,
+            1.0 / total_zoom,
+        );
+
+        // Degree ticks along the inner edge of the rotation handle, with labels for every ten degrees
+        for degrees in 0..=180 {
+            let tick_length = if degrees % 10 == 0 {
+                18.0
+            } else if degrees % 5 == 0 {
+                12.0
+            } else {
+                7.0
+            };
+            let dir = na::vector![
+                (degrees as f64).to_radians().cos(),
+                -(degrees as f64).to_radians().sin()
+            ];
+            cx.stroke(
+                kurbo::Line::new(
+                    (dir * handle_radius).to_kurbo_point(),
+                    (dir * (handle_radius - tick_length)).to_kurbo_point(),
+                ),
+                &OUTLINE_COLOR,
+                1.0 / total_zoom,
+            );
+            if degrees % 10 == 0 {
+                draw_label(cx, &degrees.to_string(), dir * (handle_radius - 30.0), 10.0)?;
+            }
+        }
+
+        // The center marker
+        cx.stroke(
+            kurbo::Line::new((-8.0, 0.0), (8.0, 0.0)),
+            &OUTLINE_COLOR,
+            OUTLINE_WIDTH / total_zoom,
+        );
+        cx.stroke(
+            kurbo::Line::new((0.0, -8.0), (0.0, 8.0)),
+            &OUTLINE_COLOR,
+            OUTLINE_WIDTH / total_zoom,
+        );
+
+        if let Some(indicated_angle) = self.indicated_angle {
+            let dir = na::vector![indicated_angle.cos(), -indicated_angle.sin()];
+            cx.stroke(
+                kurbo::Line::new((0.0, 0.0), (dir * radius).to_kurbo_point()),
+                &Self::INDICATOR_COLOR,
+                OUTLINE_WIDTH / total_zoom,
+            );
+            draw_label(
+                cx,
+                &format!("{:.0}°", indicated_angle.to_degrees()),
+                dir * (handle_radius * 0.5),
+                14.0,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A virtual set square, shaped as a right isosceles triangle.
+///
+/// Brush strokes that are started close to one of its edges are drawn along the edge.
+#[derive(Debug, Clone, Default)]
+pub struct SetSquare {
+    /// The center is the center of the hypotenuse, the angle is the angle of the hypotenuse.
+    pub placement: DrawingAidPlacement,
+}
+
+impl SetSquare {
+    /// The length of the hypotenuse, in document coordinates.
+    pub const LENGTH: f64 = 640.0;
+    /// The height of the rotation handle at the right angle corner, in document coordinates.
+    const ROTATION_HANDLE_HEIGHT: f64 = 90.0;
+    /// The distance of the scale along the hypotenuse to the corners, in document coordinates.
+    const SCALE_INSET: f64 = 40.0;
+}
+
+impl DrawingAid for SetSquare {
+    const ANGLE_LABEL_PERIOD: f64 = 360.0;
+
+    fn placement(&self) -> &DrawingAidPlacement {
+        &self.placement
+    }
+
+    fn placement_mut(&mut self) -> &mut DrawingAidPlacement {
+        &mut self.placement
+    }
+
+    fn get(drawing_aids: &DrawingAids) -> &Self {
+        &drawing_aids.setsquare
+    }
+
+    fn get_mut(drawing_aids: &mut DrawingAids) -> &mut Self {
+        &mut drawing_aids.setsquare
+    }
+
+    fn shown(tools_config: &ToolsConfig) -> bool {
+        tools_config.show_setsquare
+    }
+
+    fn set_shown(tools_config: &mut ToolsConfig, shown: bool) {
+        tools_config.show_setsquare = shown;
+    }
+
+    fn hit_local(&self, local: na::Vector2<f64>) -> Option<DrawingAidHit> {
+        let half_length = Self::LENGTH * 0.5;
+        if local[1] > 0.0 || local[0].abs() - local[1] > half_length {
+            None
+        } else if -local[1] > half_length - Self::ROTATION_HANDLE_HEIGHT {
+            Some(DrawingAidHit::RotationHandle)
+        } else {
+            Some(DrawingAidHit::Body)
+        }
+    }
+
+    fn bounds_local(&self) -> Aabb {
+        let half_length = Self::LENGTH * 0.5;
+        Aabb::new(
+            na::point![-half_length, -half_length],
+            na::point![half_length, 0.0],
+        )
+    }
+
+    /// The label is shown above the center of the hypotenuse.
+    fn angle_label_pos(&self, camera: &Camera, label_size: f64) -> na::Vector2<f64> {
+        self.center(camera) - na::vector![0.0, label_size * 0.5 / camera.total_zoom()]
+    }
+
+    fn edges(&self) -> Vec<(na::Vector2<f64>, na::Vector2<f64>)> {
+        let half_length = Self::LENGTH * 0.5;
+        let left = na::vector![-half_length, 0.0];
+        let right = na::vector![half_length, 0.0];
+        let apex = na::vector![0.0, -half_length];
+        vec![(left, right), (left, apex), (apex, right)]
+    }
+
+    fn draw_local(
+        &self,
+        cx: &mut impl RenderContext,
+        total_zoom: f64,
+        dpi: f64,
+    ) -> anyhow::Result<()> {
+        let half_length = Self::LENGTH * 0.5;
+        let handle_half_width = Self::ROTATION_HANDLE_HEIGHT;
+
+        let mut body = kurbo::BezPath::new();
+        body.move_to((-half_length, 0.0));
+        body.line_to((0.0, -half_length));
+        body.line_to((half_length, 0.0));
+        body.close_path();
+        let mut handle = kurbo::BezPath::new();
+        handle.move_to((
+            -handle_half_width,
+            -half_length + Self::ROTATION_HANDLE_HEIGHT,
+        ));
+        handle.line_to((0.0, -half_length));
+        handle.line_to((
+            handle_half_width,
+            -half_length + Self::ROTATION_HANDLE_HEIGHT,
+        ));
+        handle.close_path();
+        cx.fill(&body, &FILL_COLOR);
+        cx.fill(&handle, &HANDLE_FILL_COLOR);
+        cx.stroke(&body, &OUTLINE_COLOR, OUTLINE_WIDTH / total_zoom);
+
+        // Millimeter scale along the hypotenuse, with labels for every centimeter
+        draw_mm_scale(
+            cx,
+            Self::LENGTH - 2.0 * Self::SCALE_INSET,
+            0.0,
+            -1.0,
+            true,
+            total_zoom,
+            dpi,
+        )?;
+        Ok(())
+    }
+}
+
+/// Draw a millimeter scale centered on the origin along the local x-axis, with ticks pointing from the edge at `edge_y`
+/// in the direction of the sign of `inward`. When `with_labels` is set, every centimeter is labeled.
+fn draw_mm_scale(
+    cx: &mut impl RenderContext,
+    length: f64,
+    edge_y: f64,
+    inward: f64,
+    with_labels: bool,
+    total_zoom: f64,
+    dpi: f64,
+) -> anyhow::Result<()> {
+    let mm = MeasureUnit::convert_measurement(1.0, MeasureUnit::Mm, dpi, MeasureUnit::Px, dpi);
+    let draw_mm_ticks = mm * total_zoom >= 3.0;
+    let n_mm = (length / mm).floor() as i64;
+    let start = -(n_mm as f64) * mm * 0.5;
+    for i in 0..=n_mm {
+        let tick_length = if i % 10 == 0 {
+            18.0
+        } else if i % 5 == 0 {
+            12.0
+        } else if draw_mm_ticks {
+            7.0
+        } else {
+            continue;
+        };
+        let x = start + i as f64 * mm;
+        cx.stroke(
+            kurbo::Line::new((x, edge_y), (x, edge_y + inward.signum() * tick_length)),
+            &OUTLINE_COLOR,
+            1.0 / total_zoom,
+        );
+        if with_labels && i % 10 == 0 {
+            draw_label(
+                cx,
+                &(i / 10).to_string(),
+                na::vector![x, edge_y + inward.signum() * 28.0],
+                10.0,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Draw the text centered on the position.
+pub(crate) fn draw_label(
+    cx: &mut impl RenderContext,
+    text: &str,
+    pos: na::Vector2<f64>,
+    font_size: f64,
+) -> anyhow::Result<()> {
+    let layout = cx
+        .text()
+        .new_text_layout(text.to_string())
+        .font(piet::FontFamily::SANS_SERIF, font_size)
+        .text_color(OUTLINE_COLOR)
+        .build()
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    let size = layout.size();
+    cx.draw_text(
+        &layout,
+        (pos[0] - size.width * 0.5, pos[1] - size.height * 0.5),
+    );
+    Ok(())
+}
+
+/// Constrains the positions of a brush stroke to a drawing aid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrawingAidConstraint {
+    /// Along a line, e.g. the edge of the ruler.
+    Line {
+        /// A point on the line, in document coordinates.
+        origin: na::Vector2<f64>,
+        /// The unit vector along the line.
+        direction: na::Vector2<f64>,
+    },
+    /// Along a ray from the center of the protractor.
+    ///
+    /// The angle of the ray is determined once the pen has moved far enough away from the center.
+    ProtractorRay {
+        /// The center of the protractor, in document coordinates.
+        center: na::Vector2<f64>,
+        /// The angle of the ray relative to the baseline of the protractor.
+        ray_angle: Option<f64>,
+    },
+}
+
+impl DrawingAidConstraint {
+    /// The distance the pen needs to move away from the center of the protractor
+    /// before the angle of the ray is determined, in surface coordinates.
+    const RAY_ANGLE_DIST: f64 = 12.0;
+
+    /// Constrain the position.
+    pub fn constrain(
+        &mut self,
+        pos: na::Vector2<f64>,
+        drawing_aids: &mut DrawingAids,
+        camera: &Camera,
+    ) -> na::Vector2<f64> {
+        match self {
+            Self::Line { origin, direction } => {
+                *origin + *direction * direction.dot(&(pos - *origin))
+            }
+            Self::ProtractorRay { center, ray_angle } => {
+                if ray_angle.is_none()
+                    && (pos - *center).magnitude() >= Self::RAY_ANGLE_DIST / camera.total_zoom()
+                {
+                    *ray_angle = Some(drawing_aids.protractor.ray_angle(pos, camera));
+                    drawing_aids.protractor.indicated_angle = *ray_angle;
+                }
+                match ray_angle {
+                    Some(ray_angle) => {
+                        let direction = drawing_aids.protractor.ray_direction(*ray_angle);
+                        *center + direction * direction.dot(&(pos - *center)).max(0.0)
+                    }
+                    None => *center,
+                }
+            }
+        }
+    }
+
+    /// Constrain the position of the element in pen down and up events.
+    pub fn constrain_event(
+        &mut self,
+        event: PenEvent,
+        drawing_aids: &mut DrawingAids,
+        camera: &Camera,
+    ) -> PenEvent {
+        match event {
+            PenEvent::Down {
+                mut element,
+                modifier_keys,
+            } => {
+                element.pos = self.constrain(element.pos, drawing_aids, camera);
+                PenEvent::Down {
+                    element,
+                    modifier_keys,
+                }
+            }
+            PenEvent::Up {
+                mut element,
+                modifier_keys,
+            } => {
+                element.pos = self.constrain(element.pos, drawing_aids, camera);
+                PenEvent::Up {
+                    element,
+                    modifier_keys,
+                }
+            }
+            event => event,
+        }
+    }
+}
+
+/// The drawing aids that can be placed on the canvas.
+///
+/// Whether they are visible is part of the tools config.
+#[derive(Debug, Clone, Default)]
+pub struct DrawingAids {
+    pub ruler: Ruler,
+    pub protractor: Protractor,
+    pub setsquare: SetSquare,
+}
+
+impl DrawingAids {
+    /// The constraint for a stroke that starts at the position, if it is close to the center of the visible protractor
+    /// or to an edge of the visible ruler or set square.
+    pub fn constraint_for_stroke_start(
+        &self,
+        pos: na::Vector2<f64>,
+        camera: &Camera,
+        tools_config: &ToolsConfig,
+    ) -> Option<DrawingAidConstraint> {
+        if tools_config.show_protractor && self.protractor.center_close_to(pos, camera) {
+            return Some(DrawingAidConstraint::ProtractorRay {
+                center: self.protractor.center(camera),
+                ray_angle: None,
+            });
+        }
+        tools_config
+            .show_ruler
+            .then(|| self.ruler.edge_close_to(pos, camera))
+            .flatten()
+            .or_else(|| {
+                tools_config
+                    .show_setsquare
+                    .then(|| self.setsquare.edge_close_to(pos, camera))
+                    .flatten()
+            })
+    }
+}
+
+impl DrawableOnDoc for DrawingAids {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        let tools_config = &engine_view.config.pens_config.tools_config;
+        [
+            tools_config
+                .show_ruler
+                .then(|| self.ruler.bounds(engine_view.camera)),
+            tools_config
+                .show_protractor
+                .then(|| self.protractor.bounds(engine_view.camera)),
+            tools_config
+                .show_setsquare
+                .then(|| self.setsquare.bounds(engine_view.camera)),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(|acc, bounds| acc.merged(&bounds))
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        let tools_config = &engine_view.config.pens_config.tools_config;
+        let dpi = engine_view.document.config.format.dpi();
+        if tools_config.show_ruler {
+            self.ruler.draw(cx, engine_view.camera, dpi)?;
+        }
+        if tools_config.show_setsquare {
+            self.setsquare.draw(cx, engine_view.camera, dpi)?;
+        }
+        if tools_config.show_protractor {
+            self.protractor.draw(cx, engine_view.camera, dpi)?;
+        }
+        Ok(())
+    }
+}
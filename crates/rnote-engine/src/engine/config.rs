@@ -20,6 +20,12 @@ pub struct EngineConfig {
     pub optimize_epd: bool,
     #[serde(rename = "snap_positions")]
     pub snap_positions: bool,
+    /// Whether positions are snapped to the bounds and shape control points of other strokes.
+    #[serde(rename = "snap_to_strokes")]
+    pub snap_to_strokes: bool,
+    /// Whether the recent undo history is saved in .rnote files.
+    #[serde(rename = "save_history")]
+    pub save_history: bool,
     #[serde(skip)]
     pub visual_debug: bool,
 }
@@ -56,6 +62,8 @@ impl EngineConfigShared {
         write.pen_sounds = config.pen_sounds;
         write.optimize_epd = config.optimize_epd;
         write.snap_positions = config.snap_positions;
+        write.snap_to_strokes = config.snap_to_strokes;
+        write.save_history = config.save_history;
         write.visual_debug = config.visual_debug;
     }
 }
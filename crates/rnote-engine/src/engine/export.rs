@@ -1,13 +1,22 @@
 // Imports
 use super::{Engine, StrokeContent};
+use crate::document::SourcePdfPage;
+use crate::fileformats::pdfoverlay::PdfOverlay;
 use crate::fileformats::rnoteformat::RnoteFile;
 use crate::fileformats::{FileFormatSaver, xoppformat};
+use crate::store::StrokeKey;
+use crate::strokes::textstroke::{TextAlignment, TextStyle};
+use crate::strokes::{Stroke, TextStroke};
 use anyhow::Context;
 use futures::channel::oneshot;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use p2d::query::PointQuery;
 use rayon::prelude::*;
 use rnote_compose::SplitOrder;
+use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::io::Cursor;
 use std::sync::Arc;
 use tracing::error;
@@ -35,6 +44,9 @@ pub enum DocExportFormat {
     Pdf,
     #[serde(rename = "xopp")]
     Xopp,
+    /// The source Pdf the document was imported from, with the strokes overlaid onto its pages.
+    #[serde(rename = "annotated_pdf")]
+    AnnotatedPdf,
 }
 
 impl Default for DocExportFormat {
@@ -63,6 +75,7 @@ impl DocExportFormat {
             DocExportFormat::Svg => String::from("svg"),
             DocExportFormat::Pdf => String::from("pdf"),
             DocExportFormat::Xopp => String::from("xopp"),
+            DocExportFormat::AnnotatedPdf => String::from("pdf"),
         }
     }
 }
@@ -86,6 +99,11 @@ pub struct DocExportPrefs {
     /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into pages.
     #[serde(rename = "page_order")]
     pub page_order: SplitOrder,
+    /// Whether page titles and section headings should be written as outline and page labels.
+    ///
+    /// Only applies to the Pdf export format.
+    #[serde(rename = "with_outline")]
+    pub with_outline: bool,
 }
 
 impl Default for DocExportPrefs {
@@ -96,6 +114,7 @@ impl Default for DocExportPrefs {
             optimize_printing: false,
             export_format: DocExportFormat::default(),
             page_order: SplitOrder::default(),
+            with_outline: true,
         }
     }
 }
@@ -312,6 +331,116 @@ pub struct ExportPrefs {
     pub selection_export_prefs: SelectionExportPrefs,
 }
 
+/// A selection of the exported pages, parsed from a comma separated list of page numbers and ranges,
+/// for example `1,3-5,8-`.
+///
+/// Page numbers start with 1 and ranges without an end extend to the last page.
+/// The selected pages are always exported in the order of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSelection {
+    /// The selected ranges of page indices, with an inclusive end.
+    ranges: Vec<(usize, Option<usize>)>,
+}
+
+impl PageSelection {
+    /// Whether the page with the given index is selected.
+    pub fn contains(&self, page_i: usize) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(start, end)| page_i >= start && end.is_none_or(|end| page_i <= end))
+    }
+
+    /// The indices of the selected pages, out of `n_pages` pages.
+    pub fn indices(&self, n_pages: usize) -> Vec<usize> {
+        (0..n_pages)
+            .filter(|&page_i| self.contains(page_i))
+            .collect()
+    }
+
+    /// Keeps only the selected pages.
+    ///
+    /// Returns an error if none of the pages are selected.
+    pub fn select<T>(&self, pages: Vec<T>) -> anyhow::Result<Vec<T>> {
+        let n_pages = pages.len();
+        let selected = pages
+            .into_iter()
+            .enumerate()
+            .filter_map(|(page_i, page)| self.contains(page_i).then_some(page))
+            .collect::<Vec<T>>();
+        if selected.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The page selection \"{self}\" contains none of the {n_pages} exported pages."
+            ));
+        }
+        Ok(selected)
+    }
+}
+
+impl std::str::FromStr for PageSelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_page_nr = |page_nr: &str| -> anyhow::Result<usize> {
+            page_nr
+                .trim()
+                .parse::<usize>()
+                .map_err(|e| anyhow::anyhow!("Invalid page number \"{page_nr}\", Err: {e}"))?
+                .checked_sub(1)
+                .ok_or_else(|| anyhow::anyhow!("Page numbers start with 1."))
+        };
+        let ranges = s
+            .split(',')
+            .map(|part| match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = parse_page_nr(start)?;
+                    let end = if end.trim().is_empty() {
+                        None
+                    } else {
+                        Some(parse_page_nr(end)?)
+                    };
+                    if end.is_some_and(|end| end < start) {
+                        return Err(anyhow::anyhow!(
+                            "The end of the page range \"{part}\" is before its start."
+                        ));
+                    }
+                    Ok((start, end))
+                }
+                None => {
+                    let page_i = parse_page_nr(part)?;
+                    Ok((page_i, Some(page_i)))
+                }
+            })
+            .collect::<anyhow::Result<Vec<(usize, Option<usize>)>>>()?;
+        Ok(Self { ranges })
+    }
+}
+
+impl std::fmt::Display for PageSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|&(start, end)| match end {
+                Some(end) if end == start => format!("{}", start + 1),
+                Some(end) => format!("{}-{}", start + 1, end + 1),
+                None => format!("{}-", start + 1),
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", ranges.join(","))
+    }
+}
+
+/// Keeps only the selected pages, if there is a page selection.
+fn select_pages<T>(
+    pages: Vec<T>,
+    page_selection: Option<&PageSelection>,
+) -> anyhow::Result<Vec<T>> {
+    match page_selection {
+        Some(page_selection) => page_selection.select(pages),
+        None => Ok(pages),
+    }
+}
+
 impl Engine {
     /// The used image scale-factor for any strokes that are converted to bitmap images on export.
     pub const STROKE_EXPORT_IMAGE_SCALE: f64 = 1.8;
@@ -353,9 +482,9 @@ impl Engine {
     }
 
     pub fn extract_pages_content(&self, page_order: SplitOrder) -> Vec<StrokeContent> {
-        self.pages_bounds_w_content(page_order)
+        self.pages_w_content(page_order)
             .into_iter()
-            .map(|bounds| {
+            .map(|(bounds, background)| {
                 StrokeContent::default()
                     .with_strokes(
                         self.store.get_strokes_arc(
@@ -365,7 +494,134 @@ impl Engine {
                         ),
                     )
                     .with_bounds(bounds)
-                    .with_background(self.document.config.background)
+                    .with_background(background)
+            })
+            .collect()
+    }
+
+    /// Extract the content of the pages that are exported as Pdf together with their titles,
+    /// restricted to the selected pages.
+    pub fn extract_pdf_pages(
+        &self,
+        page_order: SplitOrder,
+        page_selection: Option<&PageSelection>,
+    ) -> anyhow::Result<Vec<(StrokeContent, Option<String>)>> {
+        let pages_content = self.extract_pages_content(page_order);
+        let pages_titles = self.pages_content_titles(&pages_content);
+        select_pages(
+            pages_content.into_iter().zip(pages_titles).collect(),
+            page_selection,
+        )
+    }
+
+    /// Extract the content of the area that is spanned by the selected pages.
+    fn extract_selected_pages_area_content(
+        &self,
+        page_order: SplitOrder,
+        page_selection: &PageSelection,
+    ) -> anyhow::Result<StrokeContent> {
+        let bounds = page_selection
+            .select(self.pages_bounds_w_content(page_order))?
+            .into_iter()
+            .reduce(|acc, bounds| acc.merged(&bounds))
+            .ok_or_else(|| anyhow::anyhow!("No pages selected."))?;
+        Ok(StrokeContent::default()
+            .with_strokes(
+                self.store.get_strokes_arc(
+                    &self
+                        .store
+                        .stroke_keys_as_rendered_intersecting_bounds(bounds),
+                ),
+            )
+            .with_bounds(bounds)
+            .with_background(self.document.config.background))
+    }
+
+    /// Extract a page with the format and background of the first document page, showing the title in large text.
+    ///
+    /// Without a title the page is blank. Used as separator pages when concatenating documents.
+    pub fn extract_title_page_content(&self, title: Option<&str>) -> StrokeContent {
+        let page_size = self.document.page_format(0).size();
+        let bounds = Aabb::new(na::point![0.0, 0.0], page_size.into());
+        let margin = page_size[0] * 0.1;
+        let strokes = title
+            .map(|title| {
+                let mut text_style = TextStyle {
+                    font_size: TextStyle::FONT_SIZE_DEFAULT * 2.0,
+                    alignment: TextAlignment::Center,
+                    ..Default::default()
+                };
+                text_style.set_max_width(Some(page_size[0] - 2.0 * margin));
+                Arc::new(Stroke::TextStroke(TextStroke::new(
+                    title.to_string(),
+                    na::vector![margin, page_size[1] / 3.0],
+                    text_style,
+                )))
+            })
+            .into_iter()
+            .collect();
+        StrokeContent::default()
+            .with_strokes(strokes)
+            .with_bounds(bounds)
+            .with_background(self.document.page_background(0))
+    }
+
+    /// The titles of the exported pages, looked up by matching their bounds with the pages of the document page list.
+    fn pages_content_titles(&self, pages_content: &[StrokeContent]) -> Vec<Option<String>> {
+        if !self.document.config.layout.has_pages() {
+            return vec![None; pages_content.len()];
+        }
+        let pages_bounds = self.document.pages_arranged_bounds();
+        pages_content
+            .iter()
+            .map(|page_content| {
+                let bounds = page_content.bounds?;
+                let page_i = pages_bounds.iter().position(|page_bounds| {
+                    (page_bounds.mins - bounds.mins).norm() < PAGE_BOUNDS_MATCH_TOLERANCE
+                })?;
+                self.document.page_title(page_i).map(String::from)
+            })
+            .collect()
+    }
+
+    /// Extract the content on top of each imported page of a source Pdf, ordered by the position of the pages.
+    ///
+    /// Pages that are present multiple times only are extracted once.
+    fn extract_source_pdf_pages_content(&self) -> Vec<(SourcePdfPage, StrokeContent)> {
+        let mut source_pdf_pages = self
+            .store
+            .stroke_keys_as_rendered()
+            .into_iter()
+            .filter_map(|key| {
+                let stroke = self.store.get_stroke_ref(key)?;
+                Some((stroke.source_pdf_page()?, stroke.bounds()))
+            })
+            .collect::<Vec<(SourcePdfPage, Aabb)>>();
+        source_pdf_pages.sort_by(|(_, first), (_, second)| {
+            first.mins[1]
+                .total_cmp(&second.mins[1])
+                .then(first.mins[0].total_cmp(&second.mins[0]))
+        });
+
+        let mut extracted = HashSet::new();
+        source_pdf_pages
+            .into_iter()
+            .filter(|(source_pdf_page, _)| extracted.insert(*source_pdf_page))
+            .map(|(source_pdf_page, bounds)| {
+                let keys = self
+                    .store
+                    .stroke_keys_as_rendered_intersecting_bounds(bounds)
+                    .into_iter()
+                    .filter(|&key| {
+                        self.store
+                            .get_stroke_ref(key)
+                            .is_some_and(|stroke| stroke.source_pdf_page().is_none())
+                    })
+                    .collect::<Vec<StrokeKey>>();
+                let content = StrokeContent::default()
+                    .with_strokes(self.store.get_strokes_arc(&keys))
+                    .with_bounds(bounds);
+                (source_pdf_page, content)
             })
             .collect()
     }
@@ -408,15 +664,35 @@ impl Engine {
         &self,
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
+        self.export_doc_w_page_selection(title, doc_export_prefs_override, None)
+    }
+
+    /// Export the selected pages of the document.
+    ///
+    /// When exporting as Svg, the area that is spanned by the selected pages is exported.
+    /// The annotated Pdf format does not support a page selection, because it keeps all pages of the source Pdf.
+    pub fn export_doc_w_page_selection(
+        &self,
+        title: String,
+        doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
 
         match doc_export_prefs.export_format {
-            DocExportFormat::Svg => self.export_doc_as_svg_bytes(doc_export_prefs_override),
-            DocExportFormat::Pdf => self.export_doc_as_pdf_bytes(title, doc_export_prefs_override),
+            DocExportFormat::Svg => {
+                self.export_doc_as_svg_bytes(doc_export_prefs_override, page_selection)
+            }
+            DocExportFormat::Pdf => {
+                self.export_doc_as_pdf_bytes(title, doc_export_prefs_override, page_selection)
+            }
             DocExportFormat::Xopp => {
-                self.export_doc_as_xopp_bytes(title, doc_export_prefs_override)
+                self.export_doc_as_xopp_bytes(title, doc_export_prefs_override, page_selection)
+            }
+            DocExportFormat::AnnotatedPdf => {
+                self.export_doc_as_annotated_pdf_bytes(doc_export_prefs_override, page_selection)
             }
         }
     }
@@ -425,15 +701,20 @@ impl Engine {
     fn export_doc_as_svg_bytes(
         &self,
         doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-        let doc_content = self.extract_document_content();
+        let doc_content = match page_selection {
+            Some(page_selection) => self
+                .extract_selected_pages_area_content(doc_export_prefs.page_order, page_selection),
+            None => Ok(self.extract_document_content()),
+        };
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
-                let doc_svg = doc_content
+                let doc_svg = doc_content?
                     .gen_svg(
                         doc_export_prefs.with_background,
                         doc_export_prefs.with_pattern,
@@ -464,61 +745,156 @@ impl Engine {
     }
 
     /// Export the doc with the strokes as Pdf.
+    ///
+    /// Text strokes are drawn through pango, so they end up as real text with embedded fonts
+    /// that can be selected and searched in the exported document.
     fn export_doc_as_pdf_bytes(
         &self,
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
-        let format_size = self.document.config.format.size();
+        let pages = self.extract_pdf_pages(doc_export_prefs.page_order, page_selection);
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
-                let target_surface =
-                    cairo::PdfSurface::for_stream(format_size[0], format_size[1], Vec::<u8>::new())
-                        .context("Creating Pdf target surface failed.")?;
-
-                target_surface
-                    .set_metadata(cairo::PdfMetadata::Title, title.as_str())
-                    .context("Set pdf surface title metadata failed.")?;
-                target_surface
-                    .set_metadata(
-                        cairo::PdfMetadata::CreateDate,
-                        crate::utils::now_formatted_string().as_str(),
-                    )
-                    .context("Set pdf surface date metadata failed.")?;
+                pdf_pages_to_bytes(&title, pages?, doc_export_prefs)
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting document as Pdf bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the given pages as Pdf, for example to concatenate the pages of several documents into a single Pdf.
+    ///
+    /// The pages can be extracted with [`Engine::extract_pdf_pages()`] and [`Engine::extract_title_page_content()`].
+    pub fn export_pdf_pages(
+        title: String,
+        pages: Vec<(StrokeContent, Option<String>)>,
+        doc_export_prefs: DocExportPrefs,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                pdf_pages_to_bytes(&title, pages, doc_export_prefs)
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while exporting pages as Pdf bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Export the doc with the strokes overlaid onto the pages of the source Pdf it was imported from.
+    ///
+    /// The original Pdf is kept intact, including its text layer, links and forms, so it stays searchable.
+    /// Only the strokes on top of imported Pdf pages are exported. Documents with pages of several imported Pdfs
+    /// can't be exported as annotated Pdf.
+    fn export_doc_as_annotated_pdf_bytes(
+        &self,
+        doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<&PageSelection>,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let doc_export_prefs =
+            doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
+        let source_pdf_pages_content = self.extract_source_pdf_pages_content();
+        let mut source_pdf_ids = source_pdf_pages_content
+            .iter()
+            .map(|(source_pdf_page, _)| source_pdf_page.pdf_id)
+            .collect::<Vec<u32>>();
+        source_pdf_ids.sort_unstable();
+        source_pdf_ids.dedup();
+        let source_pdf = source_pdf_ids
+            .first()
+            .and_then(|pdf_id| self.document.source_pdf(*pdf_id))
+            .cloned();
+        let n_source_pdfs = source_pdf_ids.len();
+        let with_page_selection = page_selection.is_some();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                if with_page_selection {
+                    return Err(anyhow::anyhow!(
+                        "Exporting as annotated Pdf does not support a page selection, all pages of the source Pdf are kept."
+                    ));
+                }
+                if n_source_pdfs > 1 {
+                    return Err(anyhow::anyhow!(
+                        "The document contains pages of {n_source_pdfs} different imported Pdfs, but exporting as annotated Pdf supports only a single source Pdf."
+                    ));
+                }
+                let Some(source_pdf) = source_pdf else {
+                    return Err(anyhow::anyhow!(
+                        "The document has no imported Pdf pages that the strokes could be overlaid onto."
+                    ));
+                };
+                let pdf_overlay = PdfOverlay::load_from_bytes(&source_pdf.data)?;
+                let target_surface = cairo::PdfSurface::for_stream(1.0, 1.0, Vec::<u8>::new())
+                    .context("Creating Pdf target surface failed.")?;
+                let mut overlaid_pages = vec![];
 
                 // New scope to avoid errors when flushing
                 {
                     let cairo_cx = cairo::Context::new(&target_surface)
                         .context("Creating new cairo context for pdf target surface failed.")?;
 
-                    for (i, page_content) in pages_content.into_iter().enumerate() {
-                        let Some(page_bounds) = page_content.bounds() else {
+                    for (source_pdf_page, page_content) in source_pdf_pages_content {
+                        if page_content.strokes.is_empty() {
+                            continue;
+                        }
+                        let (Some(page_bounds), Some(page_size)) = (
+                            page_content.bounds(),
+                            pdf_overlay.page_size(source_pdf_page.page_i),
+                        ) else {
                             continue;
                         };
+                        let page_i = source_pdf_page.page_i;
+                        target_surface
+                            .set_size(page_size[0], page_size[1])
+                            .map_err(|e| {
+                                anyhow::anyhow!(
+                                    "Setting the size of the overlay for page {page_i} failed while exporting as annotated pdf, Err: {e:?}"
+                                )
+                            })?;
                         cairo_cx.save()?;
+                        cairo_cx.scale(
+                            page_size[0] / page_bounds.extents()[0],
+                            page_size[1] / page_bounds.extents()[1],
+                        );
                         cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
                         page_content.draw_to_cairo(
                             &cairo_cx,
-                            doc_export_prefs.with_background,
-                            doc_export_prefs.with_pattern,
+                            false,
+                            false,
                             doc_export_prefs.optimize_printing,
                             DocExportPrefs::MARGIN,
                             Engine::STROKE_EXPORT_IMAGE_SCALE,
                         )?;
                         cairo_cx.show_page().map_err(|e| {
                             anyhow::anyhow!(
-                                "Showing page failed while exporting page {i} as pdf, Err: {e:?}"
+                                "Showing the overlay for page {page_i} failed while exporting as annotated pdf, Err: {e:?}"
                             )
                         })?;
                         cairo_cx.restore()?;
+                        overlaid_pages.push(page_i);
                     }
                 }
-                let data = *target_surface
+                let overlay_data = *target_surface
                     .finish_output_stream()
                     .map_err(|e| anyhow::anyhow!("Finishing outputstream failed, Err: {e:?}"))?
                     .downcast::<Vec<u8>>()
@@ -526,12 +902,15 @@ impl Engine {
                         anyhow::anyhow!("Downcasting finished output stream failed, Err: {e:?}")
                     })?;
 
-                Ok(data)
+                if overlaid_pages.is_empty() {
+                    return Ok(source_pdf.data.to_vec());
+                }
+                pdf_overlay.overlay_pages(&overlay_data, &overlaid_pages)
             };
 
             if oneshot_sender.send(result()).is_err() {
                 error!(
-                    "Sending result to receiver failed while exporting document as Pdf bytes. Receiver already dropped."
+                    "Sending result to receiver failed while exporting document as annotated Pdf bytes. Receiver already dropped."
                 );
             }
         });
@@ -544,92 +923,118 @@ impl Engine {
         &self,
         title: String,
         doc_export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.config.read().export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        // Extract the content of the pages together with the user layer positions of their strokes,
+        // so that the strokes can be exported into the matching xopp layers.
+        let pages_content = select_pages(
+            self.pages_w_content(doc_export_prefs.page_order),
+            page_selection,
+        )
+        .map(|pages| {
+            pages
+                .into_iter()
+                .map(|(bounds, background)| {
+                    let keys = self
+                        .store
+                        .stroke_keys_as_rendered_intersecting_bounds(bounds);
+                    let layer_positions = keys
+                        .iter()
+                        .map(|&key| self.store.user_layer_position(key))
+                        .collect::<Vec<Option<usize>>>();
+                    (
+                        StrokeContent::default()
+                            .with_strokes(self.store.get_strokes_arc(&keys))
+                            .with_bounds(bounds)
+                            .with_background(background),
+                        layer_positions,
+                    )
+                })
+                .collect::<Vec<(StrokeContent, Vec<Option<usize>>)>>()
+        });
+        let layer_names = self
+            .store
+            .user_layers()
+            .iter()
+            .map(|layer| layer.name.clone())
+            .collect::<Vec<String>>();
         let document = self.document.clone();
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
-                // Only one background for all pages
-                let xopp_background = xoppformat::XoppBackground {
-                    name: None,
-                    bg_type: xoppformat::XoppBackgroundType::Solid {
-                        color: crate::utils::xoppcolor_from_color(document.config.background.color),
-                        style: xoppformat::XoppBackgroundSolidStyle::Plain,
-                    },
-                };
-
                 // xopp spec needs at least one page in vec,
-                // but it is fine because pages_bounds_w_content() always produces at least one.
-                let pages = pages_content
+                // but it is fine because pages_bounds_w_content() always produces at least one
+                // and a page selection must contain at least one of them.
+                let pages = pages_content?
                     .into_iter()
-                    .filter_map(|page_content| {
+                    .filter_map(|(page_content, layer_positions)| {
                         let page_bounds = page_content.bounds()?;
+                        let xopp_background = xoppformat::XoppBackground {
+                            name: None,
+                            bg_type: xoppformat::XoppBackgroundType::Solid {
+                                color: crate::utils::xoppcolor_from_color(
+                                    page_content
+                                        .background
+                                        .unwrap_or(document.config.background)
+                                        .color,
+                                ),
+                                style: xoppformat::XoppBackgroundSolidStyle::Plain,
+                            },
+                        };
                         // Translate strokes to to page mins and convert to XoppStrokStyle
                         let xopp_strokestyles = page_content
                             .strokes
                             .into_iter()
-                            .filter_map(|mut stroke| {
+                            .zip(layer_positions)
+                            .filter_map(|(mut stroke, layer_position)| {
                                 let mut stroke = Arc::make_mut(&mut stroke).clone();
                                 stroke.translate(-page_bounds.mins.coords);
-                                stroke.into_xopp(document.config.format.dpi())
-                            })
-                            .collect::<Vec<xoppformat::XoppStrokeType>>();
-
-                        // Extract the strokes
-                        let xopp_strokes = xopp_strokestyles
-                            .iter()
-                            .filter_map(|stroke| {
-                                if let xoppformat::XoppStrokeType::XoppStroke(xoppstroke) = stroke {
-                                    Some(xoppstroke.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect::<Vec<xoppformat::XoppStroke>>();
-
-                        // Extract the texts
-                        let xopp_texts = xopp_strokestyles
-                            .iter()
-                            .filter_map(|stroke| {
-                                if let xoppformat::XoppStrokeType::XoppText(xopptext) = stroke {
-                                    Some(xopptext.clone())
-                                } else {
-                                    None
-                                }
+                                Some((
+                                    layer_position,
+                                    stroke.into_xopp(document.config.format.dpi())?,
+                                ))
                             })
-                            .collect::<Vec<xoppformat::XoppText>>();
-
-                        // Extract the images
-                        let xopp_images = xopp_strokestyles
-                            .iter()
-                            .filter_map(|stroke| {
-                                if let xoppformat::XoppStrokeType::XoppImage(xoppstroke) = stroke {
-                                    Some(xoppstroke.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect::<Vec<xoppformat::XoppImage>>();
+                            .collect::<Vec<(Option<usize>, xoppformat::XoppStrokeType)>>();
 
                         // In Rnote images are always rendered below strokes and text.
                         // To match this behaviour accurately, images are separated into another layer.
-                        let image_layer = xoppformat::XoppLayer {
+                        // It is followed by one layer for every user layer.
+                        let mut image_layer = xoppformat::XoppLayer {
                             name: None,
                             strokes: vec![],
                             texts: vec![],
-                            images: xopp_images,
-                        };
-
-                        let strokes_layer = xoppformat::XoppLayer {
-                            name: None,
-                            strokes: xopp_strokes,
-                            texts: xopp_texts,
                             images: vec![],
                         };
+                        let mut user_layers = layer_names
+                            .iter()
+                            .map(|name| xoppformat::XoppLayer {
+                                name: Some(name.clone()),
+                                strokes: vec![],
+                                texts: vec![],
+                                images: vec![],
+                            })
+                            .collect::<Vec<xoppformat::XoppLayer>>();
+
+                        for (layer_position, xopp_strokestyle) in xopp_strokestyles {
+                            let xopp_layer = layer_position
+                                .and_then(|position| user_layers.get_mut(position))
+                                .unwrap_or(&mut image_layer);
+
+                            match xopp_strokestyle {
+                                xoppformat::XoppStrokeType::XoppStroke(xoppstroke) => {
+                                    xopp_layer.strokes.push(xoppstroke)
+                                }
+                                xoppformat::XoppStrokeType::XoppText(xopptext) => {
+                                    xopp_layer.texts.push(xopptext)
+                                }
+                                xoppformat::XoppStrokeType::XoppImage(xoppimage) => {
+                                    xopp_layer.images.push(xoppimage)
+                                }
+                            }
+                        }
 
                         let page_dimensions = crate::utils::convert_coord_dpi(
                             page_bounds.extents(),
@@ -640,8 +1045,8 @@ impl Engine {
                         Some(xoppformat::XoppPage {
                             width: page_dimensions[0],
                             height: page_dimensions[1],
-                            background: xopp_background.clone(),
-                            layers: vec![image_layer, strokes_layer],
+                            background: xopp_background,
+                            layers: std::iter::once(image_layer).chain(user_layers).collect(),
                         })
                     })
                     .collect::<Vec<xoppformat::XoppPage>>();
@@ -675,17 +1080,27 @@ impl Engine {
     pub fn export_doc_pages(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
+        self.export_doc_pages_w_page_selection(doc_pages_export_prefs_override, None)
+    }
+
+    /// Export the selected document pages.
+    ///
+    /// Only the selected pages are returned, [`PageSelection::indices()`] gives their indices.
+    pub fn export_doc_pages_w_page_selection(
+        &self,
+        doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let doc_pages_export_prefs = doc_pages_export_prefs_override
             .unwrap_or(self.config.read().export_prefs.doc_pages_export_prefs);
 
         match doc_pages_export_prefs.export_format {
             DocPagesExportFormat::Svg => {
-                self.export_doc_pages_as_svgs_bytes(doc_pages_export_prefs_override)
-            }
-            DocPagesExportFormat::Png | DocPagesExportFormat::Jpeg => {
-                self.export_doc_pages_as_bitmap_bytes(doc_pages_export_prefs_override)
+                self.export_doc_pages_as_svgs_bytes(doc_pages_export_prefs_override, page_selection)
             }
+            DocPagesExportFormat::Png | DocPagesExportFormat::Jpeg => self
+                .export_doc_pages_as_bitmap_bytes(doc_pages_export_prefs_override, page_selection),
         }
     }
 
@@ -693,15 +1108,19 @@ impl Engine {
     fn export_doc_pages_as_svgs_bytes(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
         let doc_pages_export_prefs = doc_pages_export_prefs_override
             .unwrap_or(self.config.read().export_prefs.doc_pages_export_prefs);
-        let pages_content = self.extract_pages_content(doc_pages_export_prefs.page_order);
+        let pages_content = select_pages(
+            self.extract_pages_content(doc_pages_export_prefs.page_order),
+            page_selection,
+        );
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<Vec<u8>>> {
-                pages_content
+                pages_content?
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
@@ -745,11 +1164,15 @@ impl Engine {
     fn export_doc_pages_as_bitmap_bytes(
         &self,
         doc_pages_export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Vec<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<Vec<u8>>>>();
         let doc_pages_export_prefs = doc_pages_export_prefs_override
             .unwrap_or(self.config.read().export_prefs.doc_pages_export_prefs);
-        let pages_contents = self.extract_pages_content(doc_pages_export_prefs.page_order);
+        let pages_contents = select_pages(
+            self.extract_pages_content(doc_pages_export_prefs.page_order),
+            page_selection,
+        );
 
         rayon::spawn(move || {
             let result = || -> Result<Vec<Vec<u8>>, anyhow::Error> {
@@ -762,7 +1185,7 @@ impl Engine {
                     DocPagesExportFormat::Png => image::ImageFormat::Png,
                     DocPagesExportFormat::Jpeg => image::ImageFormat::Jpeg,
                 };
-                pages_contents
+                pages_contents?
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
@@ -920,10 +1343,12 @@ impl Engine {
     ///
     /// # Arguments
     /// * `size`: the size (width/height) of the thumbnail in px.
+    /// * `page_selection`: if set, the thumbnail shows the area that is spanned by the selected pages.
     pub fn generate_thumbnail(
         &self,
         size: u32,
         export_format: SelectionExportFormat,
+        page_selection: Option<&PageSelection>,
     ) -> oneshot::Receiver<Result<Option<Vec<u8>>, anyhow::Error>> {
         let (oneshot_sender, oneshot_receiver) =
             oneshot::channel::<anyhow::Result<Option<Vec<u8>>>>();
@@ -932,10 +1357,15 @@ impl Engine {
             ..Default::default()
         };
 
-        let content = self.extract_thumbnail_content(na::vector![size as f64, size as f64]);
+        let content = match page_selection {
+            Some(page_selection) => {
+                self.extract_selected_pages_area_content(SplitOrder::default(), page_selection)
+            }
+            None => Ok(self.extract_thumbnail_content(na::vector![size as f64, size as f64])),
+        };
         rayon::spawn(move || {
             let result = || -> Result<Option<Vec<u8>>, anyhow::Error> {
-                let svg = content
+                let svg = content?
                     .gen_svg(
                         selection_export_prefs.with_background,
                         selection_export_prefs.with_pattern,
@@ -986,3 +1416,357 @@ impl Engine {
         oneshot_receiver
     }
 }
+
+/// Draws the pages with their titles onto a new Pdf surface and returns the Pdf bytes.
+///
+/// When enabled in the export prefs, page titles are written as page labels and bookmarks,
+/// with the section headings of the page nested below them.
+fn pdf_pages_to_bytes(
+    title: &str,
+    pages: Vec<(StrokeContent, Option<String>)>,
+    doc_export_prefs: DocExportPrefs,
+) -> anyhow::Result<Vec<u8>> {
+    // The size is set for every page before drawing on it
+    let format_size = pages
+        .first()
+        .and_then(|(page_content, _)| page_content.size())
+        .unwrap_or_else(|| na::vector![1.0, 1.0]);
+    // Page labels are only set when at least one page has a title,
+    // so that viewers keep displaying their default labels otherwise
+    let with_page_labels =
+        doc_export_prefs.with_outline && pages.iter().any(|(_, title)| title.is_some());
+    let target_surface =
+        cairo::PdfSurface::for_stream(format_size[0], format_size[1], Vec::<u8>::new())
+            .context("Creating Pdf target surface failed.")?;
+
+    target_surface
+        .set_metadata(cairo::PdfMetadata::Title, title)
+        .context("Set pdf surface title metadata failed.")?;
+    target_surface
+        .set_metadata(
+            cairo::PdfMetadata::CreateDate,
+            crate::utils::now_formatted_string().as_str(),
+        )
+        .context("Set pdf surface date metadata failed.")?;
+
+    // New scope to avoid errors when flushing
+    {
+        let cairo_cx = cairo::Context::new(&target_surface)
+            .context("Creating new cairo context for pdf target surface failed.")?;
+
+        for (i, (page_content, page_title)) in pages.into_iter().enumerate() {
+            let Some(page_bounds) = page_content.bounds() else {
+                continue;
+            };
+            // Pages can have different sizes, the size must be set before drawing on the page
+            target_surface
+                .set_size(page_bounds.extents()[0], page_bounds.extents()[1])
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Setting the size of page {i} failed while exporting as pdf, Err: {e:?}"
+                    )
+                })?;
+            if doc_export_prefs.with_outline {
+                write_pdf_page_outline(
+                    &target_surface,
+                    i,
+                    page_title.as_deref(),
+                    with_page_labels,
+                    &page_content,
+                    page_bounds,
+                )?;
+            }
+            cairo_cx.save()?;
+            cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
+            page_content.draw_to_cairo(
+                &cairo_cx,
+                doc_export_prefs.with_background,
+                doc_export_prefs.with_pattern,
+                doc_export_prefs.optimize_printing,
+                DocExportPrefs::MARGIN,
+                Engine::STROKE_EXPORT_IMAGE_SCALE,
+            )?;
+            cairo_cx.show_page().map_err(|e| {
+                anyhow::anyhow!("Showing page failed while exporting page {i} as pdf, Err: {e:?}")
+            })?;
+            cairo_cx.restore()?;
+        }
+    }
+    let data = *target_surface
+        .finish_output_stream()
+        .map_err(|e| anyhow::anyhow!("Finishing outputstream failed, Err: {e:?}"))?
+        .downcast::<Vec<u8>>()
+        .map_err(|e| anyhow::anyhow!("Downcasting finished output stream failed, Err: {e:?}"))?;
+
+    Ok(data)
+}
+
+/// Tolerance when matching the bounds of exported pages with the pages of the document page list.
+const PAGE_BOUNDS_MATCH_TOLERANCE: f64 = 1e-3;
+
+/// Writes the page label and the outline items of the page with index `page_i` to the Pdf surface.
+///
+/// Must be called before the page is shown.
+fn write_pdf_page_outline(
+    target_surface: &cairo::PdfSurface,
+    page_i: usize,
+    page_title: Option<&str>,
+    with_page_label: bool,
+    page_content: &StrokeContent,
+    page_bounds: Aabb,
+) -> anyhow::Result<()> {
+    // Cairo can't handle interior nul bytes
+    let page_title = page_title.map(|title| title.replace('\0', ""));
+    let page_nr = page_i + 1;
+
+    if with_page_label {
+        target_surface
+            .set_page_label(
+                page_title
+                    .clone()
+                    .unwrap_or_else(|| page_nr.to_string())
+                    .as_str(),
+            )
+            .map_err(|e| {
+                anyhow::anyhow!("Setting the label of page {page_i} failed, Err: {e:?}")
+            })?;
+    }
+    let parent_id = match page_title {
+        Some(page_title) => target_surface
+            .add_outline(
+                cairo::PDF_OUTLINE_ROOT,
+                &page_title,
+                &format!("page={page_nr}"),
+                cairo::PdfOutline::empty(),
+            )
+            .map_err(|e| {
+                anyhow::anyhow!("Adding the outline item of page {page_i} failed, Err: {e:?}")
+            })?,
+        None => cairo::PDF_OUTLINE_ROOT,
+    };
+    for (heading, pos) in page_section_headings(page_content, page_bounds) {
+        target_surface
+            .add_outline(
+                parent_id,
+                &heading,
+                &format!("page={page_nr} pos=[{:.3} {:.3}]", pos[0], pos[1]),
+                cairo::PdfOutline::empty(),
+            )
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Adding the outline item of a section heading on page {page_i} failed, Err: {e:?}"
+                )
+            })?;
+    }
+    Ok(())
+}
+
+/// The section headings that start on the page, ordered from top to bottom.
+///
+/// Returns the first non-empty line of each heading together with its position relative to the page.
+fn page_section_headings(
+    page_content: &StrokeContent,
+    page_bounds: Aabb,
+) -> Vec<(String, na::Vector2<f64>)> {
+    let mut headings = page_content
+        .strokes
+        .iter()
+        .filter_map(|stroke| {
+            let Stroke::TextStroke(textstroke) = stroke.as_ref() else {
+                return None;
+            };
+            if !textstroke.text_style.section_heading {
+                return None;
+            }
+            let bounds = stroke.bounds();
+            if !page_bounds.contains_local_point(&bounds.mins) {
+                return None;
+            }
+            let heading = textstroke
+                .text
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())?
+                .replace('\0', "");
+            Some((heading, bounds.mins - page_bounds.mins))
+        })
+        .collect::<Vec<(String, na::Vector2<f64>)>>();
+    headings.sort_by(|(_, first), (_, second)| first[1].total_cmp(&second[1]));
+    headings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageSelection;
+    use crate::Engine;
+    use crate::strokes::textstroke::{RangedTextAttribute, TextAttribute, TextStyle};
+    use crate::strokes::{Stroke, TextStroke};
+    use hayro::hayro_interpret::font::Glyph;
+    use hayro::hayro_interpret::{
+        BlendMode, ClipPath, Context, Device, GlyphDrawMode, Image, InterpreterSettings, Paint,
+        PathDrawMode, SoftMask, interpret_page,
+    };
+    use hayro::hayro_syntax::Pdf;
+    use kurbo::{Affine, BezPath, Point};
+    use std::sync::Arc;
+
+    /// A device that collects the unicode text and the positions of the drawn glyphs and counts the drawn paths.
+    #[derive(Default)]
+    struct TextExtractor {
+        text: String,
+        /// The x-coordinate of the origin of each glyph with a unicode mapping.
+        glyphs_x: Vec<f64>,
+        n_paths: usize,
+    }
+
+    impl<'a> Device<'a> for TextExtractor {
+        fn set_soft_mask(&mut self, _mask: Option<SoftMask<'a>>) {}
+        fn draw_path(
+            &mut self,
+            _path: &BezPath,
+            _transform: Affine,
+            _paint: &Paint<'a>,
+            _draw_mode: &PathDrawMode,
+        ) {
+            self.n_paths += 1;
+        }
+        fn push_clip_path(&mut self, _clip_path: &ClipPath) {}
+        fn push_transparency_group(
+            &mut self,
+            _opacity: f32,
+            _mask: Option<SoftMask<'a>>,
+            _blend_mode: BlendMode,
+        ) {
+        }
+        fn draw_glyph(
+            &mut self,
+            glyph: &Glyph<'a>,
+            transform: Affine,
+            glyph_transform: Affine,
+            _paint: &Paint<'a>,
+            _draw_mode: &GlyphDrawMode,
+        ) {
+            if let Some(c) = glyph.as_unicode() {
+                self.text.push(c);
+                self.glyphs_x
+                    .push(((transform * glyph_transform) * Point::ORIGIN).x);
+            }
+        }
+        fn draw_image(&mut self, _image: Image<'a, '_>, _transform: Affine) {}
+        fn pop_clip_path(&mut self) {}
+        fn pop_transparency_group(&mut self) {}
+    }
+
+    /// Exports a text stroke with the given attributes as Pdf and extracts the drawn text and paths.
+    fn export_text_as_pdf_and_extract(
+        text: &str,
+        ranged_text_attributes: Vec<RangedTextAttribute>,
+    ) -> TextExtractor {
+        let mut engine = Engine::default();
+        let mut text_style = TextStyle::default();
+        text_style.ranged_text_attributes = ranged_text_attributes;
+        engine.store.insert_stroke(
+            Stroke::TextStroke(TextStroke::new(
+                String::from(text),
+                na::vector![40.0, 40.0],
+                text_style,
+            )),
+            None,
+        );
+        let _ = engine.doc_resize_to_fit_content();
+
+        let pdf_bytes = futures::executor::block_on(engine.export_doc_as_pdf_bytes(
+            String::from("test"),
+            None,
+            None,
+        ))
+        .unwrap()
+        .unwrap();
+        let pdf = Pdf::new(Arc::new(pdf_bytes)).unwrap();
+
+        let mut text_extractor = TextExtractor::default();
+        for page in pdf.pages().iter() {
+            let mut context = Context::new(
+                Affine::IDENTITY,
+                page.intersected_crop_box(),
+                pdf.xref(),
+                InterpreterSettings::default(),
+            );
+            interpret_page(page, &mut context, &mut text_extractor);
+        }
+        text_extractor
+    }
+
+    #[test]
+    fn pdf_export_text_is_extractable() {
+        let text = "Searchable class notes";
+        let plain = export_text_as_pdf_and_extract(text, vec![]);
+        let attributed = export_text_as_pdf_and_extract(
+            text,
+            vec![
+                RangedTextAttribute {
+                    range: 0..10,
+                    attribute: TextAttribute::FontWeight(700),
+                },
+                RangedTextAttribute {
+                    range: 11..16,
+                    attribute: TextAttribute::Underline(true),
+                },
+            ],
+        );
+
+        let extracted = attributed
+            .text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        assert!(
+            extracted.contains("Searchableclassnotes"),
+            "extracted text: {extracted}"
+        );
+
+        // The bold glyphs of "Searchable" are wider, so "class" starts further right
+        let class_start_x = |extractor: &TextExtractor| {
+            extractor
+                .text
+                .chars()
+                .zip(extractor.glyphs_x.iter())
+                .filter(|(c, _)| !c.is_whitespace())
+                .nth(10)
+                .map(|(_, &x)| x)
+                .unwrap()
+        };
+        assert!(
+            class_start_x(&attributed) > class_start_x(&plain),
+            "bold text is not wider than the plain text"
+        );
+        // The underline of "class" is drawn as an additional path
+        assert!(
+            attributed.n_paths > plain.n_paths,
+            "no underline path was drawn"
+        );
+    }
+
+    #[test]
+    fn page_selection() {
+        let page_selection = "1,3-4, 7-".parse::<PageSelection>().unwrap();
+        assert_eq!(page_selection.indices(9), vec![0, 2, 3, 6, 7, 8]);
+        assert_eq!(page_selection.to_string(), "1,3-4,7-");
+        assert_eq!(
+            page_selection.select(vec!['a', 'b', 'c']).unwrap(),
+            vec!['a', 'c']
+        );
+        assert!(page_selection.select(vec!['a', 'b']).is_ok());
+        assert!(
+            "5".parse::<PageSelection>()
+                .unwrap()
+                .select(vec!['a'])
+                .is_err()
+        );
+
+        assert!("0".parse::<PageSelection>().is_err());
+        assert!("4-2".parse::<PageSelection>().is_err());
+        assert!("1,,2".parse::<PageSelection>().is_err());
+        assert!("".parse::<PageSelection>().is_err());
+    }
+}
@@ -1,6 +1,6 @@
 // Imports
 use super::StrokeContent;
-use crate::document::Layout;
+use crate::document::{Format, Layout, Page};
 use crate::engine_view_mut;
 use crate::pens::Pen;
 use crate::pens::PenStyle;
@@ -20,6 +20,7 @@ use tracing::error;
 #[derive(
     Debug, Clone, Copy, Serialize, Deserialize, num_derive::FromPrimitive, num_derive::ToPrimitive,
 )]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename = "pdf_import_pages_type")]
 pub enum PdfImportPagesType {
     #[serde(rename = "bitmap")]
@@ -50,6 +51,7 @@ impl TryFrom<u32> for PdfImportPagesType {
 #[derive(
     Debug, Clone, Copy, Serialize, Deserialize, num_derive::FromPrimitive, num_derive::ToPrimitive,
 )]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename = "pdf_import_page_spacing")]
 pub enum PdfImportPageSpacing {
     #[serde(rename = "continuous")]
@@ -219,11 +221,12 @@ impl Engine {
     /// Generate image strokes for each page for the bytes.
     ///
     /// The bytes are expected to be from a valid Pdf.
+    /// Unless the Pdf is encrypted, it is kept as source Pdf of the document and referenced by the generated images.
     ///
     /// Note: `insert_pos` does not have an effect when the `adjust_document` import pref is set true.
     #[allow(clippy::type_complexity)]
     pub fn generate_pdf_pages_from_bytes(
-        &self,
+        &mut self,
         bytes: Vec<u8>,
         insert_pos: na::Vector2<f64>,
         page_range: Option<Range<usize>>,
@@ -245,38 +248,23 @@ impl Engine {
             insert_pos
         };
 
+        // Encrypted Pdfs are not kept, because the password is not stored
+        let source_pdf_id = password.is_none().then(|| {
+            self.document
+                .insert_source_pdf(glib::Bytes::from(bytes.as_slice()))
+        });
+
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>> {
-                match pdf_import_prefs.pages_type {
-                    PdfImportPagesType::Bitmap => {
-                        let bitmapimages = BitmapImage::from_pdf_bytes(
-                            &bytes,
-                            pdf_import_prefs,
-                            insert_pos,
-                            page_range,
-                            &format,
-                            password,
-                        )?
-                        .into_iter()
-                        .map(|s| (Stroke::BitmapImage(s), Some(StrokeLayer::Document)))
-                        .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
-                        Ok(bitmapimages)
-                    }
-                    PdfImportPagesType::Vector => {
-                        let vectorimages = VectorImage::from_pdf_bytes(
-                            &bytes,
-                            pdf_import_prefs,
-                            insert_pos,
-                            page_range,
-                            &format,
-                            password,
-                        )?
-                        .into_iter()
-                        .map(|s| (Stroke::VectorImage(s), Some(StrokeLayer::Document)))
-                        .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
-                        Ok(vectorimages)
-                    }
-                }
+                pdf_pages_from_bytes(
+                    &bytes,
+                    pdf_import_prefs,
+                    insert_pos,
+                    page_range,
+                    &format,
+                    password,
+                    source_pdf_id,
+                )
             };
 
             if oneshot_sender.send(result()).is_err() {
@@ -310,13 +298,18 @@ impl Engine {
             widget_flags |= self.change_pen_style(PenStyle::Selector);
         }
 
+        let pages_sizes = strokes
+            .iter()
+            .map(|(stroke, _)| stroke.bounds().extents())
+            .collect::<Vec<na::Vector2<f64>>>();
         if adjust_document {
-            let max_size = strokes
+            let max_size = pages_sizes
                 .iter()
-                .map(|(stroke, _)| stroke.bounds().extents())
-                .fold(na::Vector2::<f64>::zeros(), |acc, x| acc.maxs(&x));
+                .fold(na::Vector2::<f64>::zeros(), |acc, x| acc.maxs(x));
             self.document.config.format.set_width(max_size[0]);
             self.document.config.format.set_height(max_size[1]);
+            // The imported pages are stacked vertically
+            self.document.config.two_page_spread = false;
             widget_flags |= self.set_doc_layout(Layout::FixedSize) | self.doc_resize_autoexpand()
         }
 
@@ -325,6 +318,20 @@ impl Engine {
             .map(|(stroke, layer)| self.store.insert_stroke(stroke, layer))
             .collect::<Vec<StrokeKey>>();
 
+        if adjust_document {
+            // every imported page gets its own format, so that pages with different sizes are kept intact
+            self.document.pages = pages_sizes
+                .into_iter()
+                .map(|size| {
+                    let mut format = self.document.config.format;
+                    format.set_width(size[0]);
+                    format.set_height(size[1]);
+                    Page::default().with_format(format)
+                })
+                .collect();
+            widget_flags |= self.background_rendering_regenerate();
+        }
+
         // resize after the strokes are inserted, but before they are set selected
         widget_flags |= self.doc_resize_to_fit_content();
         if select {
@@ -403,3 +410,50 @@ impl Engine {
         widget_flags
     }
 }
+
+/// Generate image strokes for each page for the bytes, depending on the pages type of the Pdf import preferences.
+///
+/// The bytes are expected to be from a valid Pdf.
+/// The generated images reference their page in the source Pdf with the given id, if there is one.
+pub(crate) fn pdf_pages_from_bytes(
+    bytes: &[u8],
+    pdf_import_prefs: PdfImportPrefs,
+    insert_pos: na::Vector2<f64>,
+    page_range: Option<Range<usize>>,
+    format: &Format,
+    password: Option<String>,
+    source_pdf_id: Option<u32>,
+) -> anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>> {
+    match pdf_import_prefs.pages_type {
+        PdfImportPagesType::Bitmap => {
+            let bitmapimages = BitmapImage::from_pdf_bytes(
+                bytes,
+                pdf_import_prefs,
+                insert_pos,
+                page_range,
+                format,
+                password,
+                source_pdf_id,
+            )?
+            .into_iter()
+            .map(|s| (Stroke::BitmapImage(s), Some(StrokeLayer::Document)))
+            .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
+            Ok(bitmapimages)
+        }
+        PdfImportPagesType::Vector => {
+            let vectorimages = VectorImage::from_pdf_bytes(
+                bytes,
+                pdf_import_prefs,
+                insert_pos,
+                page_range,
+                format,
+                password,
+                source_pdf_id,
+            )?
+            .into_iter()
+            .map(|s| (Stroke::VectorImage(s), Some(StrokeLayer::Document)))
+            .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
+            Ok(vectorimages)
+        }
+    }
+}
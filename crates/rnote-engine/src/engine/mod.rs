@@ -19,14 +19,17 @@ pub use strokecontent::StrokeContent;
 
 // Imports
 use crate::Image;
-use crate::document::Layout;
+use crate::alignment::{Alignment, AlignmentReference, Distribution};
+use crate::document::format::MeasureUnit;
+use crate::document::{Background, Format, Layout};
 use crate::pens::PenMode;
 use crate::pens::{Pen, PenStyle};
-use crate::store::StrokeKey;
+use crate::store::chrono_comp::StrokeLayer;
 use crate::store::render_comp::{self, RenderCompState};
+use crate::store::{StrokeKey, UserLayers, ZOrderChange};
 use crate::strokes::content::GeneratedContentImages;
 use crate::strokes::textstroke::{TextAttribute, TextStyle};
-use crate::{Camera, Document, PenHolder, StrokeStore};
+use crate::{Camera, Document, DrawingAids, PenHolder, StrokeStore};
 use crate::{SelectionCollision, WidgetFlags};
 use futures::StreamExt;
 use futures::channel::mpsc::UnboundedReceiver;
@@ -35,6 +38,8 @@ use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::eventresult::EventPropagation;
 use rnote_compose::ext::AabbExt;
 use rnote_compose::penevent::{PenEvent, ShortcutKey};
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::FlipDirection;
 use rnote_compose::{Color, SplitOrder};
 use serde::{Deserialize, Serialize};
 use snapshot::Snapshotable;
@@ -51,6 +56,7 @@ pub struct EngineView<'a> {
     pub document: &'a Document,
     pub store: &'a StrokeStore,
     pub camera: &'a Camera,
+    pub drawing_aids: &'a DrawingAids,
     #[cfg(feature = "ui")]
     pub audioplayer: &'a Option<crate::AudioPlayer>,
     pub animation: &'a Animation,
@@ -66,6 +72,7 @@ macro_rules! engine_view {
             document: &$engine.document,
             store: &$engine.store,
             camera: &$engine.camera,
+            drawing_aids: &$engine.drawing_aids,
             #[cfg(feature = "ui")]
             audioplayer: &$engine.audioplayer,
             animation: &$engine.animation,
@@ -81,6 +88,7 @@ pub struct EngineViewMut<'a> {
     pub document: &'a mut Document,
     pub store: &'a mut StrokeStore,
     pub camera: &'a mut Camera,
+    pub drawing_aids: &'a mut DrawingAids,
     #[cfg(feature = "ui")]
     pub audioplayer: &'a mut Option<crate::AudioPlayer>,
     pub animation: &'a mut Animation,
@@ -96,6 +104,7 @@ macro_rules! engine_view_mut {
             document: &mut $engine.document,
             store: &mut $engine.store,
             camera: &mut $engine.camera,
+            drawing_aids: &mut $engine.drawing_aids,
             #[cfg(feature = "ui")]
             audioplayer: &mut $engine.audioplayer,
             animation: &mut $engine.animation,
@@ -112,6 +121,7 @@ impl EngineViewMut<'_> {
             document: self.document,
             store: self.store,
             camera: self.camera,
+            drawing_aids: self.drawing_aids,
             #[cfg(feature = "ui")]
             audioplayer: self.audioplayer,
             animation: self.animation,
@@ -146,6 +156,8 @@ pub enum EngineTask {
     },
     /// Requests that the typewriter cursor should be blinked/toggled
     BlinkTypewriterCursor,
+    /// Requests that the current brush stroke is replaced by a recognized shape, because the pen is held still.
+    RecognizeBrushShape,
     /// Change the permanent zoom to the given value
     Zoom(f64),
     /// Indicates that the application is quitting. Sent to quit the handler which receives the tasks.
@@ -190,6 +202,9 @@ pub struct Engine {
     pub camera: Camera,
     #[serde(rename = "penholder")]
     pub penholder: PenHolder,
+    /// The ruler, protractor and set square on the canvas.
+    #[serde(skip)]
+    pub drawing_aids: DrawingAids,
 
     #[cfg(feature = "ui")]
     #[serde(skip)]
@@ -204,6 +219,9 @@ pub struct Engine {
     // Background rendering
     #[serde(skip)]
     background_tile_image: Option<Image>,
+    /// The background tile images of the pages which don't use the document background.
+    #[serde(skip)]
+    page_background_tile_images: Vec<Option<Image>>,
     #[cfg(feature = "ui")]
     #[serde(skip)]
     background_rendernodes: Vec<gtk4::gsk::RenderNode>,
@@ -225,6 +243,7 @@ impl Default for Engine {
             store: StrokeStore::default(),
             camera: Camera::default(),
             penholder: PenHolder::default(),
+            drawing_aids: DrawingAids::default(),
 
             #[cfg(feature = "ui")]
             audioplayer: None,
@@ -232,6 +251,7 @@ impl Default for Engine {
             tasks_tx: EngineTaskSender(tasks_tx),
             tasks_rx: Some(EngineTaskReceiver(tasks_rx)),
             background_tile_image: None,
+            page_background_tile_images: Vec::default(),
             #[cfg(feature = "ui")]
             background_rendernodes: Vec::default(),
             origin_indicator_image: None,
@@ -315,28 +335,24 @@ impl Engine {
         self.config.read().optimize_epd
     }
 
+    pub fn save_history(&self) -> bool {
+        self.config.read().save_history
+    }
+
     /// Takes a snapshot of the current state.
     pub fn take_snapshot(&self) -> EngineSnapshot {
-        let mut store_history_entry = self.store.create_history_entry();
-
-        // Remove all trashed strokes
-        let trashed_keys = store_history_entry
-            .trash_components
-            .iter()
-            .filter_map(|(key, trash_comp)| if trash_comp.trashed { Some(key) } else { None })
-            .collect::<Vec<StrokeKey>>();
-
-        for key in trashed_keys {
-            Arc::make_mut(&mut store_history_entry.stroke_components).remove(key);
-        }
-
-        EngineSnapshot {
+        let mut snapshot = EngineSnapshot {
             document: self.document.extract_snapshot_data(),
             camera: self.camera.extract_snapshot_data(),
-            stroke_components: Arc::clone(&store_history_entry.stroke_components),
-            chrono_components: Arc::clone(&store_history_entry.chrono_components),
-            chrono_counter: store_history_entry.chrono_counter,
-        }
+            ..Default::default()
+        };
+        self.store
+            .export_to_snapshot(&mut snapshot, self.config.read().save_history);
+        let referenced_source_pdfs = snapshot.referenced_source_pdfs();
+        snapshot
+            .document
+            .retain_source_pdfs(&referenced_source_pdfs);
+        snapshot
     }
 
     /// Imports an engine snapshot. A save file should always be loaded with this method.
@@ -459,6 +475,12 @@ impl Engine {
                     widget_flags.redraw = true;
                 }
             }
+            EngineTask::RecognizeBrushShape => {
+                if let Pen::Brush(brush) = self.penholder.current_pen_mut() {
+                    widget_flags |= brush.recognize_shape_on_hold(&mut engine_view_mut!(self));
+                    widget_flags.redraw = true;
+                }
+            }
             EngineTask::Zoom(zoom) => {
                 widget_flags |= self.camera.zoom_temporarily_to(1.0) | self.camera.zoom_to(zoom);
 
@@ -541,15 +563,23 @@ impl Engine {
 
     /// Generate bounds for each page on the document which contains content.
     pub fn pages_bounds_w_content(&self, split_order: SplitOrder) -> Vec<Aabb> {
-        let doc_bounds = self.document.bounds();
+        self.pages_w_content(split_order)
+            .into_iter()
+            .map(|(bounds, _)| bounds)
+            .collect()
+    }
+
+    /// Generate bounds and the background for each page on the document which contains content.
+    pub(crate) fn pages_w_content(&self, split_order: SplitOrder) -> Vec<(Aabb, Background)> {
         let keys = self.store.stroke_keys_as_rendered();
 
         let strokes_bounds = self.store.strokes_bounds(&keys);
 
-        let pages_bounds = doc_bounds
-            .split_extended_origin_aligned(self.document.config.format.size(), split_order)
+        let pages = self
+            .document
+            .pages_bounds_w_backgrounds(split_order)
             .into_iter()
-            .filter(|page_bounds| {
+            .filter(|(page_bounds, _)| {
                 // Filter the pages out that don't intersect with any stroke
                 strokes_bounds.iter().any(|stroke_bounds| {
                     stroke_bounds.intersects_w_tolerance(
@@ -558,16 +588,19 @@ impl Engine {
                     )
                 })
             })
-            .collect::<Vec<Aabb>>();
+            .collect::<Vec<(Aabb, Background)>>();
 
-        if pages_bounds.is_empty() {
+        if pages.is_empty() {
             // If no page has content, return the origin page
-            vec![Aabb::new(
-                na::point![0.0, 0.0],
-                self.document.config.format.size().into(),
+            vec![(
+                Aabb::new(
+                    na::point![0.0, 0.0],
+                    self.document.page_format(0).size().into(),
+                ),
+                self.document.page_background(0),
             )]
         } else {
-            pages_bounds
+            pages
         }
     }
 
@@ -650,30 +683,106 @@ impl Engine {
         self.document.expand_autoexpand(&self.camera, &self.store)
     }
 
-    /// Add a page to the document when in fixed size layout.
+    /// Add a page in the document format to the document when in fixed size layout.
     ///
     /// Document layout must be set to fixed-size.
     pub fn doc_add_page_fixed_size(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if self.document.add_page_fixed_size() {
-            widget_flags |= self.update_rendering_current_viewport();
+            widget_flags |=
+                self.background_rendering_regenerate() | self.update_rendering_current_viewport();
             widget_flags.resize = true;
         }
         widget_flags
     }
 
+    /// Set the format of a single page. `None` resets the page to the document format.
+    ///
+    /// The content of the following pages is moved along, so that it stays on its page.
+    /// With the two-page spread, the content of the following rows is moved along.
+    /// Document layout must have pages.
+    pub fn doc_set_page_format(&mut self, page_i: usize, format: Option<Format>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self.document.config.layout.has_pages() {
+            return widget_flags;
+        }
+        let Some(old_row_end) = self.document.page_row_end(page_i) else {
+            return widget_flags;
+        };
+        if !self.document.set_page_format(page_i, format) {
+            return widget_flags;
+        }
+        let axis = self.document.pages_main_axis();
+        let offset = self.document.page_row_end(page_i).unwrap_or(old_row_end) - old_row_end;
+        if offset.abs() > 0.0 {
+            let keys_following = if axis == 0 {
+                self.store.keys_right_of_x(old_row_end)
+            } else {
+                self.store.keys_below_y(old_row_end)
+            };
+            let mut translation = na::Vector2::zeros();
+            translation[axis] = offset;
+            self.store.translate_strokes(&keys_following, translation);
+            self.store
+                .translate_strokes_images(&keys_following, translation);
+            self.store.update_geometry_for_strokes(&keys_following);
+            widget_flags |= self.record(Instant::now());
+        }
+        widget_flags |= self.doc_resize_autoexpand() | self.background_rendering_regenerate();
+        widget_flags.resize = true;
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Set the background of a single page. `None` resets the page to the document background.
+    ///
+    /// Document layout must have pages.
+    pub fn doc_set_page_background(
+        &mut self,
+        page_i: usize,
+        background: Option<Background>,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self.document.config.layout.has_pages()
+            || !self.document.set_page_background(page_i, background)
+        {
+            return widget_flags;
+        }
+        widget_flags |= self.background_rendering_regenerate();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Set the title of a single page. `None` removes the title.
+    ///
+    /// Document layout must have pages.
+    pub fn doc_set_page_title(&mut self, page_i: usize, title: Option<String>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self.document.config.layout.has_pages() || !self.document.set_page_title(page_i, title)
+        {
+            return widget_flags;
+        }
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
     /// Remove a page from the document when in fixed size layout.
     ///
     /// Document layout must be set to fixed-size.
     pub fn doc_remove_page_fixed_size(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
+        let last_page_bounds = self.document.pages_arranged_bounds().last().copied();
         if self.document.remove_page_fixed_size() {
-            self.store.set_trashed_keys(
-                &self
+            let removed_keys = match last_page_bounds {
+                // The right page of a two-page spread row was removed, the row itself remains
+                Some(bounds) if bounds.mins[0] > 0.0 => {
+                    self.store.keys_below_right_of(bounds.mins.coords)
+                }
+                _ => self
                     .store
                     .keys_below_y(self.document.y + self.document.height),
-                true,
-            );
+            };
+            self.store.set_trashed_keys(&removed_keys, true);
             widget_flags |= self.record(Instant::now()) | self.update_rendering_current_viewport();
             widget_flags.resize = true;
         }
@@ -697,6 +806,16 @@ impl Engine {
         widget_flags | self.doc_expand_autoexpand()
     }
 
+    /// Rotate the view around the center of the surface, in radians.
+    ///
+    /// Expands the document when in autoexpanding layouts.
+    ///
+    /// Background and content rendering then need to be updated.
+    pub fn camera_rotate_to(&mut self, rotation: f64) -> WidgetFlags {
+        let widget_flags = self.camera.rotate_to(rotation);
+        widget_flags | self.doc_expand_autoexpand()
+    }
+
     /// Update the viewport size of the camera.
     ///
     /// Background and content rendering then need to be updated.
@@ -737,17 +856,90 @@ impl Engine {
 
     pub fn set_doc_layout(&mut self, layout: Layout) -> WidgetFlags {
         if self.document.config.layout != layout {
+            let old_pages_bounds = self.doc_pages_arranged_bounds();
             self.document.config.layout = layout;
-            self.doc_resize_to_fit_content()
+            self.doc_move_content_to_rearranged_pages(old_pages_bounds)
         } else {
             self.doc_resize_autoexpand()
         }
     }
 
+    /// Set whether the pages are arranged in rows of two in the fixed-size layout.
+    pub fn set_doc_two_page_spread(&mut self, two_page_spread: bool) -> WidgetFlags {
+        if self.document.config.two_page_spread != two_page_spread {
+            let old_pages_bounds = self.doc_pages_arranged_bounds();
+            self.document.config.two_page_spread = two_page_spread;
+            self.doc_move_content_to_rearranged_pages(old_pages_bounds)
+        } else {
+            WidgetFlags::default()
+        }
+    }
+
+    /// The arranged bounds of the pages, if the document layout has pages.
+    fn doc_pages_arranged_bounds(&self) -> Option<Vec<Aabb>> {
+        self.document
+            .config
+            .layout
+            .has_pages()
+            .then(|| self.document.pages_arranged_bounds())
+    }
+
+    /// Moves the strokes of each page from the given old page bounds to the bounds of the page in the current
+    /// arrangement and resizes the doc to fit the content.
+    ///
+    /// A stroke belongs to the page that contains its center. Moving the strokes is recorded as one history entry.
+    fn doc_move_content_to_rearranged_pages(
+        &mut self,
+        old_pages_bounds: Option<Vec<Aabb>>,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let (Some(old_pages_bounds), Some(new_pages_bounds)) =
+            (old_pages_bounds, self.doc_pages_arranged_bounds())
+        {
+            // Assign all strokes to their pages before moving any, so that moved strokes are not assigned again
+            let mut pages_keys = vec![vec![]; old_pages_bounds.len()];
+            for key in self.store.stroke_keys_unordered() {
+                let Some(center) = self
+                    .store
+                    .get_stroke_ref(key)
+                    .map(|stroke| stroke.bounds().center())
+                else {
+                    continue;
+                };
+                if let Some(page_i) = old_pages_bounds
+                    .iter()
+                    .position(|bounds| bounds.contains_local_point(&center))
+                {
+                    pages_keys[page_i].push(key);
+                }
+            }
+            let mut moved = false;
+            for ((keys, old_bounds), new_bounds) in pages_keys
+                .iter()
+                .zip(old_pages_bounds.iter())
+                .zip(new_pages_bounds.iter())
+            {
+                let offset = new_bounds.mins - old_bounds.mins;
+                if keys.is_empty() || offset == na::Vector2::zeros() {
+                    continue;
+                }
+                self.store.translate_strokes(keys, offset);
+                self.store.translate_strokes_images(keys, offset);
+                self.store.update_geometry_for_strokes(keys);
+                moved = true;
+            }
+            if moved {
+                widget_flags |= self.record(Instant::now());
+                widget_flags.store_modified = true;
+            }
+        }
+        widget_flags | self.doc_resize_to_fit_content()
+    }
+
     pub fn select_all_strokes(&mut self) -> WidgetFlags {
         let widget_flags = self.change_pen_style(PenStyle::Selector);
         self.store
-            .set_selected_keys(&self.store.stroke_keys_as_rendered(), true);
+            .set_selected_keys(&self.store.stroke_keys_as_rendered_editable(), true);
         widget_flags
             | self.current_pen_update_state()
             | self.doc_resize_autoexpand()
@@ -801,6 +993,239 @@ impl Engine {
             | self.update_rendering_current_viewport()
     }
 
+    /// Group the selected strokes. Groups that are already selected become nested in the new group.
+    pub fn group_selection(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if self.store.group_strokes(&selection_keys).is_none() {
+            return widget_flags;
+        }
+        widget_flags |= self.current_pen_update_state() | self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Dissolve the outermost groups of the selected strokes.
+    pub fn ungroup_selection(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if !self.store.ungroup_strokes(&selection_keys) {
+            return widget_flags;
+        }
+        widget_flags |= self.current_pen_update_state() | self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Align the bounds of the selected strokes relative to the reference.
+    ///
+    /// Grouped strokes are aligned together.
+    pub fn align_selection(
+        &mut self,
+        alignment: Alignment,
+        reference: AlignmentReference,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(selection_bounds) = self.store.bounds_for_strokes(&selection_keys) else {
+            return widget_flags;
+        };
+        let units = self.store.group_units(&selection_keys);
+        let reference_bounds = match reference {
+            AlignmentReference::Selection => selection_bounds,
+            AlignmentReference::Page => self
+                .document
+                .page_bounds_at(selection_bounds.center().coords),
+            AlignmentReference::FirstSelected => {
+                let Some(first_unit) = self
+                    .store
+                    .first_selected_key(&selection_keys)
+                    .and_then(|first| units.iter().find(|unit| unit.contains(&first)))
+                    .and_then(|unit| self.store.bounds_for_strokes(unit))
+                else {
+                    return widget_flags;
+                };
+                first_unit
+            }
+        };
+        for unit in units {
+            let Some(bounds) = self.store.bounds_for_strokes(&unit) else {
+                continue;
+            };
+            let offset = alignment.offset(bounds, reference_bounds);
+            if offset != na::Vector2::zeros() {
+                self.store.translate_strokes(&unit, offset);
+                self.store.translate_strokes_images(&unit, offset);
+            }
+        }
+        widget_flags |= self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Distribute the selected strokes with equal gaps between their bounds.
+    ///
+    /// Grouped strokes are distributed together.
+    pub fn distribute_selection(&mut self, distribution: Distribution) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let (units, bounds): (Vec<Vec<StrokeKey>>, Vec<Aabb>) = self
+            .store
+            .group_units(&selection_keys)
+            .into_iter()
+            .filter_map(|unit| {
+                let bounds = self.store.bounds_for_strokes(&unit)?;
+                Some((unit, bounds))
+            })
+            .unzip();
+        if units.len() < 3 {
+            return widget_flags;
+        }
+        for (unit, offset) in units.iter().zip(distribution.offsets(&bounds)) {
+            if offset != na::Vector2::zeros() {
+                self.store.translate_strokes(unit, offset);
+                self.store.translate_strokes_images(unit, offset);
+            }
+        }
+        widget_flags |= self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Flip (as in mirror) the selected strokes in the given direction along the center of the selection bounds.
+    pub fn flip_selection(&mut self, direction: FlipDirection) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(selection_bounds) = self.store.bounds_for_strokes(&selection_keys) else {
+            return WidgetFlags::default();
+        };
+        let center = selection_bounds.center();
+        self.store.flip_strokes(&selection_keys, direction, center);
+        self.store
+            .flip_strokes_images(&selection_keys, direction, center);
+        self.finish_selection_transform(&selection_keys)
+    }
+
+    /// Rotate the selected strokes by the given angle (in radians) around the center of the selection bounds.
+    pub fn rotate_selection(&mut self, angle: f64) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(selection_bounds) = self.store.bounds_for_strokes(&selection_keys) else {
+            return WidgetFlags::default();
+        };
+        if angle == 0.0 {
+            return WidgetFlags::default();
+        }
+        let center = selection_bounds.center();
+        self.store.rotate_strokes(&selection_keys, angle, center);
+        self.store
+            .rotate_strokes_images(&selection_keys, angle, center);
+        self.finish_selection_transform(&selection_keys)
+    }
+
+    /// The position and size of the selection bounds, converted to the given unit.
+    ///
+    /// The document dpi is used for the conversion.
+    pub fn selection_bounds_in_unit(
+        &self,
+        unit: MeasureUnit,
+    ) -> Option<(na::Vector2<f64>, na::Vector2<f64>)> {
+        let selection_bounds = self
+            .store
+            .bounds_for_strokes(&self.store.selection_keys_as_rendered())?;
+        let dpi = self.document.config.format.dpi();
+        let convert =
+            |value| MeasureUnit::convert_measurement(value, MeasureUnit::Px, dpi, unit, dpi);
+        Some((
+            selection_bounds.mins.coords.map(convert),
+            selection_bounds.extents().map(convert),
+        ))
+    }
+
+    /// Move and resize the selected strokes so that the selection bounds have the given position and size,
+    /// both in the given unit, then rotate them clockwise by the angle in radians around the new bounds center.
+    ///
+    /// The size of an axis along which the selection has no extent can't be changed.
+    /// The entire transformation is recorded as a single history entry.
+    pub fn transform_selection(
+        &mut self,
+        pos: na::Vector2<f64>,
+        size: na::Vector2<f64>,
+        unit: MeasureUnit,
+        angle: f64,
+    ) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(selection_bounds) = self.store.bounds_for_strokes(&selection_keys) else {
+            return WidgetFlags::default();
+        };
+        let dpi = self.document.config.format.dpi();
+        let convert =
+            |value| MeasureUnit::convert_measurement(value, unit, dpi, MeasureUnit::Px, dpi);
+        let pos = pos.map(convert);
+        let size = size.map(convert);
+        let extents = selection_bounds.extents();
+        let scale = na::vector![
+            if extents[0] > 0.0 && size[0] > 0.0 {
+                size[0] / extents[0]
+            } else {
+                1.0
+            },
+            if extents[1] > 0.0 && size[1] > 0.0 {
+                size[1] / extents[1]
+            } else {
+                1.0
+            }
+        ];
+        let offset = pos - selection_bounds.mins.coords;
+        if scale == na::Vector2::repeat(1.0) && offset == na::Vector2::zeros() && angle == 0.0 {
+            return WidgetFlags::default();
+        }
+        let pivot = selection_bounds.mins.coords;
+        self.store
+            .scale_strokes_with_pivot(&selection_keys, scale, pivot);
+        self.store
+            .scale_strokes_images_with_pivot(&selection_keys, scale, pivot);
+        self.store.translate_strokes(&selection_keys, offset);
+        self.store.translate_strokes_images(&selection_keys, offset);
+        if angle != 0.0 {
+            let center = pos + extents.component_mul(&scale) * 0.5;
+            self.store
+                .rotate_strokes(&selection_keys, angle, center.into());
+            self.store
+                .rotate_strokes_images(&selection_keys, angle, center.into());
+        }
+        self.finish_selection_transform(&selection_keys)
+    }
+
+    fn finish_selection_transform(&mut self, selection_keys: &[StrokeKey]) -> WidgetFlags {
+        self.store.update_geometry_for_strokes(selection_keys);
+        self.store.set_rendering_dirty_for_strokes(selection_keys);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Change the z-order of the selected strokes within their layers.
+    pub fn change_selection_z_order(&mut self, change: ZOrderChange) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return widget_flags;
+        }
+        self.store.change_z_order(&selection_keys, change);
+        widget_flags |= self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
     pub fn nothing_selected(&self) -> bool {
         self.store.selection_keys_unordered().is_empty()
     }
@@ -826,6 +1251,146 @@ impl Engine {
             | self.update_content_rendering_current_viewport()
     }
 
+    pub fn user_layers(&self) -> &UserLayers {
+        self.store.user_layers()
+    }
+
+    /// Add a new user layer on top of all other layers and make it the current layer.
+    pub fn add_user_layer(&mut self, name: String) -> WidgetFlags {
+        let user_layers = self.store.user_layers_mut();
+        let id = user_layers.insert(name);
+        user_layers.set_current(id);
+        let mut widget_flags = self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Remove the user layer and trash all strokes in it.
+    ///
+    /// The last remaining layer can't be removed.
+    pub fn remove_user_layer(&mut self, id: u32) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.store.user_layers().len() <= 1 || self.store.user_layers().get(id).is_none() {
+            return widget_flags;
+        }
+        let keys = self.store.keys_in_user_layer(id);
+        self.store.set_selected_keys(&keys, false);
+        self.store.set_trashed_keys(&keys, true);
+        self.store.user_layers_mut().remove(id);
+
+        widget_flags |= self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    pub fn rename_user_layer(&mut self, id: u32, name: String) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.store.modify_user_layer(id, |layer| layer.name = name) {
+            widget_flags |= self.record(Instant::now());
+            widget_flags.store_modified = true;
+        }
+        widget_flags
+    }
+
+    /// Show or hide the user layer. Strokes in hidden layers are deselected.
+    pub fn set_user_layer_visible(&mut self, id: u32, visible: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self
+            .store
+            .modify_user_layer(id, |layer| layer.visible = visible)
+        {
+            return widget_flags;
+        }
+        if !visible {
+            self.store
+                .set_selected_keys(&self.store.keys_in_user_layer(id), false);
+        }
+        widget_flags |= self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Lock or unlock the user layer. Strokes in locked layers are deselected.
+    pub fn set_user_layer_locked(&mut self, id: u32, locked: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self
+            .store
+            .modify_user_layer(id, |layer| layer.locked = locked)
+        {
+            return widget_flags;
+        }
+        if locked {
+            self.store
+                .set_selected_keys(&self.store.keys_in_user_layer(id), false);
+        }
+        widget_flags |= self.current_pen_update_state() | self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    pub fn set_user_layer_opacity(&mut self, id: u32, opacity: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self
+            .store
+            .modify_user_layer(id, |layer| layer.opacity = opacity)
+        {
+            widget_flags |= self.record(Instant::now());
+            widget_flags.store_modified = true;
+            widget_flags.redraw = true;
+        }
+        widget_flags
+    }
+
+    /// Move the user layer to the given position, counting from the bottom layer.
+    pub fn move_user_layer(&mut self, id: u32, position: usize) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let user_layers = self.store.user_layers();
+        if user_layers
+            .position(id)
+            .is_none_or(|old_position| old_position == position.min(user_layers.len() - 1))
+        {
+            return widget_flags;
+        }
+        self.store.user_layers_mut().move_to(id, position);
+        widget_flags |= self.record(Instant::now()) | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Set the user layer new strokes are inserted into.
+    pub fn set_current_user_layer(&mut self, id: u32) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.store.user_layers().current() != id && self.store.user_layers().get(id).is_some() {
+            self.store.user_layers_mut().set_current(id);
+            widget_flags.store_modified = true;
+        }
+        widget_flags
+    }
+
+    /// Move the selected strokes into the given user layer, on top of the strokes that are already in it.
+    pub fn move_selection_to_user_layer(&mut self, id: u32) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() || self.store.user_layers().get(id).is_none() {
+            return widget_flags;
+        }
+        self.store
+            .set_stroke_layer(&selection_keys, StrokeLayer::UserLayer(id));
+        if !self.store.user_layers().visible(id) || self.store.user_layers().locked(id) {
+            self.store.set_selected_keys(&selection_keys, false);
+        }
+        widget_flags |= self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
     pub fn text_selection_change_style<F>(&mut self, modify_func: F) -> WidgetFlags
     where
         F: FnOnce(&mut TextStyle),
@@ -913,3 +1478,63 @@ impl Engine {
             .current_pen_style_w_override(&engine_view!(self))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::document::Layout;
+    use crate::strokes::textstroke::TextStyle;
+    use crate::strokes::{Stroke, TextStroke};
+    use rnote_compose::shapes::Shapeable;
+    use std::time::Instant;
+
+    #[test]
+    fn customized_trailing_pages_are_kept() {
+        let mut engine = Engine::default();
+        let _ = engine.set_doc_layout(Layout::FixedSize);
+        for _ in 0..3 {
+            let _ = engine.doc_add_page_fixed_size();
+        }
+        let _ = engine.doc_set_page_title(2, Some(String::from("Exercises")));
+
+        // The empty last page is removed, but not the customized one before it
+        let _ = engine.doc_resize_to_fit_content();
+        assert_eq!(engine.document.pages.len(), 3);
+        assert_eq!(engine.document.page_title(2), Some("Exercises"));
+    }
+
+    #[test]
+    fn two_page_spread_moves_page_content() {
+        let mut engine = Engine::default();
+        let _ = engine.set_doc_layout(Layout::FixedSize);
+        for _ in 0..3 {
+            let _ = engine.doc_add_page_fixed_size();
+        }
+        let page_bounds = engine.document.pages_arranged_bounds()[3];
+        let key = engine.store.insert_stroke(
+            Stroke::TextStroke(TextStroke::new(
+                String::from("Last page"),
+                page_bounds.mins.coords + na::vector![40.0, 40.0],
+                TextStyle::default(),
+            )),
+            None,
+        );
+        let _ = engine.doc_resize_to_fit_content();
+        let _ = engine.record(Instant::now());
+        let stroke_center =
+            |engine: &Engine| engine.store.get_stroke_ref(key).unwrap().bounds().center();
+        let old_center = stroke_center(&engine);
+
+        let _ = engine.set_doc_two_page_spread(true);
+        assert_eq!(engine.document.pages.len(), 4);
+        assert!(
+            engine.document.pages_arranged_bounds()[3]
+                .contains_local_point(&stroke_center(&engine))
+        );
+
+        // Moving the content is a single history entry, undoing it keeps the inserted stroke
+        let _ = engine.undo(Instant::now());
+        assert_eq!(stroke_center(&engine), old_center);
+        assert!(engine.can_undo());
+    }
+}
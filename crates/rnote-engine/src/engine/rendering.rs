@@ -15,8 +15,8 @@ impl Engine {
 
         #[cfg(feature = "ui")]
         {
-            use crate::ext::GrapheneRectExt;
-            use gtk4::{graphene, gsk, prelude::*};
+            use crate::ext::{GdkRGBAExt, GrapheneRectExt};
+            use gtk4::{gdk, graphene, gsk, prelude::*};
             use rnote_compose::SplitOrder;
             use rnote_compose::ext::AabbExt;
 
@@ -49,6 +49,59 @@ impl Engine {
                 }
             }
 
+            if self.document.config.layout.has_pages() {
+                // Pages with their own background are drawn on top, clipped to the page bounds
+                for (page_i, page_bounds) in self
+                    .document
+                    .pages_arranged_bounds()
+                    .into_iter()
+                    .enumerate()
+                {
+                    let Some(Some(image)) = self.page_background_tile_images.get(page_i) else {
+                        continue;
+                    };
+                    let Some(visible_bounds) = page_bounds.intersection(&viewport) else {
+                        continue;
+                    };
+                    let page_background = self.document.page_background(page_i);
+                    let new_texture = match image.to_memtexture() {
+                        Ok(t) => t,
+                        Err(e) => {
+                            error!(
+                                "Failed to generate memory-texture of page background tile image, Err: {e:?}"
+                            );
+                            continue;
+                        }
+                    };
+                    let mut page_rendernodes: Vec<gsk::RenderNode> = vec![
+                        gsk::ColorNode::new(
+                            &gdk::RGBA::from_compose_color(page_background.color),
+                            &graphene::Rect::from_p2d_aabb(visible_bounds),
+                        )
+                        .upcast(),
+                    ];
+                    for split_bounds in visible_bounds.split_extended_origin_aligned(
+                        page_background.tile_size(),
+                        SplitOrder::default(),
+                    ) {
+                        page_rendernodes.push(
+                            gsk::TextureNode::new(
+                                &new_texture,
+                                &graphene::Rect::from_p2d_aabb(split_bounds),
+                            )
+                            .upcast(),
+                        );
+                    }
+                    rendernodes.push(
+                        gsk::ClipNode::new(
+                            gsk::ContainerNode::new(&page_rendernodes),
+                            &graphene::Rect::from_p2d_aabb(visible_bounds),
+                        )
+                        .upcast(),
+                    );
+                }
+            }
+
             self.background_rendernodes = rendernodes;
         }
 
@@ -140,6 +193,24 @@ impl Engine {
             }
         }
 
+        self.page_background_tile_images = if self.document.config.layout.has_pages() {
+            self.document
+                .pages
+                .iter()
+                .map(|page| {
+                    let background = page.background?;
+                    background
+                        .gen_tile_image(image_scale)
+                        .inspect_err(|e| {
+                            error!("Regenerating page background tile image failed, Err: {e:?}")
+                        })
+                        .ok()
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
         match gen_origin_indicator_image(scale_factor) {
             Ok(image) => {
                 self.origin_indicator_image = Some(image);
@@ -192,6 +263,8 @@ impl Engine {
                    self.camera.image_scale(),
                );
         */
+        self.drawing_aids
+            .draw_on_doc_to_gtk_snapshot(snapshot, &engine_view!(self))?;
         self.penholder
             .draw_on_doc_to_gtk_snapshot(snapshot, &engine_view!(self))?;
 
@@ -271,7 +344,6 @@ impl Engine {
         use gtk4::{gdk, graphene, gsk, prelude::*};
         use p2d::bounding_volume::BoundingVolume;
         use rnote_compose::SplitOrder;
-        use rnote_compose::ext::AabbExt;
 
         if self.document.config.format.show_borders {
             let total_zoom = self.camera.total_zoom();
@@ -281,10 +353,7 @@ impl Engine {
 
             snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds.loosened(2.0)));
 
-            for page_bounds in doc_bounds.split_extended_origin_aligned(
-                self.document.config.format.size(),
-                SplitOrder::default(),
-            ) {
+            for page_bounds in self.document.pages_bounds(SplitOrder::default()) {
                 if !page_bounds.intersects(&viewport) {
                     continue;
                 }
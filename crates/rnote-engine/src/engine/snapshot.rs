@@ -1,16 +1,19 @@
 // Imports
-use crate::document::background;
-use crate::engine::import::XoppImportPrefs;
+use crate::document::{Page, background};
+use crate::engine::import::{self, PdfImportPrefs, XoppImportPrefs};
 use crate::fileformats::{FileFormatLoader, rnoteformat, xoppformat};
-use crate::store::{ChronoComponent, StrokeKey};
+use crate::store::chrono_comp::StrokeLayer;
+use crate::store::{ChronoComponent, GroupComponent, HistoryEntry, StrokeKey, UserLayers};
 use crate::strokes::Stroke;
 use crate::{Camera, Document, Engine};
 use anyhow::Context;
 use futures::channel::oneshot;
 use serde::{Deserialize, Serialize};
 use slotmap::{SecondaryMap, SlotMap};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
 
 /// Trait for types which hold configuration needed for engine snapshots
 pub trait Snapshotable {
@@ -29,8 +32,27 @@ pub struct EngineSnapshot {
     pub stroke_components: Arc<SlotMap<StrokeKey, Arc<Stroke>>>,
     #[serde(rename = "chrono_components")]
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
+    #[serde(rename = "group_components")]
+    pub group_components: Arc<SecondaryMap<StrokeKey, Arc<GroupComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    #[serde(rename = "user_layers")]
+    pub user_layers: Arc<UserLayers>,
+    /// The history entries that can be undone, from the oldest to the newest.
+    ///
+    /// Only saved when enabled in the engine config.
+    #[serde(rename = "history")]
+    pub history: Vec<HistoryEntry>,
+}
+
+/// A snapshot that was salvaged from a partially corrupt .rnote file.
+#[derive(Debug, Clone)]
+pub struct SalvagedSnapshot {
+    pub snapshot: EngineSnapshot,
+    /// The format version the file was saved with.
+    pub version: semver::Version,
+    /// Descriptions of what needed to be repaired or dropped while loading.
+    pub repairs: Vec<String>,
 }
 
 impl Default for EngineSnapshot {
@@ -40,12 +62,63 @@ impl Default for EngineSnapshot {
             camera: Camera::default(),
             stroke_components: Arc::new(SlotMap::with_key()),
             chrono_components: Arc::new(SecondaryMap::new()),
+            group_components: Arc::new(SecondaryMap::new()),
             chrono_counter: 0,
+            user_layers: Arc::new(UserLayers::default()),
+            history: Vec::new(),
         }
     }
 }
 
+/// Resolves the path of a pdf that is referenced by a Xopp pdf background.
+///
+/// Pdfs that were moved together with the .xopp file are found next to it.
+fn resolve_xopp_pdf_path(
+    domain: &xoppformat::XoppBackgroundPixmapDomain,
+    filename: &str,
+    xopp_file_path: Option<&Path>,
+) -> Option<PathBuf> {
+    let xopp_dir = xopp_file_path.and_then(|path| path.parent());
+    let candidates = match domain {
+        xoppformat::XoppBackgroundPixmapDomain::Absolute => {
+            let path = PathBuf::from(filename);
+            let next_to_xopp = path.file_name().and_then(|name| Some(xopp_dir?.join(name)));
+            vec![Some(path), next_to_xopp]
+        }
+        // attached pdfs are saved as `<xopp file name>.<filename>`
+        xoppformat::XoppBackgroundPixmapDomain::Attach => {
+            let attached = xopp_file_path.map(|path| {
+                let mut attached = path.as_os_str().to_owned();
+                attached.push(".");
+                attached.push(filename);
+                PathBuf::from(attached)
+            });
+            vec![attached, xopp_dir.map(|dir| dir.join(filename))]
+        }
+        xoppformat::XoppBackgroundPixmapDomain::Clone => vec![],
+    };
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|candidate| candidate.is_file())
+}
+
 impl EngineSnapshot {
+    /// The ids of the source Pdfs that are referenced by the strokes of the snapshot, including the ones in the history.
+    pub(crate) fn referenced_source_pdfs(&self) -> HashSet<u32> {
+        let history_strokes = self
+            .history
+            .iter()
+            .flat_map(|entry| entry.stroke_diffs.iter())
+            .flat_map(|diff| diff.before.iter().chain(diff.after.iter()))
+            .map(|state| &state.stroke);
+        self.stroke_components
+            .values()
+            .chain(history_strokes)
+            .filter_map(|stroke| Some(stroke.source_pdf_page()?.pdf_id))
+            .collect()
+    }
+
     /// Loads a snapshot from the bytes of a .rnote file.
     ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
@@ -68,12 +141,142 @@ impl EngineSnapshot {
 
         snapshot_receiver.await?
     }
+
+    /// Loads what can be salvaged from the bytes of a partially corrupt .rnote file.
+    ///
+    /// Stroke entries that can't be deserialized are dropped. Other snapshot fields that can't be deserialized
+    /// are replaced by their defaults.
+    pub async fn salvage_from_rnote_bytes(bytes: Vec<u8>) -> anyhow::Result<SalvagedSnapshot> {
+        let (snapshot_sender, snapshot_receiver) =
+            oneshot::channel::<anyhow::Result<SalvagedSnapshot>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<SalvagedSnapshot> {
+                let (rnote_file, version, mut repairs) =
+                    rnoteformat::RnoteFile::salvage_from_bytes(&bytes)
+                        .context("salvaging RnoteFile from bytes failed.")?;
+                let snapshot = Self::salvage_from_value(rnote_file.engine_snapshot, &mut repairs)?;
+                Ok(SalvagedSnapshot {
+                    snapshot,
+                    version,
+                    repairs,
+                })
+            };
+
+            if let Err(_data) = snapshot_sender.send(result()) {
+                error!(
+                    "Sending result to receiver failed while salvaging rnote bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        snapshot_receiver.await?
+    }
+
+    fn salvage_from_value(
+        mut value: ijson::IValue,
+        repairs: &mut Vec<String>,
+    ) -> anyhow::Result<Self> {
+        let err = match ijson::from_value::<Self>(&value) {
+            Ok(snapshot) => return Ok(snapshot),
+            Err(e) => e,
+        };
+        let Some(fields) = value.as_object_mut() else {
+            return Err(anyhow::anyhow!(
+                "engine snapshot is not a JSON object, Err: {err}"
+            ));
+        };
+
+        // Replace invalid stroke entries with vacant slots. The first slot is the sentinel and must always be vacant.
+        if let Some(slots) = fields
+            .get_mut("stroke_components")
+            .and_then(|slots| slots.as_array_mut())
+        {
+            for (i, slot) in slots.iter_mut().enumerate() {
+                let version = slot.get("version").and_then(|version| version.to_u32());
+                let stroke = slot.get("value").filter(|stroke| !stroke.is_null());
+                let valid = match (version, stroke) {
+                    (Some(version), None) => version % 2 == 0,
+                    (Some(version), Some(stroke)) => {
+                        i > 0 && version % 2 == 1 && ijson::from_value::<Stroke>(stroke).is_ok()
+                    }
+                    (None, _) => false,
+                };
+                if !valid {
+                    *slot = ijson::ijson!({ "value": null, "version": 0 });
+                    repairs.push(format!("dropped invalid stroke entry {i}."));
+                }
+            }
+        }
+
+        // Drop all remaining fields that can't be deserialized on their own, they are replaced by their defaults.
+        let keys = fields.keys().cloned().collect::<Vec<ijson::IString>>();
+        for key in keys {
+            let mut single = ijson::IObject::new();
+            if let Some(field) = fields.get(&key) {
+                single.insert(key.clone(), field.clone());
+            }
+            if let Err(e) = ijson::from_value::<Self>(&single.into()) {
+                fields.remove(&key);
+                repairs.push(format!(
+                    "dropped invalid field \"{}\", Err: {e}",
+                    key.as_str()
+                ));
+            }
+        }
+
+        ijson::from_value::<Self>(&value).context("deserializing the salvaged snapshot failed.")
+    }
+
+    /// Removes the strokes together with their components.
+    ///
+    /// The history is cleared, because its entries might reference the removed strokes.
+    pub fn remove_strokes(&mut self, keys: &[StrokeKey]) {
+        if keys.is_empty() {
+            return;
+        }
+        for &key in keys {
+            Arc::make_mut(&mut self.stroke_components).remove(key);
+            Arc::make_mut(&mut self.chrono_components).remove(key);
+            Arc::make_mut(&mut self.group_components).remove(key);
+        }
+        self.history.clear();
+    }
+
+    /// Removes the chrono and group components of strokes that don't exist
+    /// and adds chrono components for strokes that are missing one.
+    pub fn repair_components(&mut self) {
+        let stroke_components = Arc::clone(&self.stroke_components);
+        Arc::make_mut(&mut self.chrono_components)
+            .retain(|key, _| stroke_components.contains_key(key));
+        Arc::make_mut(&mut self.group_components)
+            .retain(|key, _| stroke_components.contains_key(key));
+
+        for (key, stroke) in stroke_components.iter() {
+            if self.chrono_components.contains_key(key) {
+                continue;
+            }
+            let layer = match stroke.extract_default_layer() {
+                StrokeLayer::UserLayer(_) => StrokeLayer::UserLayer(self.user_layers.current()),
+                layer => layer,
+            };
+            self.chrono_counter += 1;
+            Arc::make_mut(&mut self.chrono_components).insert(
+                key,
+                Arc::new(ChronoComponent::new(self.chrono_counter, layer)),
+            );
+        }
+    }
+
     /// Loads from the bytes of a Xournal++ .xopp file.
     ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
+    ///
+    /// Pdf backgrounds are resolved relative to `xopp_file_path` and imported as images on the document layer.
     pub async fn load_from_xopp_bytes(
         bytes: Vec<u8>,
         xopp_import_prefs: XoppImportPrefs,
+        xopp_file_path: Option<PathBuf>,
     ) -> anyhow::Result<Self> {
         let (snapshot_sender, snapshot_receiver) = oneshot::channel::<anyhow::Result<Self>>();
 
@@ -132,6 +335,27 @@ impl EngineSnapshot {
                         xopp_import_prefs.dpi,
                     ));
 
+                // Xopp pages can have different sizes, so every page gets its own format
+                engine.document.pages = xopp_file
+                    .xopp_root
+                    .pages
+                    .iter()
+                    .map(|page| {
+                        let mut format = engine.document.config.format;
+                        format.set_width(crate::utils::convert_value_dpi(
+                            page.width,
+                            xoppformat::XoppFile::DPI,
+                            xopp_import_prefs.dpi,
+                        ));
+                        format.set_height(crate::utils::convert_value_dpi(
+                            page.height,
+                            xoppformat::XoppFile::DPI,
+                            xopp_import_prefs.dpi,
+                        ));
+                        Page::default().with_format(format)
+                    })
+                    .collect();
+
                 if let Some(first_page) = xopp_file.xopp_root.pages.first()
                     && let xoppformat::XoppBackgroundType::Solid {
                         color: _color,
@@ -142,11 +366,110 @@ impl EngineSnapshot {
                     engine.document.config.background.pattern = background::PatternStyle::None;
                 }
 
+                // Xopp layers are mapped onto user layers by their name, or by their index when they are unnamed.
+                // Layers that only hold images are skipped, because images are always placed in the image layer.
+                let xopp_layer_name = |i: usize, layer: &xoppformat::XoppLayer| {
+                    layer
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| format!("Layer {}", i + 1))
+                };
+                let mut layer_names = Vec::<String>::new();
+                for page in xopp_file.xopp_root.pages.iter() {
+                    for (i, layer) in page.layers.iter().enumerate() {
+                        if layer.strokes.is_empty() && layer.texts.is_empty() {
+                            continue;
+                        }
+                        let name = xopp_layer_name(i, layer);
+                        if !layer_names.contains(&name) {
+                            layer_names.push(name);
+                        }
+                    }
+                }
+                engine
+                    .store
+                    .set_user_layers(UserLayers::from_names(layer_names.iter().cloned()));
+
                 // Offsetting as rnote has one global coordinate space
                 let mut offset = na::Vector2::<f64>::zeros();
+                // The loaded pdfs, by their resolved path. Subsequent pdf backgrounds without a filename
+                // reference the last one.
+                let mut pdfs = HashMap::<PathBuf, Option<Vec<u8>>>::new();
+                let mut source_pdf_ids = HashMap::<PathBuf, u32>::new();
+                let mut current_pdf_path: Option<PathBuf> = None;
+
+                for (page_i, page) in xopp_file.xopp_root.pages.into_iter().enumerate() {
+                    // import pdf backgrounds
+                    if let xoppformat::XoppBackgroundType::Pdf {
+                        domain,
+                        filename,
+                        pageno,
+                    } = &page.background.bg_type
+                    {
+                        if let (Some(domain), Some(filename)) = (domain, filename) {
+                            current_pdf_path =
+                                resolve_xopp_pdf_path(domain, filename, xopp_file_path.as_deref());
+                            if current_pdf_path.is_none() {
+                                warn!(
+                                    "Could not resolve pdf background `{filename}` while loading Xopp bytes."
+                                );
+                            }
+                        }
+                        if let Some(pdf_path) = current_pdf_path.as_ref()
+                            && let Some(pdf_bytes) =
+                                pdfs.entry(pdf_path.clone()).or_insert_with(|| {
+                                    std::fs::read(pdf_path)
+                                        .inspect_err(|e| {
+                                            error!(
+                                                "Reading pdf background {pdf_path:?} failed while loading Xopp bytes, Err: {e:?}"
+                                            )
+                                        })
+                                        .ok()
+                                })
+                        {
+                            let pdf_import_prefs = PdfImportPrefs {
+                                adjust_document: true,
+                                ..Default::default()
+                            };
+                            let page_i_pdf = pageno.saturating_sub(1);
+                            let source_pdf_id =
+                                *source_pdf_ids.entry(pdf_path.clone()).or_insert_with(|| {
+                                    engine
+                                        .document
+                                        .insert_source_pdf(glib::Bytes::from(pdf_bytes.as_slice()))
+                                });
+                            match import::pdf_pages_from_bytes(
+                                pdf_bytes,
+                                pdf_import_prefs,
+                                offset,
+                                Some(page_i_pdf..page_i_pdf + 1),
+                                &engine.document.page_format(page_i),
+                                None,
+                                Some(source_pdf_id),
+                            ) {
+                                Ok(pdf_pages) => {
+                                    for (pdf_page, layer) in pdf_pages {
+                                        engine.store.insert_stroke(pdf_page, layer);
+                                    }
+                                }
+                                Err(e) => {
+                                    error!(
+                                        "Creating pdf background from page {pageno} of {pdf_path:?} failed while loading Xopp bytes, Err: {e:?}",
+                                    );
+                                }
+                            }
+                        }
+                    }
+
+                    for (i, layers) in page.layers.into_iter().enumerate() {
+                        let name = xopp_layer_name(i, &layers);
+                        let user_layer = StrokeLayer::UserLayer(
+                            layer_names
+                                .iter()
+                                .position(|layer_name| *layer_name == name)
+                                .unwrap_or(0) as u32,
+                        );
 
-                for page in xopp_file.xopp_root.pages.into_iter() {
-                    for layers in page.layers.into_iter() {
                         // import strokes
                         for new_xoppstroke in layers.strokes.into_iter() {
                             match Stroke::from_xoppstroke(
@@ -155,6 +478,10 @@ impl EngineSnapshot {
                                 xopp_import_prefs.dpi,
                             ) {
                                 Ok((new_stroke, layer)) => {
+                                    let layer = match layer {
+                                        StrokeLayer::UserLayer(_) => user_layer,
+                                        layer => layer,
+                                    };
                                     engine.store.insert_stroke(new_stroke, Some(layer));
                                 }
                                 Err(e) => {
@@ -187,7 +514,7 @@ impl EngineSnapshot {
                             match Stroke::from_xopptext(new_xopptext, offset, xopp_import_prefs.dpi)
                             {
                                 Ok(new_text) => {
-                                    engine.store.insert_stroke(new_text, None);
+                                    engine.store.insert_stroke(new_text, Some(user_layer));
                                 }
                                 Err(e) => {
                                     error!(
@@ -2,6 +2,7 @@
 use crate::Drawable;
 use crate::Svg;
 use crate::document::Background;
+use crate::store::GroupComponent;
 use crate::strokes::Stroke;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::shapes::Shapeable;
@@ -17,6 +18,11 @@ use tracing::warn;
 pub struct StrokeContent {
     #[serde(rename = "strokes")]
     pub strokes: Vec<Arc<Stroke>>,
+    /// The groups of the strokes, in the same order as the strokes.
+    ///
+    /// Can be empty when the content doesn't hold any groups.
+    #[serde(rename = "groups")]
+    pub groups: Vec<GroupComponent>,
     #[serde(rename = "bounds")]
     pub bounds: Option<Aabb>,
     #[serde(rename = "background")]
@@ -37,6 +43,11 @@ impl StrokeContent {
         self
     }
 
+    pub fn with_groups(mut self, groups: Vec<GroupComponent>) -> Self {
+        self.groups = groups;
+        self
+    }
+
     pub fn with_background(mut self, background: Background) -> Self {
         self.background = Some(background);
         self
@@ -1,4 +1,5 @@
 // Modules
+pub(crate) mod pdfoverlay;
 pub mod rnoteformat;
 pub mod xoppformat;
 
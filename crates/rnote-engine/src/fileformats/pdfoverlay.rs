@@ -0,0 +1,213 @@
+// Imports
+use anyhow::Context;
+use lopdf::{Dictionary, Object, ObjectId, Stream};
+
+/// A Pdf that gets content of another Pdf overlaid onto its pages.
+///
+/// The original page content, text, links, annotations and forms are kept intact.
+#[derive(Debug)]
+pub(crate) struct PdfOverlay {
+    doc: lopdf::Document,
+}
+
+impl PdfOverlay {
+    /// The name of the overlay Form XObject in the page resources.
+    const XOBJECT_NAME: &'static str = "RnoteOverlay";
+
+    pub(crate) fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let doc = lopdf::Document::load_mem(bytes).context("Loading the source Pdf failed.")?;
+        if doc.is_encrypted() {
+            return Err(anyhow::anyhow!(
+                "Overlaying content onto encrypted Pdfs is not supported."
+            ));
+        }
+        Ok(Self { doc })
+    }
+
+    /// The ids of the pages, ordered by page index.
+    fn page_ids(&self) -> Vec<ObjectId> {
+        self.doc.get_pages().into_values().collect()
+    }
+
+    /// The size of the page at the given index as it is displayed, in points.
+    ///
+    /// Respects the crop box and the rotation of the page.
+    pub(crate) fn page_size(&self, page_i: usize) -> Option<na::Vector2<f64>> {
+        let page_id = *self.page_ids().get(page_i)?;
+        let (crop_box, rotation) = self.page_geometry(page_id).ok()?;
+        let size = na::vector![crop_box[2] - crop_box[0], crop_box[3] - crop_box[1]];
+        if rotation % 180 == 0 {
+            Some(size)
+        } else {
+            Some(na::vector![size[1], size[0]])
+        }
+    }
+
+    /// Overlays the pages of the overlay Pdf onto the pages with the given indices, in order.
+    ///
+    /// The overlay pages are expected to be sized as reported by [PdfOverlay::page_size()].
+    pub(crate) fn overlay_pages(
+        mut self,
+        overlay_bytes: &[u8],
+        page_indices: &[usize],
+    ) -> anyhow::Result<Vec<u8>> {
+        let mut overlay =
+            lopdf::Document::load_mem(overlay_bytes).context("Loading the overlay Pdf failed.")?;
+        overlay.renumber_objects_with(self.doc.max_id + 1);
+        self.doc.max_id = overlay.max_id;
+        let overlay_page_ids = overlay.get_pages().into_values().collect::<Vec<ObjectId>>();
+        let page_ids = self.page_ids();
+
+        let mut forms = Vec::with_capacity(page_indices.len());
+        for (&page_i, &overlay_page_id) in page_indices.iter().zip(overlay_page_ids.iter()) {
+            let page_id = *page_ids
+                .get(page_i)
+                .ok_or_else(|| anyhow::anyhow!("Source Pdf has no page with index {page_i}."))?;
+            let content = overlay.get_page_content(overlay_page_id).with_context(|| {
+                format!("Getting the overlay content for page {page_i} failed.")
+            })?;
+            let resources = overlay
+                .get_dictionary(overlay_page_id)?
+                .get(b"Resources")
+                .cloned()
+                .unwrap_or_else(|_| Object::Dictionary(Dictionary::new()));
+            forms.push((page_id, content, resources));
+        }
+        // The objects that are referenced by the overlay resources. Unused ones are pruned before saving.
+        self.doc.objects.extend(overlay.objects);
+
+        for (page_id, content, resources) in forms {
+            self.overlay_page(page_id, content, resources)?;
+        }
+
+        self.doc.prune_objects();
+        let mut bytes = Vec::new();
+        self.doc
+            .save_to(&mut bytes)
+            .context("Saving the overlaid Pdf failed.")?;
+        Ok(bytes)
+    }
+
+    /// Draws the overlay content on top of the existing content of the page.
+    fn overlay_page(
+        &mut self,
+        page_id: ObjectId,
+        content: Vec<u8>,
+        resources: Object,
+    ) -> anyhow::Result<()> {
+        let (crop_box, rotation) = self.page_geometry(page_id)?;
+        let (width, height) = (crop_box[2] - crop_box[0], crop_box[3] - crop_box[1]);
+        let (form_width, form_height) = if rotation % 180 == 0 {
+            (width, height)
+        } else {
+            (height, width)
+        };
+
+        let mut form = Stream::new(
+            lopdf::dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => vec![
+                    Object::Integer(0),
+                    Object::Integer(0),
+                    Object::Real(form_width as f32),
+                    Object::Real(form_height as f32),
+                ],
+                "Resources" => resources,
+            },
+            content,
+        );
+        // Not every content can be compressed, then it is stored uncompressed
+        let _ = form.compress();
+        let form_id = self.doc.add_object(form);
+
+        // Maps the displayed, possibly rotated page space of the overlay to the page space.
+        let [a, b, c, d, e, f] = match rotation {
+            90 => [0.0, 1.0, -1.0, 0.0, width, 0.0],
+            180 => [-1.0, 0.0, 0.0, -1.0, width, height],
+            270 => [0.0, -1.0, 1.0, 0.0, 0.0, height],
+            _ => [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+        };
+        let (e, f) = (e + crop_box[0], f + crop_box[1]);
+
+        let mut resources = self.page_resources(page_id)?;
+        let mut xobjects = match resources.get(b"XObject") {
+            Ok(xobjects) => self.doc.dereference(xobjects)?.1.as_dict()?.clone(),
+            Err(_) => Dictionary::new(),
+        };
+        xobjects.set(Self::XOBJECT_NAME, form_id);
+        resources.set("XObject", xobjects);
+
+        // The existing content is wrapped in a saved graphics state,
+        // so that changes to it don't leak into the overlay.
+        let save_id = self
+            .doc
+            .add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+        let overlay_id = self.doc.add_object(Stream::new(
+            Dictionary::new(),
+            format!(
+                "\nQ\nq\n{a} {b} {c} {d} {e:.3} {f:.3} cm\n/{} Do\nQ\n",
+                Self::XOBJECT_NAME
+            )
+            .into_bytes(),
+        ));
+        let contents = std::iter::once(save_id)
+            .chain(self.doc.get_page_contents(page_id))
+            .chain(std::iter::once(overlay_id))
+            .map(Object::Reference)
+            .collect::<Vec<Object>>();
+
+        let page = self.doc.get_dictionary_mut(page_id)?;
+        page.set("Resources", resources);
+        page.set("Contents", contents);
+        Ok(())
+    }
+
+    /// The crop box and the rotation in degrees of the page, both possibly inherited.
+    fn page_geometry(&self, page_id: ObjectId) -> anyhow::Result<([f64; 4], i64)> {
+        let page_box = self
+            .inherited_page_attribute(page_id, b"CropBox")
+            .or_else(|| self.inherited_page_attribute(page_id, b"MediaBox"))
+            .ok_or_else(|| anyhow::anyhow!("Page has no media box."))?;
+        let coords = self
+            .doc
+            .dereference(page_box)?
+            .1
+            .as_array()?
+            .iter()
+            .map(|coord| Ok(self.doc.dereference(coord)?.1.as_float()? as f64))
+            .collect::<anyhow::Result<Vec<f64>>>()?;
+        let [x0, y0, x1, y1] = coords[..] else {
+            return Err(anyhow::anyhow!("Page has an invalid media box."));
+        };
+        let rotation = self
+            .inherited_page_attribute(page_id, b"Rotate")
+            .and_then(|rotation| rotation.as_i64().ok())
+            .unwrap_or(0)
+            .rem_euclid(360);
+
+        Ok(([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)], rotation))
+    }
+
+    /// The resources of the page, resolved into an owned dictionary.
+    fn page_resources(&self, page_id: ObjectId) -> anyhow::Result<Dictionary> {
+        match self.inherited_page_attribute(page_id, b"Resources") {
+            Some(resources) => Ok(self.doc.dereference(resources)?.1.as_dict()?.clone()),
+            None => Ok(Dictionary::new()),
+        }
+    }
+
+    /// Looks up an attribute of the page, walking up the page tree for attributes that can be inherited.
+    fn inherited_page_attribute(&self, page_id: ObjectId, key: &[u8]) -> Option<&Object> {
+        let mut node = self.doc.get_dictionary(page_id).ok()?;
+        // Guards against cycles in malformed page trees
+        for _ in 0..64 {
+            if let Ok(attribute) = node.get(key) {
+                return Some(attribute);
+            }
+            let parent_id = node.get(b"Parent").ok()?.as_reference().ok()?;
+            node = self.doc.get_dictionary(parent_id).ok()?;
+        }
+        None
+    }
+}
@@ -0,0 +1,62 @@
+// Imports
+use super::maj0min13::RnoteFileMaj0Min13;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RnoteFileMaj0Min15 {
+    /// A snapshot of the engine.
+    #[serde(rename = "engine_snapshot")]
+    pub engine_snapshot: ijson::IValue,
+}
+
+impl TryFrom<RnoteFileMaj0Min13> for RnoteFileMaj0Min15 {
+    type Error = anyhow::Error;
+
+    /// The document gained the page list with the per-page formats and backgrounds,
+    /// and the snapshot gained the optionally saved undo history.
+    ///
+    /// Pen path elements gained the optional stylus tilt, rotation and timestamp,
+    /// and the smooth and textured styles gained stroke dynamics.
+    /// These are absent in older files and deserialize to their defaults.
+    fn try_from(mut value: RnoteFileMaj0Min13) -> Result<Self, Self::Error> {
+        let engine_snapshot = value
+            .engine_snapshot
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("engine snapshot is not a JSON object."))?;
+        let document = engine_snapshot
+            .get_mut("document")
+            .ok_or_else(|| anyhow!("`engine_snapshot` has no value `document`."))?
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("`document` is not a JSON object."))?;
+
+        let height = document
+            .get("height")
+            .and_then(|height| height.to_f64())
+            .ok_or_else(|| anyhow!("document has no number value `height`."))?;
+        let format_height = document
+            .get("config")
+            .and_then(|config| config.as_object())
+            .and_then(|config| config.get("format"))
+            .and_then(|format| format.as_object())
+            .and_then(|format| format.get("height"))
+            .and_then(|height| height.to_f64())
+            .ok_or_else(|| anyhow!("document config has no number value `format.height`."))?;
+        let n_pages = if format_height > 0.0 {
+            ((height / format_height).ceil() as usize).max(1)
+        } else {
+            1
+        };
+
+        // All pages have the document format and background, so they are empty objects.
+        let pages = (0..n_pages)
+            .map(|_| ijson::IObject::new())
+            .collect::<ijson::IArray>();
+        document.insert("pages", pages);
+        engine_snapshot.insert("history", ijson::IArray::new());
+
+        Ok(Self {
+            engine_snapshot: value.engine_snapshot,
+        })
+    }
+}
@@ -7,6 +7,7 @@
 
 // Modules
 pub(crate) mod maj0min13;
+pub(crate) mod maj0min15;
 pub(crate) mod maj0min5patch8;
 pub(crate) mod maj0min5patch9;
 pub(crate) mod maj0min6;
@@ -18,6 +19,7 @@ use self::maj0min5patch9::RnoteFileMaj0Min5Patch9;
 use self::maj0min6::RnoteFileMaj0Min6;
 use self::maj0min9::RnoteFileMaj0Min9;
 use self::maj0min13::RnoteFileMaj0Min13;
+use self::maj0min15::RnoteFileMaj0Min15;
 
 use super::{FileFormatLoader, FileFormatSaver};
 use anyhow::Context;
@@ -60,6 +62,67 @@ fn decompress_from_gzip(compressed: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
     Ok(bytes)
 }
 
+/// Decompress from gzip, keeping everything that could be decompressed before an error occurred.
+///
+/// Used to salvage truncated or otherwise damaged files, the error is returned alongside the bytes.
+fn decompress_from_gzip_lossy(compressed: &[u8]) -> (Vec<u8>, Option<anyhow::Error>) {
+    let mut bytes = Vec::new();
+    let mut decoder = flate2::read::MultiGzDecoder::new(compressed);
+    // `read_to_end()` appends all data that was read before the error
+    let res = decoder.read_to_end(&mut bytes);
+    (bytes, res.err().map(anyhow::Error::from))
+}
+
+/// Closes truncated JSON by cutting it after the last complete value and closing all open objects and arrays.
+///
+/// Returns None if there is no position where the JSON can be cut.
+fn close_truncated_json(json: &[u8]) -> Option<Vec<u8>> {
+    let mut open = Vec::<u8>::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // The position the JSON can be cut at and the number of open objects and arrays there.
+    //
+    // The open brackets before this number can't change until the next cut position is found,
+    // because a cut position is recorded after every closing bracket.
+    let mut cut: Option<(usize, usize)> = None;
+
+    for (i, &b) in json.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                open.push(b);
+                cut = Some((i + 1, open.len()));
+            }
+            b'}' | b']' => {
+                open.pop()?;
+                cut = Some((i + 1, open.len()));
+            }
+            b',' => cut = Some((i, open.len())),
+            _ => {}
+        }
+    }
+
+    let (pos, n_open) = cut?;
+    let mut closed = json[..pos].to_vec();
+    closed.extend(
+        open[..n_open]
+            .iter()
+            .rev()
+            .map(|&b| if b == b'{' { b'}' } else { b']' }),
+    );
+    Some(closed)
+}
+
 /// The rnote file wrapper.
 ///
 /// Used to extract and match the version up front, before deserializing the data.
@@ -75,26 +138,59 @@ struct RnotefileWrapper {
 /// The Rnote file in the newest format version.
 ///
 /// This struct exists to allow for upgrading older versions before loading the file in.
-pub type RnoteFile = RnoteFileMaj0Min13;
+pub type RnoteFile = RnoteFileMaj0Min15;
 
 impl RnoteFile {
     pub const SEMVER: &'static str = crate::utils::crate_version();
-}
 
-impl FileFormatLoader for RnoteFile {
-    fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
-        let wrapper = serde_json::from_slice::<RnotefileWrapper>(
-            &decompress_from_gzip(bytes).context("decompressing bytes failed.")?,
-        )
-        .context("deserializing RnotefileWrapper from bytes failed.")?;
+    /// Loads what can be salvaged from the bytes of a partially corrupt file.
+    ///
+    /// Truncated gzip data is decompressed as far as possible and truncated JSON is closed after the last complete value.
+    /// Returns the file, the format version it was saved with and descriptions of what needed to be repaired.
+    pub(crate) fn salvage_from_bytes(
+        bytes: &[u8],
+    ) -> anyhow::Result<(Self, semver::Version, Vec<String>)> {
+        let mut repairs = Vec::new();
+        let (decompressed, decompress_err) = decompress_from_gzip_lossy(bytes);
+        if let Some(e) = decompress_err {
+            repairs.push(format!(
+                "decompressing failed after {} bytes, Err: {e}",
+                decompressed.len()
+            ));
+        }
+        let wrapper = match serde_json::from_slice::<RnotefileWrapper>(&decompressed) {
+            Ok(wrapper) => wrapper,
+            Err(e) => {
+                let closed = close_truncated_json(&decompressed).ok_or_else(|| {
+                    anyhow::anyhow!("no complete JSON value found in the decompressed data.")
+                })?;
+                repairs.push(format!(
+                    "JSON was invalid, cut after the last complete value and closed. Err: {e}"
+                ));
+                serde_json::from_slice::<RnotefileWrapper>(&closed)
+                    .context("deserializing RnotefileWrapper from the closed JSON failed.")?
+            }
+        };
+        let version = wrapper.version.clone();
+        Ok((Self::from_wrapper(wrapper)?, version, repairs))
+    }
 
+    fn from_wrapper(wrapper: RnotefileWrapper) -> anyhow::Result<Self> {
         // Conversions for older file format versions happen here
-        if semver::VersionReq::parse(">=0.13.0")
+        if semver::VersionReq::parse(">=0.15.0")
+            .unwrap()
+            .matches(&wrapper.version)
+        {
+            ijson::from_value::<RnoteFileMaj0Min15>(&wrapper.data)
+                .context("deserializing RnoteFileMaj0Min15 failed.")
+        } else if semver::VersionReq::parse(">=0.13.0")
             .unwrap()
             .matches(&wrapper.version)
         {
             ijson::from_value::<RnoteFileMaj0Min13>(&wrapper.data)
                 .context("deserializing RnoteFileMaj0Min13 failed.")
+                .and_then(RnoteFileMaj0Min15::try_from)
+                .context("converting RnoteFileMaj0Min13 to newest file version failed.")
         } else if semver::VersionReq::parse(">=0.9.0")
             .unwrap()
             .matches(&wrapper.version)
@@ -102,6 +198,7 @@ impl FileFormatLoader for RnoteFile {
             ijson::from_value::<RnoteFileMaj0Min9>(&wrapper.data)
                 .context("deserializing RnoteFileMaj0Min9 failed.")
                 .and_then(RnoteFileMaj0Min13::try_from)
+                .and_then(RnoteFileMaj0Min15::try_from)
                 .context("converting RnoteFileMaj0Min9 to newest file version failed.")
         } else if semver::VersionReq::parse(">=0.5.10")
             .unwrap()
@@ -111,6 +208,7 @@ impl FileFormatLoader for RnoteFile {
                 .context("deserializing RnoteFileMaj0Min6 failed.")
                 .and_then(RnoteFileMaj0Min9::try_from)
                 .and_then(RnoteFileMaj0Min13::try_from)
+                .and_then(RnoteFileMaj0Min15::try_from)
                 .context("converting RnoteFileMaj0Min6 to newest file version failed.")
         } else if semver::VersionReq::parse(">=0.5.9")
             .unwrap()
@@ -121,6 +219,7 @@ impl FileFormatLoader for RnoteFile {
                 .and_then(RnoteFileMaj0Min6::try_from)
                 .and_then(RnoteFileMaj0Min9::try_from)
                 .and_then(RnoteFileMaj0Min13::try_from)
+                .and_then(RnoteFileMaj0Min15::try_from)
                 .context("converting RnoteFileMaj0Min5Patch9 to newest file version failed.")
         } else if semver::VersionReq::parse(">=0.5.0")
             .unwrap()
@@ -132,6 +231,7 @@ impl FileFormatLoader for RnoteFile {
                 .and_then(RnoteFileMaj0Min6::try_from)
                 .and_then(RnoteFileMaj0Min9::try_from)
                 .and_then(RnoteFileMaj0Min13::try_from)
+                .and_then(RnoteFileMaj0Min15::try_from)
                 .context("converting RnoteFileMaj0Min5Patch8 to newest file version failed.")
         } else {
             Err(anyhow::anyhow!(
@@ -142,6 +242,17 @@ impl FileFormatLoader for RnoteFile {
     }
 }
 
+impl FileFormatLoader for RnoteFile {
+    fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let wrapper = serde_json::from_slice::<RnotefileWrapper>(
+            &decompress_from_gzip(bytes).context("decompressing bytes failed.")?,
+        )
+        .context("deserializing RnotefileWrapper from bytes failed.")?;
+
+        Self::from_wrapper(wrapper)
+    }
+}
+
 impl FileFormatSaver for RnoteFile {
     fn save_as_bytes(&self, _file_name: &str) -> anyhow::Result<Vec<u8>> {
         let wrapper = RnotefileWrapper {
@@ -217,8 +217,15 @@ pub enum XoppBackgroundType {
         /// The filename that is to the image for the pixmap.
         filename: String,
     },
-    /// A background with a pdf. Currently **UNIMPLEMENTED**.
-    Pdf,
+    /// A background with a page of a pdf.
+    Pdf {
+        /// The domain for the pdf. Only present on the first page that references the pdf.
+        domain: Option<XoppBackgroundPixmapDomain>,
+        /// The filename of the pdf. Only present on the first page that references the pdf.
+        filename: Option<String>,
+        /// The page number in the pdf, starting at 1.
+        pageno: usize,
+    },
 }
 
 impl XmlWritable for XoppBackgroundType {
@@ -234,8 +241,19 @@ impl XmlWritable for XoppBackgroundType {
                 w.write_attribute("domain", &domain.to_xml_attr_value());
                 w.write_attribute("filename", filename);
             }
-            Self::Pdf => {
+            Self::Pdf {
+                domain,
+                filename,
+                pageno,
+            } => {
                 w.write_attribute("type", "pdf");
+                if let Some(domain) = domain {
+                    w.write_attribute("domain", &domain.to_xml_attr_value());
+                }
+                if let Some(filename) = filename {
+                    w.write_attribute("filename", filename);
+                }
+                w.write_attribute("pageno", &format!("{pageno}ll"));
             }
         }
     }
@@ -340,6 +358,23 @@ impl ToXmlAttributeValue for XoppBackgroundPixmapDomain {
     }
 }
 
+impl FromXmlAttributeValue for XoppBackgroundPixmapDomain {
+    fn from_xml_attr_value(s: &str) -> Result<Self, anyhow::Error>
+    where
+        Self: Sized,
+    {
+        match s {
+            "absolute" => Ok(Self::Absolute),
+            "attach" => Ok(Self::Attach),
+            "clone" => Ok(Self::Clone),
+            o => Err(anyhow::anyhow!(
+                "Err while parsing `domain` attribute of XoppBackground, {:?} is not a valid value",
+                o
+            )),
+        }
+    }
+}
+
 impl Default for XoppBackgroundPixmapDomain {
     fn default() -> Self {
         Self::Absolute
@@ -406,7 +441,26 @@ impl XmlLoadable for XoppBackground {
                 self.bg_type = XoppBackgroundType::Pixmap { domain, filename };
             }
             "pdf" => {
-                self.bg_type = XoppBackgroundType::Pdf;
+                let domain = node
+                    .attribute("domain")
+                    .map(XoppBackgroundPixmapDomain::from_xml_attr_value)
+                    .transpose()?;
+                let filename = node
+                    .attribute("filename")
+                    .map(|filename| filename.to_string());
+                // The page number is saved with a `ll` suffix, e.g. `3ll`
+                let pageno = node
+                    .attribute("pageno")
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Failed to parse `pageno` attribute in XoppBackground with node id {:?}, could not find attribute", node.id())
+                    })?
+                    .trim_end_matches('l')
+                    .parse::<usize>()?;
+                self.bg_type = XoppBackgroundType::Pdf {
+                    domain,
+                    filename,
+                    pageno,
+                };
             }
             _ => {
                 return Err(anyhow::anyhow!("Failed to parse `type` attribute of XoppBackground with node id {:?}, is not a valid value", node.id()));
@@ -9,11 +9,13 @@
 //! The main entry point is the [Engine] struct.
 
 // Modules
+pub mod alignment;
 #[cfg(feature = "ui")]
 pub mod audioplayer;
 pub mod camera;
 pub mod document;
 pub mod drawable;
+pub mod drawingaids;
 pub mod engine;
 pub mod ext;
 pub mod fileformats;
@@ -35,6 +37,7 @@ pub use camera::Camera;
 pub use document::Document;
 pub use drawable::Drawable;
 pub use drawable::DrawableOnDoc;
+pub use drawingaids::DrawingAids;
 pub use engine::Engine;
 pub use image::Image;
 pub use pens::PenHolder;
@@ -2,10 +2,13 @@
 use super::PenBehaviour;
 use super::PenStyle;
 use super::pensconfig::brushconfig::BrushStyle;
-use crate::engine::{EngineView, EngineViewMut};
+use crate::drawingaids::DrawingAidConstraint;
+use crate::engine::{EngineTask, EngineView, EngineViewMut};
 use crate::store::StrokeKey;
 use crate::strokes::BrushStroke;
+use crate::strokes::ShapeStroke;
 use crate::strokes::Stroke;
+use crate::tasks::{OneOffTaskError, OneOffTaskHandle};
 use crate::{DrawableOnDoc, WidgetFlags};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use piet::RenderContext;
@@ -18,7 +21,9 @@ use rnote_compose::builders::{
 use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::penevent::{PenEvent, PenProgress};
 use rnote_compose::penpath::{Element, Segment};
-use std::time::Instant;
+use rnote_compose::shapes::recognition::recognize_shape;
+use std::time::{Duration, Instant};
+use tracing::error;
 
 #[derive(Debug)]
 enum BrushState {
@@ -27,7 +32,16 @@ enum BrushState {
         path_builder: Box<dyn Buildable<Emit = Segment>>,
         current_stroke_key: StrokeKey,
         preview_style: Style,
+        /// The position where the pen started to be held still.
+        hold_pos: na::Vector2<f64>,
+        /// Triggers shape recognition when the pen is held still, if enabled.
+        hold_task_handle: Option<OneOffTaskHandle>,
+        /// Constrains the stroke to a drawing aid when it was started close to one of them.
+        drawing_aid_constraint: Option<DrawingAidConstraint>,
     },
+    /// The stroke was replaced by a recognized shape while the pen was held still,
+    /// waiting for the pen to be lifted.
+    Recognized,
 }
 
 #[derive(Debug)]
@@ -69,7 +83,21 @@ impl PenBehaviour for Brush {
         let mut widget_flags = WidgetFlags::default();
 
         let event_result = match (&mut self.state, event) {
-            (BrushState::Idle, PenEvent::Down { element, .. }) => {
+            (BrushState::Idle, PenEvent::Down { mut element, .. }) => {
+                let mut drawing_aid_constraint =
+                    engine_view.drawing_aids.constraint_for_stroke_start(
+                        element.pos,
+                        engine_view.camera,
+                        &engine_view.config.pens_config.tools_config,
+                    );
+                if let Some(constraint) = drawing_aid_constraint.as_mut() {
+                    element.pos = constraint.constrain(
+                        element.pos,
+                        engine_view.drawing_aids,
+                        engine_view.camera,
+                    );
+                }
+
                 if !element.filter_by_bounds(
                     engine_view
                         .document
@@ -97,13 +125,11 @@ impl PenBehaviour for Brush {
 
                     let current_stroke_key = engine_view.store.insert_stroke(
                         brushstroke,
-                        Some(
-                            engine_view
-                                .config
-                                .pens_config
-                                .brush_config
-                                .layer_for_current_options(),
-                        ),
+                        engine_view
+                            .config
+                            .pens_config
+                            .brush_config
+                            .layer_for_current_options(),
                     );
 
                     engine_view.store.regenerate_rendering_for_stroke(
@@ -112,6 +138,13 @@ impl PenBehaviour for Brush {
                         engine_view.camera.image_scale(),
                     );
 
+                    let hold_task_handle = engine_view
+                        .config
+                        .pens_config
+                        .brush_config
+                        .shape_recognition
+                        .then(|| new_hold_task_handle(engine_view));
+
                     self.state = BrushState::Drawing {
                         path_builder: new_builder(
                             engine_view.config.pens_config.brush_config.builder_type,
@@ -120,6 +153,9 @@ impl PenBehaviour for Brush {
                         ),
                         current_stroke_key,
                         preview_style,
+                        hold_pos: element.pos,
+                        hold_task_handle,
+                        drawing_aid_constraint,
                     };
 
                     EventResult {
@@ -140,6 +176,20 @@ impl PenBehaviour for Brush {
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::Idle,
             },
+            (BrushState::Recognized, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.state = BrushState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (BrushState::Recognized, _) => EventResult {
+                handled: true,
+                propagate: EventPropagation::Stop,
+                progress: PenProgress::InProgress,
+            },
             (
                 BrushState::Drawing {
                     current_stroke_key, ..
@@ -156,25 +206,9 @@ impl PenBehaviour for Brush {
                         .style_for_current_options();
                 }
 
-                // Finish up the last stroke
-                engine_view
-                    .store
-                    .update_geometry_for_stroke(*current_stroke_key);
-                engine_view.store.regenerate_rendering_for_stroke_threaded(
-                    engine_view.tasks_tx.clone(),
-                    *current_stroke_key,
-                    engine_view.camera.viewport(),
-                    engine_view.camera.image_scale(),
-                );
-                widget_flags |= engine_view
-                    .document
-                    .resize_autoexpand(engine_view.store, engine_view.camera);
-
+                widget_flags |= finish_stroke(*current_stroke_key, engine_view);
                 self.state = BrushState::Idle;
 
-                widget_flags |= engine_view.store.record(Instant::now());
-                widget_flags.store_modified = true;
-
                 EventResult {
                     handled: true,
                     propagate: EventPropagation::Stop,
@@ -185,10 +219,40 @@ impl PenBehaviour for Brush {
                 BrushState::Drawing {
                     path_builder,
                     current_stroke_key,
+                    hold_pos,
+                    hold_task_handle,
+                    drawing_aid_constraint,
                     ..
                 },
                 pen_event,
             ) => {
+                let pen_event = match drawing_aid_constraint {
+                    Some(constraint) => constraint.constrain_event(
+                        pen_event,
+                        engine_view.drawing_aids,
+                        engine_view.camera,
+                    ),
+                    None => pen_event,
+                };
+
+                if let (PenEvent::Down { element, .. }, Some(handle)) =
+                    (&pen_event, hold_task_handle.as_mut())
+                    && (element.pos - *hold_pos).magnitude() > Self::HOLD_MOVE_TOLERANCE
+                {
+                    // The pen moved, so holding it still starts over
+                    *hold_pos = element.pos;
+                    match handle.reset_timeout() {
+                        Ok(()) => {}
+                        Err(OneOffTaskError::TimeoutReached) => {
+                            *handle = new_hold_task_handle(engine_view);
+                        }
+                        Err(e) => {
+                            error!("Resetting the brush hold task timeout failed, Err: {e:?}");
+                            *handle = new_hold_task_handle(engine_view);
+                        }
+                    }
+                }
+
                 let builder_result =
                     path_builder.handle_event(pen_event, now, Constraints::default());
                 let handled = builder_result.handled;
@@ -258,8 +322,16 @@ impl PenBehaviour for Brush {
                             );
                         }
 
-                        if let Some(Stroke::BrushStroke(brushstroke)) =
-                            engine_view.store.get_stroke_mut(*current_stroke_key)
+                        let recognized = engine_view
+                            .config
+                            .pens_config
+                            .brush_config
+                            .shape_recognition
+                            && replace_w_recognized_shape(*current_stroke_key, engine_view);
+
+                        if !recognized
+                            && let Some(Stroke::BrushStroke(brushstroke)) =
+                                engine_view.store.get_stroke_mut(*current_stroke_key)
                         {
                             brushstroke.style = engine_view
                                 .config
@@ -268,25 +340,9 @@ impl PenBehaviour for Brush {
                                 .style_for_current_options();
                         }
 
-                        // Finish up the last stroke
-                        engine_view
-                            .store
-                            .update_geometry_for_stroke(*current_stroke_key);
-                        engine_view.store.regenerate_rendering_for_stroke_threaded(
-                            engine_view.tasks_tx.clone(),
-                            *current_stroke_key,
-                            engine_view.camera.viewport(),
-                            engine_view.camera.image_scale(),
-                        );
-                        widget_flags |= engine_view
-                            .document
-                            .resize_autoexpand(engine_view.store, engine_view.camera);
-
+                        widget_flags |= finish_stroke(*current_stroke_key, engine_view);
                         self.state = BrushState::Idle;
 
-                        widget_flags |= engine_view.store.record(Instant::now());
-                        widget_flags.store_modified = true;
-
                         PenProgress::Finished
                     }
                 };
@@ -312,7 +368,7 @@ impl DrawableOnDoc for Brush {
             .style_for_current_options();
 
         match &self.state {
-            BrushState::Idle => None,
+            BrushState::Idle | BrushState::Recognized => None,
             BrushState::Drawing { path_builder, .. } => {
                 path_builder.bounds(&style, engine_view.camera.zoom())
             }
@@ -327,7 +383,7 @@ impl DrawableOnDoc for Brush {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
         match &self.state {
-            BrushState::Idle => {}
+            BrushState::Idle | BrushState::Recognized => {}
             BrushState::Drawing {
                 path_builder,
                 preview_style,
@@ -355,6 +411,34 @@ impl DrawableOnDoc for Brush {
 
 impl Brush {
     const INPUT_OVERSHOOT: f64 = 30.0;
+    /// The duration the pen needs to be held still to trigger shape recognition.
+    const HOLD_DURATION: Duration = Duration::from_millis(600);
+    /// The distance the pen can move while still being considered held still.
+    const HOLD_MOVE_TOLERANCE: f64 = 4.0;
+
+    /// Replaces the current stroke with a recognized shape when the pen is held still.
+    ///
+    /// Is triggered by the hold task. The following pen events are ignored until the pen is lifted.
+    pub(crate) fn recognize_shape_on_hold(
+        &mut self,
+        engine_view: &mut EngineViewMut,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let BrushState::Drawing {
+            current_stroke_key, ..
+        } = &self.state
+        else {
+            return widget_flags;
+        };
+        let current_stroke_key = *current_stroke_key;
+
+        if replace_w_recognized_shape(current_stroke_key, engine_view) {
+            widget_flags |= finish_stroke(current_stroke_key, engine_view);
+            self.state = BrushState::Recognized;
+        }
+
+        widget_flags
+    }
 
     fn get_preview_style(engine_view: &EngineView) -> Style {
         let mut style = engine_view
@@ -386,6 +470,60 @@ fn trigger_brush_sound(engine_view: &mut EngineViewMut) {
     }
 }
 
+fn new_hold_task_handle(engine_view: &EngineViewMut) -> OneOffTaskHandle {
+    let tasks_tx = engine_view.tasks_tx.clone();
+    OneOffTaskHandle::new(
+        move || tasks_tx.send(EngineTask::RecognizeBrushShape),
+        Brush::HOLD_DURATION,
+    )
+}
+
+/// Replaces the brush stroke with a shape stroke, if a shape is recognized in its path.
+///
+/// Returns true if the stroke was replaced.
+fn replace_w_recognized_shape(key: StrokeKey, engine_view: &mut EngineViewMut) -> bool {
+    let Some(Stroke::BrushStroke(brushstroke)) = engine_view.store.get_stroke_ref(key) else {
+        return false;
+    };
+    let points = std::iter::once(brushstroke.path.start.pos)
+        .chain(brushstroke.path.segments.iter().map(|seg| seg.end().pos))
+        .collect::<Vec<na::Vector2<f64>>>();
+    let Some(shape) = recognize_shape(&points) else {
+        return false;
+    };
+    let style = engine_view
+        .config
+        .pens_config
+        .brush_config
+        .shape_style_for_current_options();
+
+    if let Some(stroke) = engine_view.store.get_stroke_mut(key) {
+        *stroke = Stroke::ShapeStroke(ShapeStroke::new(shape, style));
+    }
+    true
+}
+
+/// Finishes up the stroke, updating its geometry and rendering and recording the changes.
+fn finish_stroke(key: StrokeKey, engine_view: &mut EngineViewMut) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+
+    engine_view.store.update_geometry_for_stroke(key);
+    engine_view.store.regenerate_rendering_for_stroke_threaded(
+        engine_view.tasks_tx.clone(),
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+    widget_flags |= engine_view
+        .document
+        .resize_autoexpand(engine_view.store, engine_view.camera);
+    widget_flags |= engine_view.store.record(Instant::now());
+    widget_flags.store_modified = true;
+    engine_view.drawing_aids.protractor.indicated_angle = None;
+
+    widget_flags
+}
+
 fn new_builder(
     builder_type: PenPathBuilderType,
     element: Element,
@@ -23,12 +23,15 @@ pub enum EraserState {
 #[derive(Clone, Debug)]
 pub struct Eraser {
     pub(crate) state: EraserState,
+    /// The eraser bounds of the current stroke, with which bitmap images are erased when the stroke is finished.
+    bitmap_eraser_bounds: Vec<Aabb>,
 }
 
 impl Default for Eraser {
     fn default() -> Self {
         Self {
             state: EraserState::Up,
+            bitmap_eraser_bounds: Vec::new(),
         }
     }
 }
@@ -39,6 +42,7 @@ impl PenBehaviour for Eraser {
     }
 
     fn deinit(&mut self) -> WidgetFlags {
+        self.bitmap_eraser_bounds.clear();
         WidgetFlags::default()
     }
 
@@ -60,7 +64,8 @@ impl PenBehaviour for Eraser {
 
         let event_result = match (&mut self.state, event) {
             (EraserState::Up | EraserState::Proximity { .. }, PenEvent::Down { element, .. }) => {
-                widget_flags |= erase(element, engine_view);
+                self.bitmap_eraser_bounds.clear();
+                widget_flags |= erase(element, &mut self.bitmap_eraser_bounds, engine_view);
                 self.state = EraserState::Down(element);
                 EventResult {
                     handled: true,
@@ -85,7 +90,7 @@ impl PenBehaviour for Eraser {
                 progress: PenProgress::Idle,
             },
             (EraserState::Down(current_element), PenEvent::Down { element, .. }) => {
-                widget_flags |= erase(element, engine_view);
+                widget_flags |= erase(element, &mut self.bitmap_eraser_bounds, engine_view);
                 *current_element = element;
                 EventResult {
                     handled: true,
@@ -94,8 +99,9 @@ impl PenBehaviour for Eraser {
                 }
             }
             (EraserState::Down { .. }, PenEvent::Up { element, .. }) => {
-                widget_flags |=
-                    erase(element, engine_view) | engine_view.store.record(Instant::now());
+                widget_flags |= erase(element, &mut self.bitmap_eraser_bounds, engine_view)
+                    | erase_bitmap_images(&mut self.bitmap_eraser_bounds, engine_view)
+                    | engine_view.store.record(Instant::now());
                 self.state = EraserState::Up;
                 EventResult {
                     handled: true,
@@ -126,7 +132,8 @@ impl PenBehaviour for Eraser {
             }
             (EraserState::Proximity { .. } | EraserState::Down { .. }, PenEvent::Cancel) => {
                 self.state = EraserState::Up;
-                widget_flags |= engine_view.store.record(Instant::now());
+                widget_flags |= erase_bitmap_images(&mut self.bitmap_eraser_bounds, engine_view)
+                    | engine_view.store.record(Instant::now());
                 EventResult {
                     handled: true,
                     propagate: EventPropagation::Stop,
@@ -220,7 +227,11 @@ impl DrawableOnDoc for Eraser {
     }
 }
 
-fn erase(element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
+fn erase(
+    element: Element,
+    bitmap_eraser_bounds: &mut Vec<Aabb>,
+    engine_view: &mut EngineViewMut,
+) -> WidgetFlags {
     // the widget_flags.store_modified flag is set in the `.trash_..()` methods
     let mut widget_flags = WidgetFlags::default();
 
@@ -236,15 +247,17 @@ fn erase(element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
             );
         }
         EraserStyle::SplitCollidingStrokes => {
-            let (modified_strokes, wf) = engine_view.store.split_colliding_strokes(
-                engine_view
-                    .config
-                    .pens_config
-                    .eraser_config
-                    .eraser_bounds(element),
-                engine_view.camera.viewport(),
-            );
+            let eraser_bounds = engine_view
+                .config
+                .pens_config
+                .eraser_config
+                .eraser_bounds(element);
+            let (modified_strokes, wf) = engine_view
+                .store
+                .split_colliding_strokes(eraser_bounds, engine_view.camera.viewport());
             widget_flags |= wf;
+            // Erasing pixels copies the image data, so it is done once when the stroke is finished
+            bitmap_eraser_bounds.push(eraser_bounds);
 
             engine_view.store.regenerate_rendering_for_strokes(
                 &modified_strokes,
@@ -256,3 +269,20 @@ fn erase(element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
 
     widget_flags
 }
+
+/// Erase the bitmap images with the eraser bounds of the finished stroke.
+fn erase_bitmap_images(
+    bitmap_eraser_bounds: &mut Vec<Aabb>,
+    engine_view: &mut EngineViewMut,
+) -> WidgetFlags {
+    let bitmap_eraser_bounds = std::mem::take(bitmap_eraser_bounds);
+    let (modified_strokes, widget_flags) = engine_view
+        .store
+        .erase_colliding_bitmap_pixels(&bitmap_eraser_bounds);
+    engine_view.store.regenerate_rendering_for_strokes(
+        &modified_strokes,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+    widget_flags
+}
@@ -110,16 +110,20 @@ pub struct BrushConfig {
     pub solid_options: SolidOptions,
     #[serde(rename = "textured_options")]
     pub textured_options: TexturedOptions,
+    /// Whether finished strokes, or strokes while the pen is held still, are converted into recognized shapes.
+    #[serde(rename = "shape_recognition")]
+    pub shape_recognition: bool,
 }
 
 impl BrushConfig {
     pub const STROKE_WIDTH_MIN: f64 = 0.1;
     pub const STROKE_WIDTH_MAX: f64 = 500.0;
 
-    pub(crate) fn layer_for_current_options(&self) -> StrokeLayer {
+    /// The layer for new strokes. `None` inserts them into the current user layer.
+    pub(crate) fn layer_for_current_options(&self) -> Option<StrokeLayer> {
         match &self.style {
-            BrushStyle::Marker => StrokeLayer::Highlighter,
-            BrushStyle::Solid | BrushStyle::Textured => StrokeLayer::UserLayer(0),
+            BrushStyle::Marker => Some(StrokeLayer::Highlighter),
+            BrushStyle::Solid | BrushStyle::Textured => None,
         }
     }
 
@@ -148,4 +152,23 @@ impl BrushConfig {
             }
         }
     }
+
+    /// The style for shapes that were recognized in brush strokes.
+    ///
+    /// Shapes can't be drawn textured, so a smooth style with the same width and color is used instead.
+    pub(crate) fn shape_style_for_current_options(&self) -> Style {
+        match &self.style {
+            BrushStyle::Marker | BrushStyle::Solid => self.style_for_current_options(),
+            BrushStyle::Textured => {
+                let mut options = SmoothOptions {
+                    stroke_width: self.textured_options.stroke_width,
+                    stroke_color: self.textured_options.stroke_color,
+                    ..Default::default()
+                };
+                options.update_piet_stroke_style();
+
+                Style::Smooth(options)
+            }
+        }
+    }
 }
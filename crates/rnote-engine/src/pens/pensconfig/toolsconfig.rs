@@ -24,6 +24,12 @@ pub enum ToolStyle {
     Zoom,
     #[serde(rename = "laser")]
     Laser,
+    #[serde(rename = "ruler")]
+    Ruler,
+    #[serde(rename = "protractor")]
+    Protractor,
+    #[serde(rename = "setsquare")]
+    SetSquare,
 }
 
 impl Default for ToolStyle {
@@ -57,4 +63,13 @@ pub struct ToolsConfig {
     #[serde(rename = "style")]
     pub style: ToolStyle,
     pub verticalspace_tool_config: VerticalSpaceToolConfig,
+    /// Whether the ruler is shown on the canvas.
+    #[serde(rename = "show_ruler")]
+    pub show_ruler: bool,
+    /// Whether the protractor is shown on the canvas.
+    #[serde(rename = "show_protractor")]
+    pub show_protractor: bool,
+    /// Whether the set square is shown on the canvas.
+    #[serde(rename = "show_setsquare")]
+    pub show_setsquare: bool,
 }
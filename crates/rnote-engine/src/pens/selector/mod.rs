@@ -7,7 +7,7 @@ use super::PenStyle;
 use super::pensconfig::selectorconfig::SelectorStyle;
 use crate::Svg;
 use crate::engine::{EngineView, EngineViewMut, StrokeContent};
-use crate::snap::SnapCorner;
+use crate::snap::{SnapCorner, SnapGuide};
 use crate::store::StrokeKey;
 use crate::strokes::Content;
 use crate::{Camera, DrawableOnDoc, Engine, WidgetFlags};
@@ -83,6 +83,8 @@ impl Default for SelectorState {
 pub struct Selector {
     pub(super) state: SelectorState,
     pos: Option<na::Vector2<f64>>,
+    /// The guides to the strokes the selection is currently snapped to.
+    snap_guides: Vec<SnapGuide>,
 }
 
 impl Default for Selector {
@@ -90,6 +92,7 @@ impl Default for Selector {
         Self {
             state: Default::default(),
             pos: None,
+            snap_guides: vec![],
         }
     }
 }
@@ -111,6 +114,7 @@ impl PenBehaviour for Selector {
         let mut widget_flags = WidgetFlags::default();
 
         let selection = engine_view.store.selection_keys_as_rendered();
+        self.snap_guides.clear();
 
         self.state =
             if let Some(selection_bounds) = engine_view.store.bounds_for_strokes(&selection) {
@@ -302,7 +306,10 @@ impl DrawableOnDoc for Selector {
             }
             SelectorState::ModifySelection {
                 selection_bounds, ..
-            } => Some(selection_bounds.extend_by(Self::RESIZE_NODE_SIZE / total_zoom)),
+            } => Some(self.snap_guides.iter().fold(
+                selection_bounds.extend_by(Self::RESIZE_NODE_SIZE / total_zoom),
+                |bounds, guide| bounds.merged(&guide.bounds(total_zoom)),
+            )),
         }
     }
 
@@ -452,6 +459,10 @@ impl DrawableOnDoc for Selector {
                     }
                     _ => {}
                 }
+
+                for guide in self.snap_guides.iter() {
+                    guide.draw(cx, total_zoom);
+                }
             }
         }
 
@@ -792,8 +803,8 @@ impl Selector {
     }
 
     fn select_all(&mut self, engine_view: &mut EngineViewMut, widget_flags: &mut WidgetFlags) {
-        // Select all keys
-        let all_strokes = engine_view.store.stroke_keys_as_rendered();
+        // Select all keys, except for the strokes in locked layers
+        let all_strokes = engine_view.store.stroke_keys_as_rendered_editable();
 
         if !all_strokes.is_empty() {
             engine_view.store.set_selected_keys(&all_strokes, true);
@@ -4,7 +4,7 @@ use crate::WidgetFlags;
 use crate::engine::EngineViewMut;
 use crate::pens::PenBehaviour;
 use crate::pens::pensconfig::selectorconfig::SelectorStyle;
-use crate::snap::SnapCorner;
+use crate::snap::{self, SnapCorner, StrokeSnap};
 use crate::store::StrokeKey;
 use p2d::bounding_volume::Aabb;
 use p2d::query::PointQuery;
@@ -25,6 +25,7 @@ impl Selector {
     ) -> (EventResult<PenProgress>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
         self.pos = Some(element.pos);
+        self.snap_guides.clear();
 
         let event_result = match &mut self.state {
             SelectorState::Idle => {
@@ -95,9 +96,15 @@ impl Selector {
                                 .and_then(|key| engine_view.store.selected(key).map(|s| !s))
                                 .unwrap_or(false)
                         {
-                            let key_to_add = key_to_add.unwrap();
-                            engine_view.store.set_selected(key_to_add, true);
-                            selection.push(key_to_add);
+                            // Clicking a member of a group adds the entire group
+                            let keys_to_add = engine_view
+                                .store
+                                .keys_w_group_members(&[key_to_add.unwrap()])
+                                .into_iter()
+                                .filter(|key| !selection.contains(key))
+                                .collect::<Vec<StrokeKey>>();
+                            engine_view.store.set_selected_keys(&keys_to_add, true);
+                            selection.extend(keys_to_add);
                             if let Some(new_bounds) =
                                 engine_view.store.bounds_for_strokes(selection)
                             {
@@ -178,9 +185,15 @@ impl Selector {
                         {
                             // defer the [SelectorStyle::Single] after checking whether we are
                             // clicking on a selection node
-                            let key_to_add = key_to_add.unwrap();
-                            engine_view.store.set_selected(key_to_add, true);
-                            selection.push(key_to_add);
+                            // Clicking a member of a group adds the entire group
+                            let keys_to_add = engine_view
+                                .store
+                                .keys_w_group_members(&[key_to_add.unwrap()])
+                                .into_iter()
+                                .filter(|key| !selection.contains(key))
+                                .collect::<Vec<StrokeKey>>();
+                            engine_view.store.set_selected_keys(&keys_to_add, true);
+                            selection.extend(keys_to_add);
                             if let Some(new_bounds) =
                                 engine_view.store.bounds_for_strokes(selection)
                             {
@@ -220,10 +233,21 @@ impl Selector {
                             SnapCorner::BottomRight => selection_bounds.maxs.coords,
                         };
 
-                        let offset = engine_view.document.snap_position(
-                            snap_corner_pos + (element.pos - *current_pos),
+                        let raw_offset = element.pos - *current_pos;
+                        let grid_offset = engine_view
+                            .document
+                            .snap_position(snap_corner_pos + raw_offset, engine_view.config)
+                            - snap_corner_pos;
+                        // Snapping to other strokes takes precedence over the grid
+                        let stroke_snap = StrokeSnap::to_strokes(
+                            &snap::bounds_snap_points(selection_bounds.translate(raw_offset)),
+                            selection,
+                            engine_view.store,
+                            engine_view.camera,
                             engine_view.config,
-                        ) - snap_corner_pos;
+                        );
+                        let offset = stroke_snap.apply(raw_offset, grid_offset);
+                        self.snap_guides = stroke_snap.guides;
 
                         if offset.magnitude()
                             > Self::TRANSLATE_OFFSET_THRESHOLD / engine_view.camera.total_zoom()
@@ -418,6 +442,7 @@ impl Selector {
     ) -> (EventResult<PenProgress>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
         self.pos = Some(element.pos);
+        self.snap_guides.clear();
 
         let event_result = match &mut self.state {
             SelectorState::Idle => EventResult {
@@ -482,6 +507,8 @@ impl Selector {
                 };
 
                 if !new_selection.is_empty() {
+                    // Groups are always selected as a whole
+                    let new_selection = engine_view.store.keys_w_group_members(&new_selection);
                     engine_view.store.set_selected_keys(&new_selection, true);
 
                     widget_flags.store_modified = true;
@@ -755,6 +782,7 @@ impl Selector {
     ) -> (EventResult<PenProgress>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
         self.pos = None;
+        self.snap_guides.clear();
 
         let event_result = match &mut self.state {
             SelectorState::Idle => EventResult {
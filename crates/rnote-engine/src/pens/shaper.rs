@@ -2,10 +2,11 @@
 use super::PenBehaviour;
 use super::PenStyle;
 use crate::engine::{EngineView, EngineViewMut};
+use crate::snap::{SnapGuide, StrokeSnap};
 use crate::strokes::ShapeStroke;
 use crate::strokes::Stroke;
 use crate::{DrawableOnDoc, WidgetFlags};
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use piet::RenderContext;
 use rnote_compose::Shape;
 use rnote_compose::builders::buildable::{Buildable, BuilderCreator, BuilderProgress};
@@ -30,12 +31,15 @@ enum ShaperState {
 #[derive(Debug)]
 pub struct Shaper {
     state: ShaperState,
+    /// The guides to the strokes the current position is snapped to.
+    snap_guides: Vec<SnapGuide>,
 }
 
 impl Default for Shaper {
     fn default() -> Self {
         Self {
             state: ShaperState::Idle,
+            snap_guides: vec![],
         }
     }
 }
@@ -72,9 +76,8 @@ impl PenBehaviour for Shaper {
                     .pens_config
                     .shaper_config
                     .new_style_seeds();
-                element.pos = engine_view
-                    .document
-                    .snap_position(element.pos, engine_view.config);
+                element.pos =
+                    Self::snap_position_w_guides(&mut self.snap_guides, element.pos, engine_view);
 
                 self.state = ShaperState::BuildShape {
                     builder: new_builder(
@@ -97,6 +100,7 @@ impl PenBehaviour for Shaper {
             },
             (ShaperState::BuildShape { .. }, PenEvent::Cancel) => {
                 self.state = ShaperState::Idle;
+                self.snap_guides.clear();
 
                 EventResult {
                     handled: false,
@@ -128,10 +132,20 @@ impl PenBehaviour for Shaper {
                     PenEvent::Text { .. } | PenEvent::Cancel => false,
                 };
                 match &mut event {
-                    PenEvent::Down { element, .. } | PenEvent::Up { element, .. } => {
-                        element.pos = engine_view
-                            .document
-                            .snap_position(element.pos, engine_view.config);
+                    PenEvent::Down { element, .. } => {
+                        element.pos = Self::snap_position_w_guides(
+                            &mut self.snap_guides,
+                            element.pos,
+                            engine_view,
+                        );
+                    }
+                    PenEvent::Up { element, .. } => {
+                        element.pos = Self::snap_position_w_guides(
+                            &mut self.snap_guides,
+                            element.pos,
+                            engine_view,
+                        );
+                        self.snap_guides.clear();
                     }
                     _ => {}
                 }
@@ -191,6 +205,7 @@ impl PenBehaviour for Shaper {
                         }
 
                         self.state = ShaperState::Idle;
+                        self.snap_guides.clear();
 
                         if shapes_emitted {
                             widget_flags |= engine_view
@@ -212,6 +227,7 @@ impl PenBehaviour for Shaper {
                     && modifier_keys.is_empty()
                 {
                     self.state = ShaperState::Idle;
+                    self.snap_guides.clear();
                     progress = PenProgress::Finished;
                 }
 
@@ -235,10 +251,15 @@ impl DrawableOnDoc for Shaper {
             .shaper_config
             .gen_style_for_current_options();
 
+        let total_zoom = engine_view.camera.total_zoom();
+
         match &self.state {
             ShaperState::Idle => None,
             ShaperState::BuildShape { builder } => {
-                builder.bounds(&style, engine_view.camera.total_zoom())
+                let bounds = builder.bounds(&style, total_zoom)?;
+                Some(self.snap_guides.iter().fold(bounds, |bounds, guide| {
+                    bounds.merged(&guide.bounds(total_zoom))
+                }))
             }
         }
     }
@@ -258,7 +279,10 @@ impl DrawableOnDoc for Shaper {
         match &self.state {
             ShaperState::Idle => {}
             ShaperState::BuildShape { builder } => {
-                builder.draw_styled(cx, &style, engine_view.camera.total_zoom())
+                builder.draw_styled(cx, &style, engine_view.camera.total_zoom());
+                for guide in self.snap_guides.iter() {
+                    guide.draw(cx, engine_view.camera.total_zoom());
+                }
             }
         }
 
@@ -267,6 +291,27 @@ impl DrawableOnDoc for Shaper {
     }
 }
 
+impl Shaper {
+    /// Snap the position to other strokes, or else to the grid, and update the guides.
+    fn snap_position_w_guides(
+        snap_guides: &mut Vec<SnapGuide>,
+        pos: na::Vector2<f64>,
+        engine_view: &EngineViewMut,
+    ) -> na::Vector2<f64> {
+        let grid_snapped = engine_view.document.snap_position(pos, engine_view.config);
+        let stroke_snap = StrokeSnap::to_strokes(
+            &[pos],
+            &[],
+            engine_view.store,
+            engine_view.camera,
+            engine_view.config,
+        );
+        let snapped = stroke_snap.apply(pos, grid_snapped);
+        *snap_guides = stroke_snap.guides;
+        snapped
+    }
+}
+
 fn new_builder(
     builder_type: ShapeBuilderType,
     element: Element,
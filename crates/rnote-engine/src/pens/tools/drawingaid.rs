@@ -0,0 +1,186 @@
+// Imports
+use super::ToolsState;
+use crate::drawingaids::{DrawingAid, DrawingAidHit, draw_label};
+use crate::engine::{EngineView, EngineViewMut};
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::Aabb;
+use piet::RenderContext;
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::penevent::PenProgress;
+use rnote_compose::{EventResult, PenEvent};
+use std::marker::PhantomData;
+use std::time::Instant;
+
+/// Places, moves and rotates a drawing aid.
+#[derive(Clone, Debug)]
+pub(super) struct DrawingAidTool<A: DrawingAid> {
+    state: ToolsState,
+    hit: DrawingAidHit,
+    /// The offset from the pen position to the aid center while moving.
+    offset: na::Vector2<f64>,
+    /// The angle of the aid when rotating started.
+    start_angle: f64,
+    /// The angle of the pen position around the aid center when rotating started.
+    start_pen_angle: f64,
+    aid: PhantomData<A>,
+}
+
+impl<A: DrawingAid> Default for DrawingAidTool<A> {
+    fn default() -> Self {
+        Self {
+            state: ToolsState::default(),
+            hit: DrawingAidHit::Body,
+            offset: na::Vector2::zeros(),
+            start_angle: 0.0,
+            start_pen_angle: 0.0,
+            aid: PhantomData,
+        }
+    }
+}
+
+impl<A: DrawingAid> DrawingAidTool<A> {
+    /// The size of the angle label while rotating, in surface coordinates.
+    const ANGLE_LABEL_SIZE: f64 = 80.0;
+    const ANGLE_LABEL_FONT_SIZE: f64 = 14.0;
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (ToolsState::Idle, PenEvent::Down { element, .. }) => {
+                let tools_config = &mut engine_view.config.pens_config.tools_config;
+                if !A::shown(tools_config) {
+                    A::set_shown(tools_config, true);
+                    A::get_mut(engine_view.drawing_aids).placement_mut().center = Some(element.pos);
+                    widget_flags.refresh_ui = true;
+                }
+                let aid = A::get_mut(engine_view.drawing_aids);
+                let center = aid.center(engine_view.camera);
+
+                match aid.hit(element.pos, engine_view.camera) {
+                    Some(DrawingAidHit::RotationHandle) => {
+                        self.hit = DrawingAidHit::RotationHandle;
+                        self.start_angle = aid.angle();
+                        self.start_pen_angle = pen_angle(element.pos, center);
+                        aid.placement_mut().center = Some(center);
+                    }
+                    Some(DrawingAidHit::Body) => {
+                        self.hit = DrawingAidHit::Body;
+                        self.offset = center - element.pos;
+                        aid.placement_mut().center = Some(center);
+                    }
+                    None => {
+                        // Place the aid at the pen position
+                        self.hit = DrawingAidHit::Body;
+                        self.offset = na::Vector2::zeros();
+                        aid.placement_mut().center = Some(element.pos);
+                    }
+                }
+                self.state = ToolsState::Active;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (ToolsState::Active, PenEvent::Down { element, .. }) => {
+                let aid = A::get_mut(engine_view.drawing_aids);
+                match self.hit {
+                    DrawingAidHit::Body => {
+                        aid.placement_mut().center = Some(element.pos + self.offset);
+                    }
+                    DrawingAidHit::RotationHandle => {
+                        let pen_angle = pen_angle(element.pos, aid.center(engine_view.camera));
+                        aid.rotate_from(self.start_angle, pen_angle - self.start_pen_angle);
+                    }
+                }
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Active, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.state = ToolsState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (
+                ToolsState::Active,
+                PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. },
+            ) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (result, widget_flags)
+    }
+
+    fn rotating(&self) -> bool {
+        matches!(self.state, ToolsState::Active) && self.hit == DrawingAidHit::RotationHandle
+    }
+}
+
+/// The angle of the pen position around the center, in radians.
+fn pen_angle(pos: na::Vector2<f64>, center: na::Vector2<f64>) -> f64 {
+    let offset = pos - center;
+    offset[1].atan2(offset[0])
+}
+
+impl<A: DrawingAid> DrawableOnDoc for DrawingAidTool<A> {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        if !self.rotating() {
+            return None;
+        }
+        let label_pos = A::get(engine_view.drawing_aids)
+            .angle_label_pos(engine_view.camera, Self::ANGLE_LABEL_SIZE);
+        Some(Aabb::from_half_extents(
+            label_pos.into(),
+            na::Vector2::repeat(Self::ANGLE_LABEL_SIZE * 0.5 / engine_view.camera.total_zoom()),
+        ))
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if !self.rotating() {
+            return Ok(());
+        }
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+        let aid = A::get(engine_view.drawing_aids);
+        // The angle counter-clockwise on screen, which is the usual orientation for angles
+        let degrees = (-aid.angle())
+            .to_degrees()
+            .rem_euclid(A::ANGLE_LABEL_PERIOD);
+        draw_label(
+            cx,
+            &format!("{degrees:.0}°"),
+            aid.angle_label_pos(engine_view.camera, Self::ANGLE_LABEL_SIZE),
+            Self::ANGLE_LABEL_FONT_SIZE / engine_view.camera.total_zoom(),
+        )?;
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
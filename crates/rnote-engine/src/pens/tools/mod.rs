@@ -1,10 +1,12 @@
 // Modules
+mod drawingaid;
 mod laser;
 mod offsetcamera;
 mod verticalspace;
 mod zoom;
 
 // Re-Exports
+use drawingaid::DrawingAidTool;
 use laser::LaserTool;
 use offsetcamera::OffsetCameraTool;
 use verticalspace::VerticalSpaceTool;
@@ -14,6 +16,7 @@ use zoom::ZoomTool;
 use super::PenBehaviour;
 use super::PenStyle;
 use super::pensconfig::toolsconfig::ToolStyle;
+use crate::drawingaids::{Protractor, Ruler, SetSquare};
 use crate::engine::{EngineView, EngineViewMut};
 use crate::{DrawableOnDoc, WidgetFlags};
 use p2d::bounding_volume::Aabb;
@@ -40,6 +43,9 @@ pub struct Tools {
     offsetcamera_tool: OffsetCameraTool,
     zoom_tool: ZoomTool,
     laser_tool: LaserTool,
+    ruler_tool: DrawingAidTool<Ruler>,
+    protractor_tool: DrawingAidTool<Protractor>,
+    setsquare_tool: DrawingAidTool<SetSquare>,
 }
 
 impl PenBehaviour for Tools {
@@ -73,6 +79,9 @@ impl PenBehaviour for Tools {
             ToolStyle::OffsetCamera => self.offsetcamera_tool.handle_event(event, now, engine_view),
             ToolStyle::Zoom => self.zoom_tool.handle_event(event, now, engine_view),
             ToolStyle::Laser => self.laser_tool.handle_event(event, now, engine_view),
+            ToolStyle::Ruler => self.ruler_tool.handle_event(event, now, engine_view),
+            ToolStyle::Protractor => self.protractor_tool.handle_event(event, now, engine_view),
+            ToolStyle::SetSquare => self.setsquare_tool.handle_event(event, now, engine_view),
         }
     }
 
@@ -91,6 +100,9 @@ impl DrawableOnDoc for Tools {
             ToolStyle::OffsetCamera => self.offsetcamera_tool.bounds_on_doc(engine_view),
             ToolStyle::Zoom => self.zoom_tool.bounds_on_doc(engine_view),
             ToolStyle::Laser => self.laser_tool.bounds_on_doc(engine_view),
+            ToolStyle::Ruler => self.ruler_tool.bounds_on_doc(engine_view),
+            ToolStyle::Protractor => self.protractor_tool.bounds_on_doc(engine_view),
+            ToolStyle::SetSquare => self.setsquare_tool.bounds_on_doc(engine_view),
         }
     }
 
@@ -114,6 +126,15 @@ impl DrawableOnDoc for Tools {
             ToolStyle::Laser => {
                 self.laser_tool.draw_on_doc(cx, engine_view)?;
             }
+            ToolStyle::Ruler => {
+                self.ruler_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Protractor => {
+                self.protractor_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::SetSquare => {
+                self.setsquare_tool.draw_on_doc(cx, engine_view)?;
+            }
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -71,9 +71,10 @@ impl OffsetCameraTool {
                         .transform_point(&self.start.into())
                         .coords;
 
-                widget_flags |= engine_view
-                    .camera
-                    .set_offset(engine_view.camera.offset() - offset, engine_view.document);
+                widget_flags |= engine_view.camera.set_offset(
+                    engine_view.camera.offset() - engine_view.camera.unrotate_surface_vec(offset),
+                    engine_view.document,
+                );
                 widget_flags |= engine_view
                     .document
                     .resize_autoexpand(engine_view.store, engine_view.camera);
@@ -67,17 +67,11 @@ impl VerticalSpaceTool {
                     .tools_config
                     .verticalspace_tool_config
                     .limit_movement_vertical_borders;
-                let y_max = ((self.pos_y / engine_view.document.config.format.height()).floor()
-                    + 1.0f64)
-                    * engine_view.document.config.format.height();
-                let limit_x = {
-                    let page_number_hor =
-                        (pos_x / engine_view.document.config.format.width()).floor();
-                    (
-                        page_number_hor * engine_view.document.config.format.width(),
-                        (page_number_hor + 1.0f64) * engine_view.document.config.format.width(),
-                    )
-                };
+                let page_bounds = engine_view
+                    .document
+                    .page_bounds_at(na::vector![pos_x, self.pos_y]);
+                let y_max = page_bounds.maxs[1];
+                let limit_x = (page_bounds.mins[0], page_bounds.maxs[0]);
                 self.limit_x = if limit_movement_vertical_borders {
                     Some(limit_x)
                 } else {
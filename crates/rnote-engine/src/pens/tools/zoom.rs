@@ -92,9 +92,13 @@ impl ZoomTool {
 
                     // Translate the camera view so that the start_surface_coord has the same surface position
                     // as before the zoom occurred
-                    let new_camera_offset =
-                        (((camera_offset + self.start_surface_coord) / total_zoom_old) * new_zoom)
-                            - self.start_surface_coord;
+                    let start_surface_coord_unrotated = engine_view
+                        .camera
+                        .unrotate_surface_pos(self.start_surface_coord);
+                    let new_camera_offset = (((camera_offset + start_surface_coord_unrotated)
+                        / total_zoom_old)
+                        * new_zoom)
+                        - start_surface_coord_unrotated;
                     widget_flags |= engine_view
                         .camera
                         .set_offset(new_camera_offset, engine_view.document);
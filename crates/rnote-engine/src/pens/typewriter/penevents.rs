@@ -56,8 +56,6 @@ impl Typewriter {
                             GraphemeCursor::new(0, textstroke.text.len(), true)
                         };
 
-                        engine_view.store.update_chrono_to_last(stroke_key);
-
                         new_state = TypewriterState::Modifying {
                             modify_state: ModifyState::Idle,
                             stroke_key,
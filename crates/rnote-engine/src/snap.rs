@@ -1,5 +1,11 @@
 // Imports
-use p2d::bounding_volume::Aabb;
+use crate::engine::EngineConfig;
+use crate::store::StrokeKey;
+use crate::{Camera, StrokeStore};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use rnote_compose::color;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SnapCorner {
@@ -28,3 +34,162 @@ impl SnapCorner {
         }
     }
 }
+
+/// A guide line that is displayed while positions are snapped to other strokes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapGuide {
+    /// The start of the line, in document coordinates.
+    pub start: na::Vector2<f64>,
+    /// The end of the line, in document coordinates.
+    pub end: na::Vector2<f64>,
+}
+
+impl SnapGuide {
+    /// The line width, in surface coordinates.
+    const LINE_WIDTH: f64 = 1.5;
+    /// The radius of the markers at the ends of the line, in surface coordinates.
+    const MARKER_RADIUS: f64 = 3.0;
+    /// The line color.
+    const COLOR: piet::Color = color::GNOME_REDS[2].with_a8(220);
+
+    /// The bounds of the guide when it is drawn.
+    pub fn bounds(&self, total_zoom: f64) -> Aabb {
+        Aabb::new_positive(self.start.into(), self.end.into())
+            .loosened(Self::MARKER_RADIUS.max(Self::LINE_WIDTH) / total_zoom)
+    }
+
+    /// Draw the guide.
+    pub fn draw(&self, cx: &mut impl RenderContext, total_zoom: f64) {
+        cx.stroke(
+            kurbo::Line::new(self.start.to_kurbo_point(), self.end.to_kurbo_point()),
+            &Self::COLOR,
+            Self::LINE_WIDTH / total_zoom,
+        );
+        for pos in [self.start, self.end] {
+            cx.fill(
+                kurbo::Circle::new(pos.to_kurbo_point(), Self::MARKER_RADIUS / total_zoom),
+                &Self::COLOR,
+            );
+        }
+    }
+}
+
+/// The result of snapping positions to other strokes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrokeSnap {
+    /// The offset for each axis that snaps the positions, or None if nothing is close enough along the axis.
+    pub offset: [Option<f64>; 2],
+    /// The guides that show to what the snapped positions are aligned.
+    pub guides: Vec<SnapGuide>,
+}
+
+impl StrokeSnap {
+    /// The maximum distance where positions get snapped, in surface coordinates.
+    pub const SNAP_DIST: f64 = 8.0;
+    /// Positions are considered aligned below this distance, in document coordinates.
+    const ALIGNED_EPSILON: f64 = 1e-6;
+
+    /// Snap the positions to the targets, independently along the x and y axis.
+    ///
+    /// Along each axis the offset with the smallest distance that is at most `snap_dist` is picked.
+    pub fn new(
+        positions: &[na::Vector2<f64>],
+        targets: &[na::Vector2<f64>],
+        snap_dist: f64,
+    ) -> Self {
+        let mut offset = [None; 2];
+        for (axis, axis_offset) in offset.iter_mut().enumerate() {
+            for pos in positions {
+                for target in targets {
+                    let dist = target[axis] - pos[axis];
+                    if dist.abs() <= snap_dist
+                        && axis_offset.is_none_or(|current: f64| dist.abs() < current.abs())
+                    {
+                        *axis_offset = Some(dist);
+                    }
+                }
+            }
+        }
+
+        let mut guides = vec![];
+        for (axis, axis_offset) in offset.iter().enumerate() {
+            let Some(axis_offset) = axis_offset else {
+                continue;
+            };
+            let other = 1 - axis;
+            // The aligned coordinates along the axis, with the extents they span along the other axis
+            let mut lines: Vec<(f64, f64, f64)> = vec![];
+            for pos in positions {
+                let snapped = pos[axis] + axis_offset;
+                for target in targets {
+                    if (target[axis] - snapped).abs() > Self::ALIGNED_EPSILON {
+                        continue;
+                    }
+                    let (min, max) = (pos[other].min(target[other]), pos[other].max(target[other]));
+                    match lines
+                        .iter_mut()
+                        .find(|(coord, ..)| (coord - snapped).abs() <= Self::ALIGNED_EPSILON)
+                    {
+                        Some(line) => {
+                            line.1 = line.1.min(min);
+                            line.2 = line.2.max(max);
+                        }
+                        None => lines.push((snapped, min, max)),
+                    }
+                }
+            }
+            guides.extend(lines.into_iter().map(|(coord, min, max)| {
+                let mut start = na::Vector2::repeat(coord);
+                let mut end = na::Vector2::repeat(coord);
+                start[other] = min;
+                end[other] = max;
+                SnapGuide { start, end }
+            }));
+        }
+
+        Self { offset, guides }
+    }
+
+    /// Snap the positions to the visible strokes in the viewport when `snap_to_strokes` is enabled.
+    ///
+    /// The strokes for the excluded keys, usually the ones that are moved, are not snapped to.
+    pub(crate) fn to_strokes(
+        positions: &[na::Vector2<f64>],
+        exclude: &[StrokeKey],
+        store: &StrokeStore,
+        camera: &Camera,
+        config: &EngineConfig,
+    ) -> Self {
+        if !config.snap_to_strokes {
+            return Self::default();
+        }
+        let targets = store.snap_targets(camera.viewport(), exclude);
+        Self::new(positions, &targets, Self::SNAP_DIST / camera.total_zoom())
+    }
+
+    /// Whether positions were snapped along any axis.
+    pub fn snapped(&self) -> bool {
+        self.offset.iter().any(Option::is_some)
+    }
+
+    /// Apply the snap offset to the position along the snapped axes, using the fallback along the others.
+    pub fn apply(&self, pos: na::Vector2<f64>, fallback: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::vector![
+            self.offset[0].map_or(fallback[0], |offset| pos[0] + offset),
+            self.offset[1].map_or(fallback[1], |offset| pos[1] + offset)
+        ]
+    }
+}
+
+/// The points of the bounds that can be snapped: the corners, the centers of the edges and the center.
+pub fn bounds_snap_points(bounds: Aabb) -> Vec<na::Vector2<f64>> {
+    let center = bounds.center().coords;
+    [bounds.mins[0], center[0], bounds.maxs[0]]
+        .into_iter()
+        .flat_map(|x| {
+            [bounds.mins[1], center[1], bounds.maxs[1]]
+                .into_iter()
+                .map(move |y| na::vector![x, y])
+        })
+        .collect()
+}
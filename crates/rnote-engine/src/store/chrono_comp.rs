@@ -1,9 +1,11 @@
 // Imports
 use super::{StrokeKey, StrokeStore};
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rayon::slice::ParallelSliceMut;
+use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq)]
@@ -85,15 +87,153 @@ impl ChronoComponent {
     }
 }
 
+/// A change of the z-order of strokes within their layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZOrderChange {
+    /// Move the strokes above all other strokes.
+    BringToFront,
+    /// Move the strokes below all other strokes.
+    SendToBack,
+    /// Move each stroke above the next stroke that overlaps with it.
+    BringForward,
+    /// Move each stroke below the previous stroke that overlaps with it.
+    SendBackward,
+}
+
 /// Systems that are related to their chronological ordering.
 impl StrokeStore {
     pub(crate) fn update_chrono_to_last(&mut self, key: StrokeKey) {
+        self.track_modified(key);
         if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components).get_mut(key) {
             self.chrono_counter += 1;
             Arc::make_mut(chrono_comp).t = self.chrono_counter;
         }
     }
 
+    /// Change the z-order of the strokes, keeping their relative order and the layers they are in.
+    ///
+    /// The strokes are reordered by redistributing the existing chrono components of each layer.
+    pub(crate) fn change_z_order(&mut self, keys: &[StrokeKey], change: ZOrderChange) {
+        let selected = keys.iter().copied().collect::<HashSet<StrokeKey>>();
+        let layers = keys
+            .iter()
+            .filter_map(|&key| Some(self.chrono_components.get(key)?.layer))
+            .collect::<BTreeSet<StrokeLayer>>();
+
+        for layer in layers {
+            let layer_keys = self
+                .keys_sorted_chrono()
+                .into_iter()
+                .filter(|&key| {
+                    self.chrono_components
+                        .get(key)
+                        .is_some_and(|chrono_comp| chrono_comp.layer == layer)
+                })
+                .collect::<Vec<StrokeKey>>();
+            let (mut moved, mut others): (Vec<StrokeKey>, Vec<StrokeKey>) = layer_keys
+                .iter()
+                .copied()
+                .partition(|key| selected.contains(key));
+            let new_order = match change {
+                ZOrderChange::BringToFront => {
+                    others.append(&mut moved);
+                    others
+                }
+                ZOrderChange::SendToBack => {
+                    moved.append(&mut others);
+                    moved
+                }
+                ZOrderChange::BringForward => self.step_forward(layer_keys.clone(), &selected),
+                ZOrderChange::SendBackward => self.step_backward(layer_keys.clone(), &selected),
+            };
+
+            let ts = layer_keys
+                .iter()
+                .filter_map(|&key| Some(self.chrono_components.get(key)?.t))
+                .collect::<Vec<u32>>();
+            for (key, t) in new_order.into_iter().zip(ts) {
+                if self.chrono_components.get(key).is_none_or(|c| c.t == t) {
+                    continue;
+                }
+                self.track_modified(key);
+                if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components).get_mut(key) {
+                    Arc::make_mut(chrono_comp).t = t;
+                }
+            }
+        }
+    }
+
+    /// Whether the strokes overlap and the other stroke is not trashed.
+    fn overlaps_with(&self, key: StrokeKey, other: StrokeKey) -> bool {
+        if self.trashed(other).unwrap_or(true) {
+            return false;
+        }
+        match (
+            self.stroke_components.get(key),
+            self.stroke_components.get(other),
+        ) {
+            (Some(stroke), Some(other)) => stroke.bounds().intersects(&other.bounds()),
+            _ => false,
+        }
+    }
+
+    /// Move the selected keys of the chronologically ordered keys above the next overlapping key.
+    ///
+    /// Selected keys don't move past each other.
+    fn step_forward(
+        &self,
+        mut order: Vec<StrokeKey>,
+        selected: &HashSet<StrokeKey>,
+    ) -> Vec<StrokeKey> {
+        // The selected keys can only be moved below this index, which keeps their relative order
+        let mut limit = order.len();
+        for i in (0..order.len()).rev() {
+            let key = order[i];
+            if !selected.contains(&key) {
+                continue;
+            }
+            match (i + 1..limit).find(|&j| self.overlaps_with(key, order[j])) {
+                Some(j) => {
+                    order.remove(i);
+                    order.insert(j, key);
+                    limit = j;
+                }
+                None => limit = i,
+            }
+        }
+        order
+    }
+
+    /// Move the selected keys of the chronologically ordered keys below the previous overlapping key.
+    ///
+    /// Selected keys don't move past each other.
+    fn step_backward(
+        &self,
+        mut order: Vec<StrokeKey>,
+        selected: &HashSet<StrokeKey>,
+    ) -> Vec<StrokeKey> {
+        // The selected keys can only be moved above this index, which keeps their relative order
+        let mut limit = 0;
+        for i in 0..order.len() {
+            let key = order[i];
+            if !selected.contains(&key) {
+                continue;
+            }
+            match (limit..i)
+                .rev()
+                .find(|&j| self.overlaps_with(key, order[j]))
+            {
+                Some(j) => {
+                    order.remove(i);
+                    order.insert(j, key);
+                    limit = j + 1;
+                }
+                None => limit = i + 1,
+            }
+        }
+        order
+    }
+
     /// Returns the keys in chronological order, as in first: gets drawn first, last: gets drawn last.
     pub(crate) fn keys_sorted_chrono(&self) -> Vec<StrokeKey> {
         let mut keys = self.stroke_components.keys().collect::<Vec<StrokeKey>>();
@@ -119,7 +259,7 @@ impl StrokeStore {
             if let Some(first_chrono) = chrono_components.get(first)
                 && let Some(second_chrono) = chrono_components.get(second)
             {
-                let layer_order = first_chrono.layer.cmp(&second_chrono.layer);
+                let layer_order = self.cmp_stroke_layers(first_chrono.layer, second_chrono.layer);
 
                 if layer_order != std::cmp::Ordering::Equal {
                     layer_order
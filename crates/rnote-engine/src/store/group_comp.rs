@@ -0,0 +1,208 @@
+// Imports
+use super::{StrokeKey, StrokeStore};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// The groups a stroke is a member of.
+///
+/// Groups can be nested. A group consists of all strokes that have its id in their group component.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename = "group_component")]
+pub struct GroupComponent {
+    /// The ids of the groups, from the outermost to the innermost group.
+    #[serde(rename = "groups")]
+    pub groups: Vec<u32>,
+}
+
+impl GroupComponent {
+    /// The outermost group, which is selected as a whole.
+    pub fn outermost(&self) -> Option<u32> {
+        self.groups.first().copied()
+    }
+}
+
+/// Systems that are related to stroke groups.
+impl StrokeStore {
+    /// Rebuild the slotmap of the group components, keeping the existing ones for the keys of the stroke components
+    /// and inserting empty components for the others.
+    pub(crate) fn rebuild_group_components_slotmap(&mut self) {
+        let group_components = self
+            .stroke_components
+            .keys()
+            .map(|key| {
+                let group_comp = self.group_components.get(key).cloned().unwrap_or_default();
+                (key, group_comp)
+            })
+            .collect();
+        self.group_components = Arc::new(group_components);
+    }
+
+    pub(crate) fn group_component(&self, key: StrokeKey) -> Option<&GroupComponent> {
+        self.group_components.get(key).map(Arc::as_ref)
+    }
+
+    /// Replace the group component of the stroke.
+    pub(crate) fn set_group_component(&mut self, key: StrokeKey, group_comp: GroupComponent) {
+        if self
+            .group_component(key)
+            .is_none_or(|current| *current == group_comp)
+        {
+            return;
+        }
+        self.track_modified(key);
+        Arc::make_mut(&mut self.group_components).insert(key, Arc::new(group_comp));
+    }
+
+    /// A group id that is not yet used by any stroke.
+    fn new_group_id(&self) -> u32 {
+        self.group_components
+            .values()
+            .flat_map(|group_comp| group_comp.groups.iter())
+            .max()
+            .map_or(0, |id| id + 1)
+    }
+
+    /// Extend the keys by the other members of the outermost groups they are in.
+    ///
+    /// Trashed strokes and strokes in layers that can't be edited are not added.
+    /// The given keys come first and keep their order.
+    pub(crate) fn keys_w_group_members(&self, keys: &[StrokeKey]) -> Vec<StrokeKey> {
+        let outermost_groups = keys
+            .iter()
+            .filter_map(|&key| self.group_component(key)?.outermost())
+            .collect::<HashSet<u32>>();
+        let mut keys_set = keys.iter().copied().collect::<HashSet<StrokeKey>>();
+        let mut extended = keys.to_vec();
+        if outermost_groups.is_empty() {
+            return extended;
+        }
+
+        for key in self.keys_sorted_chrono() {
+            if !keys_set.contains(&key)
+                && !self.trashed(key).unwrap_or(false)
+                && self.layer_editable(key)
+                && self
+                    .group_component(key)
+                    .and_then(GroupComponent::outermost)
+                    .is_some_and(|group| outermost_groups.contains(&group))
+            {
+                keys_set.insert(key);
+                extended.push(key);
+            }
+        }
+        extended
+    }
+
+    /// Partition the keys into units that are arranged together: the members of each outermost group,
+    /// and single strokes that are not grouped.
+    ///
+    /// The units are ordered by their first key.
+    pub(crate) fn group_units(&self, keys: &[StrokeKey]) -> Vec<Vec<StrokeKey>> {
+        let mut units = Vec::<Vec<StrokeKey>>::new();
+        let mut group_unit_indices = HashMap::<u32, usize>::new();
+        for &key in keys {
+            match self
+                .group_component(key)
+                .and_then(GroupComponent::outermost)
+            {
+                Some(group) => match group_unit_indices.get(&group) {
+                    Some(&i) => units[i].push(key),
+                    None => {
+                        group_unit_indices.insert(group, units.len());
+                        units.push(vec![key]);
+                    }
+                },
+                None => units.push(vec![key]),
+            }
+        }
+        units
+    }
+
+    /// Group the strokes, including the other members of the groups they are already in.
+    ///
+    /// Existing groups become nested in the new group.
+    /// Returns the id of the new group, or None if there were not at least two strokes to group.
+    pub(crate) fn group_strokes(&mut self, keys: &[StrokeKey]) -> Option<u32> {
+        let keys = self.keys_w_group_members(keys);
+        let already_grouped = self
+            .group_component(*keys.first()?)
+            .and_then(GroupComponent::outermost)
+            .is_some_and(|first_group| {
+                keys.iter().all(|&key| {
+                    self.group_component(key)
+                        .and_then(GroupComponent::outermost)
+                        == Some(first_group)
+                })
+            });
+        if keys.len() < 2 || already_grouped {
+            return None;
+        }
+
+        let group_id = self.new_group_id();
+        for key in keys {
+            let Some(mut group_comp) = self.group_component(key).cloned() else {
+                continue;
+            };
+            group_comp.groups.insert(0, group_id);
+            self.set_group_component(key, group_comp);
+        }
+        Some(group_id)
+    }
+
+    /// Dissolve the outermost groups of the strokes, leaving the groups nested in them intact.
+    ///
+    /// Returns true if any group was dissolved.
+    pub(crate) fn ungroup_strokes(&mut self, keys: &[StrokeKey]) -> bool {
+        let mut ungrouped = false;
+        for key in self.keys_w_group_members(keys) {
+            let Some(mut group_comp) = self.group_component(key).cloned() else {
+                continue;
+            };
+            if group_comp.groups.is_empty() {
+                continue;
+            }
+            group_comp.groups.remove(0);
+            self.set_group_component(key, group_comp);
+            ungrouped = true;
+        }
+        ungrouped
+    }
+
+    /// The group components of the strokes, in the same order.
+    pub(crate) fn group_components_for_keys(&self, keys: &[StrokeKey]) -> Vec<GroupComponent> {
+        keys.iter()
+            .map(|&key| self.group_component(key).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    /// Put the strokes into copies of the given groups, in the same order.
+    ///
+    /// The group ids are replaced by new ones, so that the strokes don't become members of existing groups.
+    /// Groups that end up with a single member are left out.
+    pub(crate) fn insert_group_copies(&mut self, keys: &[StrokeKey], groups: &[GroupComponent]) {
+        let mut n_members = HashMap::<u32, usize>::new();
+        for group_comp in groups.iter().take(keys.len()) {
+            for &id in group_comp.groups.iter() {
+                *n_members.entry(id).or_default() += 1;
+            }
+        }
+        let mut next_id = self.new_group_id();
+        let mut new_ids = HashMap::<u32, u32>::new();
+
+        for (&key, group_comp) in keys.iter().zip(groups.iter()) {
+            let groups = group_comp
+                .groups
+                .iter()
+                .filter(|id| n_members.get(id).copied().unwrap_or(0) > 1)
+                .map(|id| {
+                    *new_ids.entry(*id).or_insert_with(|| {
+                        next_id += 1;
+                        next_id - 1
+                    })
+                })
+                .collect();
+            self.set_group_component(key, GroupComponent { groups });
+        }
+    }
+}
@@ -0,0 +1,451 @@
+// Imports
+use super::{
+    ChronoComponent, GroupComponent, RenderComponent, StrokeKey, StrokeStore, TrashComponent,
+    UserLayers,
+};
+use crate::WidgetFlags;
+use crate::strokes::Stroke;
+use rnote_compose::penpath::Segment;
+use rnote_compose::shapes::Shapeable;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::debug;
+
+/// The state of a single stroke that is tracked by the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "stroke_state")]
+pub struct StrokeState {
+    #[serde(rename = "stroke")]
+    pub stroke: Arc<Stroke>,
+    #[serde(rename = "trash_component")]
+    pub trash_component: Arc<TrashComponent>,
+    #[serde(rename = "chrono_component")]
+    pub chrono_component: Arc<ChronoComponent>,
+    #[serde(default, rename = "group_component")]
+    pub group_component: Arc<GroupComponent>,
+}
+
+impl StrokeState {
+    /// Checks the equality to the other state by doing pointer compares.
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.stroke, &other.stroke)
+            && Arc::ptr_eq(&self.trash_component, &other.trash_component)
+            && Arc::ptr_eq(&self.chrono_component, &other.chrono_component)
+            && Arc::ptr_eq(&self.group_component, &other.group_component)
+    }
+
+    /// A rough estimate of the memory the state occupies, in bytes.
+    fn memory_size(&self) -> usize {
+        let stroke_size = match self.stroke.as_ref() {
+            Stroke::BrushStroke(brushstroke) => {
+                brushstroke.path.segments.len() * std::mem::size_of::<Segment>()
+            }
+            Stroke::ShapeStroke(_) => 0,
+            Stroke::TextStroke(textstroke) => textstroke.text.len(),
+            Stroke::VectorImage(vectorimage) => vectorimage.svg_data.len(),
+            Stroke::BitmapImage(bitmapimage) => bitmapimage.image.data.len(),
+        };
+        std::mem::size_of::<Stroke>()
+            + std::mem::size_of::<TrashComponent>()
+            + std::mem::size_of::<ChronoComponent>()
+            + std::mem::size_of::<GroupComponent>()
+            + self.group_component.groups.len() * std::mem::size_of::<u32>()
+            + stroke_size
+    }
+}
+
+/// The change of a single stroke between two recorded states.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "stroke_diff")]
+pub struct StrokeDiff {
+    #[serde(rename = "key")]
+    pub key: StrokeKey,
+    /// The state before the change. `None` when the stroke got inserted.
+    #[serde(rename = "before")]
+    pub before: Option<StrokeState>,
+    /// The state after the change. `None` when the stroke got removed.
+    #[serde(rename = "after")]
+    pub after: Option<StrokeState>,
+}
+
+/// A history entry, holding the changes between two recorded states of the store.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "history_entry")]
+pub struct HistoryEntry {
+    #[serde(rename = "stroke_diffs")]
+    pub stroke_diffs: Vec<StrokeDiff>,
+    #[serde(rename = "chrono_counter_before")]
+    pub chrono_counter_before: u32,
+    #[serde(rename = "chrono_counter_after")]
+    pub chrono_counter_after: u32,
+    /// The user layers before and after the change, if they have changed.
+    #[serde(rename = "user_layers")]
+    pub user_layers: Option<(Arc<UserLayers>, Arc<UserLayers>)>,
+}
+
+impl HistoryEntry {
+    pub fn is_empty(&self) -> bool {
+        self.stroke_diffs.is_empty()
+            && self.chrono_counter_before == self.chrono_counter_after
+            && self.user_layers.is_none()
+    }
+
+    /// A rough estimate of the memory the entry occupies, in bytes.
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self
+                .stroke_diffs
+                .iter()
+                .map(|diff| {
+                    std::mem::size_of::<StrokeDiff>()
+                        + diff.before.as_ref().map_or(0, StrokeState::memory_size)
+                        + diff.after.as_ref().map_or(0, StrokeState::memory_size)
+                })
+                .sum::<usize>()
+    }
+
+    /// Merge the changes of a later entry into this one.
+    fn merge(&mut self, later: HistoryEntry) {
+        let mut diff_indices = self
+            .stroke_diffs
+            .iter()
+            .enumerate()
+            .map(|(i, diff)| (diff.key, i))
+            .collect::<HashMap<StrokeKey, usize>>();
+
+        for diff in later.stroke_diffs {
+            if let Some(&i) = diff_indices.get(&diff.key) {
+                self.stroke_diffs[i].after = diff.after;
+            } else {
+                diff_indices.insert(diff.key, self.stroke_diffs.len());
+                self.stroke_diffs.push(diff);
+            }
+        }
+        self.chrono_counter_after = later.chrono_counter_after;
+        self.user_layers = match (self.user_layers.take(), later.user_layers) {
+            (Some((before, _)), Some((_, after))) => Some((before, after)),
+            (user_layers, None) | (None, user_layers) => user_layers,
+        };
+    }
+}
+
+/// Systems that are related to the history.
+///
+/// Instead of recording the entire state, only the changes to the strokes are recorded.
+/// Every system that modifies, inserts or removes stroke, trash, chrono or group components must track them
+/// with [StrokeStore::track_modified()] or [StrokeStore::track_inserted()] beforehand.
+impl StrokeStore {
+    /// The memory budget of the history in bytes.
+    ///
+    /// When it is exceeded, the oldest entries are dropped.
+    pub(crate) const HISTORY_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+    /// The memory budget of the history that is saved in snapshots, in bytes.
+    pub(crate) const SAVED_HISTORY_MEMORY_BUDGET: usize = 16 * 1024 * 1024;
+
+    /// The current state of the stroke as it is tracked by the history.
+    fn stroke_state(&self, key: StrokeKey) -> Option<StrokeState> {
+        Some(StrokeState {
+            stroke: Arc::clone(self.stroke_components.get(key)?),
+            trash_component: Arc::clone(self.trash_components.get(key)?),
+            chrono_component: Arc::clone(self.chrono_components.get(key)?),
+            group_component: self.group_components.get(key).cloned().unwrap_or_default(),
+        })
+    }
+
+    /// Track the stroke as modified, saving its state before the modification if it isn't already tracked.
+    ///
+    /// Must be called before the stroke, trash, chrono or group component of the stroke get modified or removed.
+    pub(crate) fn track_modified(&mut self, key: StrokeKey) {
+        if !self.history_pending.contains_key(&key) {
+            let state = self.stroke_state(key);
+            self.history_pending.insert(key, state);
+        }
+    }
+
+    /// Track the stroke as newly inserted.
+    pub(crate) fn track_inserted(&mut self, key: StrokeKey) {
+        self.history_pending.entry(key).or_insert(None);
+    }
+
+    /// Collect the tracked changes since the last recorded state into a history entry.
+    fn take_pending_changes(&mut self) -> HistoryEntry {
+        let stroke_diffs = std::mem::take(&mut self.history_pending)
+            .into_iter()
+            .filter_map(|(key, before)| {
+                let after = self.stroke_state(key);
+                match (&before, &after) {
+                    (None, None) => return None,
+                    (Some(before), Some(after)) if before.ptr_eq(after) => return None,
+                    _ => {}
+                }
+                Some(StrokeDiff { key, before, after })
+            })
+            .collect();
+        let user_layers =
+            (!Arc::ptr_eq(&self.recorded_user_layers, &self.user_layers)).then(|| {
+                (
+                    Arc::clone(&self.recorded_user_layers),
+                    Arc::clone(&self.user_layers),
+                )
+            });
+        let entry = HistoryEntry {
+            stroke_diffs,
+            chrono_counter_before: self.recorded_chrono_counter,
+            chrono_counter_after: self.chrono_counter,
+            user_layers,
+        };
+
+        self.recorded_chrono_counter = self.chrono_counter;
+        self.recorded_user_layers = Arc::clone(&self.user_layers);
+        entry
+    }
+
+    /// Revert the tracked changes that were not yet recorded.
+    fn discard_pending_changes(&mut self) {
+        for (key, before) in std::mem::take(&mut self.history_pending) {
+            self.apply_stroke_state(key, before);
+        }
+        self.chrono_counter = self.recorded_chrono_counter;
+        self.user_layers = Arc::clone(&self.recorded_user_layers);
+    }
+
+    /// Replace the components of the stroke with the given state, or remove it when the state is `None`.
+    fn apply_stroke_state(&mut self, key: StrokeKey, state: Option<StrokeState>) {
+        let Some(state) = state else {
+            Arc::make_mut(&mut self.trash_components).remove(key);
+            Arc::make_mut(&mut self.selection_components).remove(key);
+            Arc::make_mut(&mut self.chrono_components).remove(key);
+            Arc::make_mut(&mut self.group_components).remove(key);
+            Arc::make_mut(&mut self.stroke_components).remove(key);
+            self.render_components.remove(key);
+            self.key_tree.remove_with_key(key);
+            return;
+        };
+        let bounds = state.stroke.bounds();
+
+        let key = if let Some(stroke) = Arc::make_mut(&mut self.stroke_components).get_mut(key) {
+            *stroke = state.stroke;
+            self.key_tree.update_with_key(key, bounds);
+            key
+        } else {
+            // The stroke was removed from the store, so it needs to be inserted again with a new key.
+            let new_key = Arc::make_mut(&mut self.stroke_components).insert(state.stroke);
+            self.key_tree.insert_with_key(new_key, bounds);
+            self.remap_history_key(key, new_key);
+            new_key
+        };
+        Arc::make_mut(&mut self.trash_components).insert(key, state.trash_component);
+        Arc::make_mut(&mut self.chrono_components).insert(key, state.chrono_component);
+        Arc::make_mut(&mut self.group_components).insert(key, state.group_component);
+        if !self.render_components.contains_key(key) {
+            self.render_components
+                .insert(key, RenderComponent::default());
+        }
+        self.set_rendering_dirty(key);
+    }
+
+    /// Replace all occurrences of the old key in the history with the new key.
+    fn remap_history_key(&mut self, old_key: StrokeKey, new_key: StrokeKey) {
+        for diff in self
+            .history
+            .iter_mut()
+            .flat_map(|entry| entry.stroke_diffs.iter_mut())
+            .filter(|diff| diff.key == old_key)
+        {
+            diff.key = new_key;
+        }
+    }
+
+    /// Remove all entries that could be redone.
+    fn truncate_history_future(&mut self) {
+        for entry in self.history.drain(self.live_index..) {
+            self.history_memory_size = self.history_memory_size.saturating_sub(entry.memory_size());
+        }
+    }
+
+    /// Record the current state and save the changes in the history.
+    pub(crate) fn record(&mut self, _now: Instant) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        let entry = self.take_pending_changes();
+        if !entry.is_empty() {
+            // as soon as the current state is recorded, remove the future
+            self.truncate_history_future();
+
+            self.history_memory_size += entry.memory_size();
+            self.history.push_back(entry);
+            self.live_index += 1;
+
+            // drop the oldest entries if the memory budget is exceeded, but always keep the latest one
+            while self.history_memory_size > Self::HISTORY_MEMORY_BUDGET && self.history.len() > 1 {
+                if let Some(oldest) = self.history.pop_front() {
+                    self.history_memory_size = self
+                        .history_memory_size
+                        .saturating_sub(oldest.memory_size());
+                    self.live_index -= 1;
+                }
+            }
+        } else {
+            debug!("State has not changed, no need to record.");
+        }
+
+        widget_flags.hide_undo = Some(!self.can_undo());
+        widget_flags.hide_redo = Some(!self.can_redo());
+
+        widget_flags
+    }
+
+    /// Update the latest history entry with the changes of the current state.
+    pub(crate) fn update_latest_history_entry(&mut self, _now: Instant) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        let changes = self.take_pending_changes();
+        if !changes.is_empty() {
+            // as soon as the current state is recorded, remove the future
+            self.truncate_history_future();
+
+            // Without any recorded entry the changes become part of the initial state.
+            if let Some(latest) = self
+                .live_index
+                .checked_sub(1)
+                .and_then(|i| self.history.get_mut(i))
+            {
+                self.history_memory_size = self
+                    .history_memory_size
+                    .saturating_sub(latest.memory_size());
+                latest.merge(changes);
+                self.history_memory_size += latest.memory_size();
+            }
+        } else {
+            debug!("State has not changed, no need to update history with current state.");
+        }
+
+        widget_flags.hide_undo = Some(!self.can_undo());
+        widget_flags.hide_redo = Some(!self.can_redo());
+
+        widget_flags
+    }
+
+    /// Undo the latest changes.
+    ///
+    /// Should only be called from inside the engine undo wrapper function.
+    pub(crate) fn undo(&mut self, _now: Instant) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if !self.can_undo() {
+            return widget_flags;
+        }
+
+        self.discard_pending_changes();
+        self.live_index -= 1;
+        let entry = self.history[self.live_index].clone();
+
+        for diff in entry.stroke_diffs.into_iter().rev() {
+            self.apply_stroke_state(diff.key, diff.before);
+        }
+        self.chrono_counter = entry.chrono_counter_before;
+        if let Some((user_layers, _)) = entry.user_layers {
+            self.user_layers = user_layers;
+        }
+        self.recorded_chrono_counter = self.chrono_counter;
+        self.recorded_user_layers = Arc::clone(&self.user_layers);
+        self.rebuild_selection_components_slotmap();
+
+        widget_flags.hide_undo = Some(!self.can_undo());
+        widget_flags.hide_redo = Some(!self.can_redo());
+        widget_flags.store_modified = true;
+
+        widget_flags
+    }
+
+    /// Redo the latest changes.
+    ///
+    /// Should only be called from inside the engine redo wrapper function.
+    pub(crate) fn redo(&mut self, _now: Instant) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if !self.can_redo() {
+            return widget_flags;
+        }
+
+        self.discard_pending_changes();
+        let entry = self.history[self.live_index].clone();
+        self.live_index += 1;
+
+        for diff in entry.stroke_diffs {
+            self.apply_stroke_state(diff.key, diff.after);
+        }
+        self.chrono_counter = entry.chrono_counter_after;
+        if let Some((_, user_layers)) = entry.user_layers {
+            self.user_layers = user_layers;
+        }
+        self.recorded_chrono_counter = self.chrono_counter;
+        self.recorded_user_layers = Arc::clone(&self.user_layers);
+        self.rebuild_selection_components_slotmap();
+
+        widget_flags.hide_undo = Some(!self.can_undo());
+        widget_flags.hide_redo = Some(!self.can_redo());
+        widget_flags.store_modified = true;
+
+        widget_flags
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        self.live_index > 0
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        self.live_index < self.history.len()
+    }
+
+    /// Export the newest entries that can be undone and fit into the saved history memory budget,
+    /// ordered from the oldest to the newest.
+    pub(crate) fn export_history(&self) -> Vec<HistoryEntry> {
+        let mut memory_size = 0;
+        let mut entries = self
+            .history
+            .range(..self.live_index)
+            .rev()
+            .take_while(|entry| {
+                memory_size += entry.memory_size();
+                memory_size <= Self::SAVED_HISTORY_MEMORY_BUDGET
+            })
+            .cloned()
+            .collect::<Vec<HistoryEntry>>();
+        entries.reverse();
+        entries
+    }
+
+    /// Import history entries that lead up to the current state, replacing the current history.
+    pub(crate) fn import_history(&mut self, entries: Vec<HistoryEntry>) -> WidgetFlags {
+        let mut widget_flags = self.clear_history();
+
+        self.history_memory_size = entries.iter().map(HistoryEntry::memory_size).sum();
+        self.live_index = entries.len();
+        self.history = entries.into();
+
+        widget_flags.hide_undo = Some(!self.can_undo());
+        widget_flags.hide_redo = Some(!self.can_redo());
+
+        widget_flags
+    }
+
+    /// Clear the history, making the current state the initial state.
+    pub(crate) fn clear_history(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        self.history.clear();
+        self.live_index = 0;
+        self.history_memory_size = 0;
+        self.history_pending.clear();
+        self.recorded_chrono_counter = self.chrono_counter;
+        self.recorded_user_layers = Arc::clone(&self.user_layers);
+
+        widget_flags.hide_undo = Some(true);
+        widget_flags.hide_redo = Some(true);
+
+        widget_flags
+    }
+}
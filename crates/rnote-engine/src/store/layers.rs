@@ -0,0 +1,339 @@
+// Imports
+use super::chrono_comp::StrokeLayer;
+use super::{StrokeKey, StrokeStore};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// The properties of a single user layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "user_layer_props")]
+pub struct UserLayerProps {
+    /// The id that is referenced by [StrokeLayer::UserLayer].
+    #[serde(rename = "id")]
+    pub id: u32,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "visible")]
+    pub visible: bool,
+    /// Locked layers are still rendered, but their strokes can't be selected or erased.
+    #[serde(rename = "locked")]
+    pub locked: bool,
+    #[serde(rename = "opacity", with = "rnote_compose::serialize::f64_dp3")]
+    pub opacity: f64,
+}
+
+impl Default for UserLayerProps {
+    fn default() -> Self {
+        Self::new(0, String::from("Layer 1"))
+    }
+}
+
+impl UserLayerProps {
+    pub const OPACITY_MIN: f64 = 0.0;
+    pub const OPACITY_MAX: f64 = 1.0;
+
+    pub fn new(id: u32, name: String) -> Self {
+        Self {
+            id,
+            name,
+            visible: true,
+            locked: false,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// The table of user layers, ordered from the bottom to the top layer.
+///
+/// Always holds at least one layer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "user_layers")]
+pub struct UserLayers {
+    #[serde(rename = "layers")]
+    layers: Vec<UserLayerProps>,
+    /// The id of the layer new strokes are inserted into.
+    #[serde(rename = "current")]
+    current: u32,
+}
+
+impl Default for UserLayers {
+    fn default() -> Self {
+        Self {
+            layers: vec![UserLayerProps::default()],
+            current: 0,
+        }
+    }
+}
+
+impl UserLayers {
+    /// Creates new layers with the given names, ordered from the bottom to the top layer.
+    pub fn from_names(names: impl IntoIterator<Item = String>) -> Self {
+        let layers = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| UserLayerProps::new(i as u32, name))
+            .collect::<Vec<UserLayerProps>>();
+
+        if layers.is_empty() {
+            return Self::default();
+        }
+        Self { layers, current: 0 }
+    }
+
+    /// Iterate over the layers, from the bottom to the top layer.
+    pub fn iter(&self) -> impl Iterator<Item = &UserLayerProps> {
+        self.layers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&UserLayerProps> {
+        self.layers.iter().find(|layer| layer.id == id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u32) -> Option<&mut UserLayerProps> {
+        self.layers.iter_mut().find(|layer| layer.id == id)
+    }
+
+    /// The position of the layer with the given id, counting from the bottom layer.
+    pub fn position(&self, id: u32) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.id == id)
+    }
+
+    /// The id of the layer new strokes are inserted into.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Sets the current layer. Returns false if no layer with the given id exists.
+    pub(crate) fn set_current(&mut self, id: u32) -> bool {
+        if self.get(id).is_none() {
+            return false;
+        }
+        self.current = id;
+        true
+    }
+
+    /// Layers that are not present in the table are treated as visible.
+    pub fn visible(&self, id: u32) -> bool {
+        self.get(id).map(|layer| layer.visible).unwrap_or(true)
+    }
+
+    /// Layers that are not present in the table are treated as unlocked.
+    pub fn locked(&self, id: u32) -> bool {
+        self.get(id).map(|layer| layer.locked).unwrap_or(false)
+    }
+
+    pub fn opacity(&self, id: u32) -> f64 {
+        self.get(id).map(|layer| layer.opacity).unwrap_or(1.0)
+    }
+
+    /// Inserts a new layer on top of all other layers and returns its id.
+    pub(crate) fn insert(&mut self, name: String) -> u32 {
+        let id = self
+            .layers
+            .iter()
+            .map(|layer| layer.id + 1)
+            .max()
+            .unwrap_or(0);
+        self.layers.push(UserLayerProps::new(id, name));
+        id
+    }
+
+    /// Ensures a layer with the given id exists, inserting it on top if it doesn't.
+    pub(crate) fn ensure(&mut self, id: u32) {
+        if self.get(id).is_none() {
+            let name = format!("Layer {}", self.layers.len() + 1);
+            self.layers.push(UserLayerProps::new(id, name));
+        }
+    }
+
+    /// Removes the layer with the given id.
+    ///
+    /// The last remaining layer can't be removed.
+    /// If the current layer is removed, the layer below it becomes the current one.
+    pub(crate) fn remove(&mut self, id: u32) -> Option<UserLayerProps> {
+        if self.layers.len() <= 1 {
+            return None;
+        }
+        let position = self.position(id)?;
+        let removed = self.layers.remove(position);
+
+        if self.current == id {
+            self.current = self.layers[position.saturating_sub(1)].id;
+        }
+        Some(removed)
+    }
+
+    /// Moves the layer with the given id to the new position, counting from the bottom layer.
+    ///
+    /// Returns false if the layer does not exist or the position did not change.
+    pub(crate) fn move_to(&mut self, id: u32, position: usize) -> bool {
+        let Some(old_position) = self.position(id) else {
+            return false;
+        };
+        let position = position.min(self.layers.len() - 1);
+        if old_position == position {
+            return false;
+        }
+        let layer = self.layers.remove(old_position);
+        self.layers.insert(position, layer);
+        true
+    }
+
+    /// Compares the given layers by their position in the table.
+    ///
+    /// Layers that are not present in the table are ordered above all others, by their id.
+    pub(crate) fn cmp_ids(&self, first: u32, second: u32) -> Ordering {
+        match (self.position(first), self.position(second)) {
+            (Some(first_pos), Some(second_pos)) => first_pos.cmp(&second_pos),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => first.cmp(&second),
+        }
+    }
+}
+
+/// Systems that are related to the user layers.
+impl StrokeStore {
+    pub(crate) fn user_layers(&self) -> &UserLayers {
+        &self.user_layers
+    }
+
+    pub(crate) fn user_layers_mut(&mut self) -> &mut UserLayers {
+        Arc::make_mut(&mut self.user_layers)
+    }
+
+    /// Replaces the user layers.
+    ///
+    /// Layers that are still referenced by strokes but are missing in the new table are added again.
+    pub(crate) fn set_user_layers(&mut self, user_layers: UserLayers) {
+        self.user_layers = Arc::new(user_layers);
+        self.ensure_user_layers_for_strokes();
+    }
+
+    /// The layer of the stroke.
+    pub(crate) fn stroke_layer(&self, key: StrokeKey) -> Option<StrokeLayer> {
+        self.chrono_components
+            .get(key)
+            .map(|chrono_comp| chrono_comp.layer)
+    }
+
+    /// Compares the given stroke layers in the order they are rendered, respecting the order of the user layers.
+    pub(crate) fn cmp_stroke_layers(&self, first: StrokeLayer, second: StrokeLayer) -> Ordering {
+        match (first, second) {
+            (StrokeLayer::UserLayer(first_id), StrokeLayer::UserLayer(second_id)) => {
+                self.user_layers.cmp_ids(first_id, second_id)
+            }
+            _ => first.cmp(&second),
+        }
+    }
+
+    /// Whether the layer of the stroke is visible. Strokes that are not in a user layer are always visible.
+    pub(crate) fn layer_visible(&self, key: StrokeKey) -> bool {
+        match self.stroke_layer(key) {
+            Some(StrokeLayer::UserLayer(id)) => self.user_layers.visible(id),
+            _ => true,
+        }
+    }
+
+    /// Whether the stroke can be selected, erased or otherwise be interacted with, depending on the state of its layer.
+    pub(crate) fn layer_editable(&self, key: StrokeKey) -> bool {
+        match self.stroke_layer(key) {
+            Some(StrokeLayer::UserLayer(id)) => {
+                self.user_layers.visible(id) && !self.user_layers.locked(id)
+            }
+            _ => true,
+        }
+    }
+
+    /// The position of the user layer of the stroke in the layer table.
+    ///
+    /// Highlighter strokes are placed in the bottom layer, images and document strokes are not in any user layer.
+    pub(crate) fn user_layer_position(&self, key: StrokeKey) -> Option<usize> {
+        match self.stroke_layer(key)? {
+            StrokeLayer::UserLayer(id) => Some(
+                self.user_layers
+                    .position(id)
+                    .unwrap_or(self.user_layers.len() - 1),
+            ),
+            StrokeLayer::Highlighter => Some(0),
+            StrokeLayer::Image | StrokeLayer::Document => None,
+        }
+    }
+
+    /// All stroke keys in the given user layer that are not trashed, unordered.
+    pub(crate) fn keys_in_user_layer(&self, id: u32) -> Vec<StrokeKey> {
+        self.stroke_keys_unordered()
+            .into_iter()
+            .filter(|&key| self.stroke_layer(key) == Some(StrokeLayer::UserLayer(id)))
+            .collect()
+    }
+
+    /// Moves the strokes into the given layer, on top of the strokes that are already in it.
+    pub(crate) fn set_stroke_layer(&mut self, keys: &[StrokeKey], layer: StrokeLayer) {
+        if let StrokeLayer::UserLayer(id) = layer
+            && self.user_layers.get(id).is_none()
+        {
+            self.user_layers_mut().ensure(id);
+        }
+        for &key in keys {
+            self.track_modified(key);
+            if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components).get_mut(key) {
+                Arc::make_mut(chrono_comp).layer = layer;
+            }
+            self.update_chrono_to_last(key);
+        }
+    }
+
+    /// Modifies the properties of the user layer with the given id.
+    ///
+    /// Returns true if they were changed.
+    pub(crate) fn modify_user_layer(
+        &mut self,
+        id: u32,
+        modify: impl FnOnce(&mut UserLayerProps),
+    ) -> bool {
+        let Some(mut props) = self.user_layers.get(id).cloned() else {
+            return false;
+        };
+        modify(&mut props);
+        props.opacity = props
+            .opacity
+            .clamp(UserLayerProps::OPACITY_MIN, UserLayerProps::OPACITY_MAX);
+
+        if self.user_layers.get(id) == Some(&props) {
+            return false;
+        }
+        if let Some(layer) = self.user_layers_mut().get_mut(id) {
+            *layer = props;
+        }
+        true
+    }
+
+    /// Ensures all user layers referenced by the stored strokes exist in the layer table.
+    pub(crate) fn ensure_user_layers_for_strokes(&mut self) {
+        let ids = self
+            .stroke_components
+            .keys()
+            .filter_map(|key| match self.stroke_layer(key)? {
+                StrokeLayer::UserLayer(id) => Some(id),
+                _ => None,
+            })
+            .collect::<BTreeSet<u32>>();
+
+        for id in ids {
+            if self.user_layers.get(id).is_none() {
+                self.user_layers_mut().ensure(id);
+            }
+        }
+    }
+}
@@ -234,6 +234,9 @@ impl StrokeStore {
     }
 
     /// Regenerate the rendering of all keys for the given viewport that need to be rerendered.
+    ///
+    /// When the view is rotated, the viewport is the bounding box of the rotated viewport rectangle,
+    /// as returned by `Camera::viewport()`.
     pub(crate) fn regenerate_rendering_in_viewport_threaded(
         &mut self,
         tasks_tx: EngineTaskSender,
@@ -489,13 +492,35 @@ impl StrokeStore {
         doc_bounds: Aabb,
         viewport: Aabb,
     ) {
+        use super::chrono_comp::StrokeLayer;
         use crate::ext::{GdkRGBAExt, GrapheneRectExt};
         use gtk4::{gdk, graphene, prelude::*};
         use rnote_compose::color;
 
         snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds));
 
+        // The keys are sorted by their layer, so the strokes of a translucent user layer
+        // can be grouped together with a single opacity node.
+        let mut current_layer = None;
+        let mut opacity_pushed = false;
+
         for key in self.stroke_keys_as_rendered_intersecting_bounds(viewport) {
+            let layer = self.stroke_layer(key);
+            if layer != current_layer {
+                if opacity_pushed {
+                    snapshot.pop();
+                    opacity_pushed = false;
+                }
+                if let Some(StrokeLayer::UserLayer(id)) = layer {
+                    let opacity = self.user_layers.opacity(id);
+                    if opacity < 1.0 {
+                        snapshot.push_opacity(opacity);
+                        opacity_pushed = true;
+                    }
+                }
+                current_layer = layer;
+            }
+
             if let Some(stroke) = self.stroke_components.get(key)
                 && let Some(render_comp) = self.render_components.get(key)
             {
@@ -519,6 +544,9 @@ impl StrokeStore {
             }
         }
 
+        if opacity_pushed {
+            snapshot.pop();
+        }
         snapshot.pop();
     }
 
@@ -13,11 +13,17 @@ use std::sync::Arc;
 pub struct SelectionComponent {
     #[serde(default, rename = "selected")]
     pub selected: bool,
+    /// Increases with every stroke that gets selected, so that the order of selecting can be retraced.
+    #[serde(skip)]
+    pub selected_order: u32,
 }
 
 impl Default for SelectionComponent {
     fn default() -> Self {
-        Self { selected: false }
+        Self {
+            selected: false,
+            selected_order: 0,
+        }
     }
 }
 
@@ -50,12 +56,23 @@ impl StrokeStore {
             .get_mut(key)
             .map(Arc::make_mut)
         {
+            if selected && !selection_comp.selected {
+                self.selection_counter += 1;
+                selection_comp.selected_order = self.selection_counter;
+            }
             selection_comp.selected = selected;
-
-            self.update_chrono_to_last(key);
         }
     }
 
+    /// The key of the given keys that was selected first.
+    pub(crate) fn first_selected_key(&self, keys: &[StrokeKey]) -> Option<StrokeKey> {
+        keys.iter()
+            .filter_map(|&key| Some((key, self.selection_components.get(key)?)))
+            .filter(|(_, selection_comp)| selection_comp.selected)
+            .min_by_key(|(_, selection_comp)| selection_comp.selected_order)
+            .map(|(key, _)| key)
+    }
+
     pub(crate) fn set_selected_keys(&mut self, keys: &[StrokeKey], selected: bool) {
         keys.iter().for_each(|&key| {
             self.set_selected(key, selected);
@@ -99,12 +116,15 @@ impl StrokeStore {
     pub(crate) fn duplicate_selection(&mut self) -> Vec<StrokeKey> {
         let old_selected = self.selection_keys_as_rendered();
         self.set_selected_keys(&old_selected, false);
+        let old_groups = self.group_components_for_keys(&old_selected);
 
         let new_selected = old_selected
             .iter()
             .filter_map(|&old_key| {
-                let new_key =
-                    self.insert_stroke((**self.stroke_components.get(old_key)?).clone(), None);
+                let new_key = self.insert_stroke(
+                    (**self.stroke_components.get(old_key)?).clone(),
+                    self.stroke_layer(old_key),
+                );
                 self.set_selected(new_key, true);
 
                 // duplicate and insert the render images of the old stroke to avoid flickering
@@ -125,6 +145,7 @@ impl StrokeStore {
                 Some(new_key)
             })
             .collect::<Vec<StrokeKey>>();
+        self.insert_group_copies(&new_selected, &old_groups);
 
         // Offsetting the new selected stroke to make the duplication apparent
         self.translate_strokes(&new_selected, Stroke::IMPORT_OFFSET_DEFAULT);
@@ -144,7 +165,7 @@ impl StrokeStore {
         let keys: Vec<(StrokeKey, Aabb)> = self
             .key_tree
             .iter()
-            .filter(|(key, _)| !(self.trashed(*key).unwrap_or(false)))
+            .filter(|(key, _)| !(self.trashed(*key).unwrap_or(false)) && self.layer_visible(*key))
             .collect();
         if keys.is_empty() {
             return (Vec::new(), None);
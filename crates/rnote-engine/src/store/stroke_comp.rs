@@ -2,6 +2,7 @@
 use super::StrokeKey;
 use super::render_comp::RenderCompState;
 use crate::engine::StrokeContent;
+use crate::snap;
 use crate::strokes::{Content, Stroke};
 use crate::{StrokeStore, WidgetFlags};
 use geo::intersects::Intersects;
@@ -10,7 +11,8 @@ use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::Color;
 use rnote_compose::penpath::Element;
 use rnote_compose::shapes::Shapeable;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{FlipDirection, Transformable};
+use std::collections::HashSet;
 use std::sync::Arc;
 #[cfg(feature = "ui")]
 use tracing::error;
@@ -24,6 +26,7 @@ impl StrokeStore {
 
     /// Gets a mutable reference to a stroke.
     pub(crate) fn get_stroke_mut(&mut self, key: StrokeKey) -> Option<&mut Stroke> {
+        self.track_modified(key);
         Arc::make_mut(&mut self.stroke_components)
             .get_mut(key)
             .map(Arc::make_mut)
@@ -67,11 +70,21 @@ impl StrokeStore {
             .collect()
     }
 
-    /// Storke keys in the order that they should be rendered.
+    /// Stroke keys in the order that they should be rendered.
+    ///
+    /// Excludes strokes in hidden layers.
     pub(crate) fn stroke_keys_as_rendered(&self) -> Vec<StrokeKey> {
         self.keys_sorted_chrono()
             .into_iter()
-            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)) && self.layer_visible(key))
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// Stroke keys in the order that they should be rendered, excluding strokes in hidden or locked layers.
+    pub(crate) fn stroke_keys_as_rendered_editable(&self) -> Vec<StrokeKey> {
+        self.stroke_keys_as_rendered()
+            .into_iter()
+            .filter(|&key| self.layer_editable(key))
             .collect::<Vec<StrokeKey>>()
     }
 
@@ -82,7 +95,7 @@ impl StrokeStore {
     ) -> Vec<StrokeKey> {
         self.keys_sorted_chrono_intersecting_bounds(bounds)
             .into_iter()
-            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)) && self.layer_visible(key))
             .collect::<Vec<StrokeKey>>()
     }
 
@@ -90,10 +103,33 @@ impl StrokeStore {
     pub(crate) fn stroke_keys_as_rendered_in_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
         self.keys_sorted_chrono_in_bounds(bounds)
             .into_iter()
-            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)) && self.layer_visible(key))
             .collect::<Vec<StrokeKey>>()
     }
 
+    /// The positions of the visible strokes intersecting the bounds that other positions can be snapped to.
+    ///
+    /// These are the corners, edge centers and centers of the stroke bounds and the control points of shapes.
+    pub(crate) fn snap_targets(
+        &self,
+        bounds: Aabb,
+        exclude: &[StrokeKey],
+    ) -> Vec<na::Vector2<f64>> {
+        let exclude = exclude.iter().copied().collect::<HashSet<StrokeKey>>();
+        self.stroke_keys_as_rendered_intersecting_bounds(bounds)
+            .into_iter()
+            .filter(|key| !exclude.contains(key))
+            .filter_map(|key| self.stroke_components.get(key))
+            .flat_map(|stroke| {
+                let mut targets = snap::bounds_snap_points(stroke.bounds());
+                if let Stroke::ShapeStroke(shapestroke) = stroke.as_ref() {
+                    targets.extend(shapestroke.shape.control_points());
+                }
+                targets
+            })
+            .collect()
+    }
+
     /// Clone the strokes for the given keys.
     #[allow(unused)]
     pub(crate) fn clone_strokes(&self, keys: &[StrokeKey]) -> Vec<Stroke> {
@@ -141,7 +177,6 @@ impl StrokeStore {
     }
 
     /// Calculate the width needed to fit all strokes.
-    #[allow(unused)]
     pub(crate) fn calc_width(&self) -> f64 {
         let strokes_iter = self
             .stroke_keys_unordered()
@@ -180,6 +215,7 @@ impl StrokeStore {
     }
 
     pub(crate) fn set_stroke_pos(&mut self, key: StrokeKey, pos: na::Vector2<f64>) {
+        self.track_modified(key);
         let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
             .get_mut(key)
             .map(Arc::make_mut)
@@ -194,6 +230,7 @@ impl StrokeStore {
     /// The strokes then need to update their geometry and rendering.
     pub(crate) fn translate_strokes(&mut self, keys: &[StrokeKey], offset: na::Vector2<f64>) {
         keys.iter().for_each(|&key| {
+            self.track_modified(key);
             if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                 .get_mut(key)
                 .map(Arc::make_mut)
@@ -244,6 +281,7 @@ impl StrokeStore {
         center: na::Point2<f64>,
     ) {
         keys.iter().for_each(|&key| {
+            self.track_modified(key);
             if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                 .get_mut(key)
                 .map(Arc::make_mut)
@@ -268,6 +306,7 @@ impl StrokeStore {
         }
 
         keys.iter().for_each(|&key| {
+            self.track_modified(key);
             if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                 .get_mut(key)
                 .map(Arc::make_mut)
@@ -309,6 +348,7 @@ impl StrokeStore {
         }
 
         keys.iter().for_each(|&key| {
+            self.track_modified(key);
             if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                 .get_mut(key)
                 .map(Arc::make_mut)
@@ -338,6 +378,7 @@ impl StrokeStore {
         }
 
         keys.iter().for_each(|&key| {
+            self.track_modified(key);
             if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                 .get_mut(key)
                 .map(Arc::make_mut)
@@ -399,6 +440,7 @@ impl StrokeStore {
     /// The strokes then need to update their rendering.
     pub(crate) fn scale_strokes(&mut self, keys: &[StrokeKey], scale: na::Vector2<f64>) {
         keys.iter().for_each(|&key| {
+            self.track_modified(key);
             if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                 .get_mut(key)
                 .map(Arc::make_mut)
@@ -465,6 +507,57 @@ impl StrokeStore {
         self.translate_strokes_images(strokes, pivot);
     }
 
+    /// Flip (as in mirror) the strokes in the given direction along the axis through the center.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn flip_strokes(
+        &mut self,
+        keys: &[StrokeKey],
+        direction: FlipDirection,
+        center: na::Point2<f64>,
+    ) {
+        keys.iter().for_each(|&key| {
+            self.track_modified(key);
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                stroke.flip(direction, center);
+                self.key_tree.update_with_key(key, stroke.bounds());
+            }
+        });
+    }
+
+    /// Flip the stroke rendering images.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn flip_strokes_images(
+        &mut self,
+        keys: &[StrokeKey],
+        direction: FlipDirection,
+        center: na::Point2<f64>,
+    ) {
+        keys.iter().for_each(|&key| {
+            if let Some(render_comp) = self.render_components.get_mut(key) {
+                render_comp.state = RenderCompState::Dirty;
+
+                for image in render_comp.images.iter_mut() {
+                    image.flip(direction, center);
+                }
+
+                #[cfg(feature = "ui")]
+                match crate::Image::images_to_rendernodes(&render_comp.images) {
+                    Ok(rendernodes) => {
+                        render_comp.rendernodes = rendernodes;
+                    }
+                    Err(e) => error!(
+                        "Generating rendernodes from images failed while flipping stroke images, Err: {e:?}"
+                    ),
+                }
+            }
+        });
+    }
+
     /// Return the keys for stroke whose hitboxes are contained in the given polygon path.
     pub(crate) fn strokes_hitboxes_contained_in_path_polygon(
         &mut self,
@@ -491,8 +584,8 @@ impl StrokeStore {
         self.keys_sorted_chrono_intersecting_bounds(bounds)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or its layer is hidden or locked
+                if self.trashed(key)? || !self.layer_editable(key) {
                     return None;
                 }
 
@@ -546,8 +639,8 @@ impl StrokeStore {
         self.keys_sorted_chrono_intersecting_bounds(bounds)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or its layer is hidden or locked
+                if self.trashed(key)? || !self.layer_editable(key) {
                     return None;
                 }
 
@@ -579,8 +672,8 @@ impl StrokeStore {
         self.keys_sorted_chrono_intersecting_bounds(viewport.merged(&aabb))
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or its layer is hidden or locked
+                if self.trashed(key)? || !self.layer_editable(key) {
                     return None;
                 }
 
@@ -616,6 +709,9 @@ impl StrokeStore {
         self.stroke_keys_as_rendered_intersecting_bounds(bounds)
             .into_iter()
             .filter(|&key| {
+                if !self.layer_editable(key) {
+                    return false;
+                }
                 if let Some(stroke) = self.stroke_components.get(key) {
                     stroke
                         .hitboxes()
@@ -642,6 +738,35 @@ impl StrokeStore {
             .collect::<Vec<StrokeKey>>()
     }
 
+    /// Return all keys right of the given `x`.
+    pub(crate) fn keys_right_of_x(&self, x: f64) -> Vec<StrokeKey> {
+        self.stroke_components
+            .iter()
+            .filter_map(|(key, stroke)| {
+                if stroke.bounds().mins[0] > x {
+                    Some(key)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// Return all keys below and right of the given position.
+    pub(crate) fn keys_below_right_of(&self, pos: na::Vector2<f64>) -> Vec<StrokeKey> {
+        self.stroke_components
+            .iter()
+            .filter_map(|(key, stroke)| {
+                let mins = stroke.bounds().mins;
+                if mins[0] > pos[0] && mins[1] > pos[1] {
+                    Some(key)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
     pub(crate) fn keys_between(
         &self,
         y_start: f64,
@@ -688,26 +813,29 @@ impl StrokeStore {
     }
 
     pub(crate) fn fetch_stroke_content(&self, keys: &[StrokeKey]) -> StrokeContent {
+        let keys = keys
+            .iter()
+            .copied()
+            .filter(|&k| self.stroke_components.contains_key(k))
+            .collect::<Vec<StrokeKey>>();
         let strokes = keys
             .iter()
             .filter_map(|k| self.stroke_components.get(*k).cloned())
             .collect();
 
-        StrokeContent::default().with_strokes(strokes)
+        StrokeContent::default()
+            .with_strokes(strokes)
+            .with_groups(self.group_components_for_keys(&keys))
     }
 
     /// Cut the strokes for the given keys and return them as stroke content.
     pub(crate) fn cut_stroke_content(&mut self, keys: &[StrokeKey]) -> StrokeContent {
-        let strokes = keys
-            .iter()
-            .filter_map(|k| {
-                self.set_selected(*k, false);
-                self.set_trashed(*k, true);
-                self.stroke_components.get(*k).cloned()
-            })
-            .collect();
-
-        StrokeContent::default().with_strokes(strokes)
+        let content = self.fetch_stroke_content(keys);
+        for &key in keys {
+            self.set_selected(key, false);
+            self.set_trashed(key, true);
+        }
+        content
     }
 
     /// Paste the clipboard content as a selection.
@@ -729,7 +857,7 @@ impl StrokeStore {
             .iter()
             .fold(Aabb::new_invalid(), |acc, s| acc.merged(&s.bounds()));
 
-        clipboard_content
+        let inserted_keys = clipboard_content
             .strokes
             .into_iter()
             .map(|s| {
@@ -747,6 +875,9 @@ impl StrokeStore {
                 self.set_selected(key, true);
                 key
             })
-            .collect()
+            .collect::<Vec<StrokeKey>>();
+        self.insert_group_copies(&inserted_keys, &clipboard_content.groups);
+
+        inserted_keys
     }
 }
@@ -2,7 +2,7 @@
 use super::chrono_comp::StrokeLayer;
 use super::{StrokeKey, StrokeStore};
 use crate::WidgetFlags;
-use crate::strokes::{BrushStroke, Stroke};
+use crate::strokes::{BrushStroke, ShapeStroke, Stroke};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::PenPath;
 use rnote_compose::shapes::Shapeable;
@@ -44,6 +44,7 @@ impl StrokeStore {
     }
 
     pub(crate) fn set_trashed(&mut self, key: StrokeKey, trash: bool) {
+        self.track_modified(key);
         if let Some(trash_comp) = Arc::make_mut(&mut self.trash_components)
             .get_mut(key)
             .map(Arc::make_mut)
@@ -85,37 +86,40 @@ impl StrokeStore {
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
-        self.stroke_keys_as_rendered_intersecting_bounds(viewport)
+        let keys = self
+            .stroke_keys_as_rendered_intersecting_bounds(viewport)
             .into_iter()
-            .for_each(|key| {
-                let mut trash_current_stroke = false;
-
-                if let Some(stroke) = self.stroke_components.get(key) {
-                    match stroke.as_ref() {
-                        Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) => {
-                            // First check if eraser even intersects stroke bounds, avoiding unnecessary work
-                            if eraser_bounds.intersects(&stroke.bounds()) {
-                                for hitbox in stroke.hitboxes().into_iter() {
-                                    if eraser_bounds.intersects(&hitbox) {
-                                        trash_current_stroke = true;
+            .filter(|&key| self.layer_editable(key))
+            .collect::<Vec<StrokeKey>>();
+
+        keys.into_iter().for_each(|key| {
+            let mut trash_current_stroke = false;
+
+            if let Some(stroke) = self.stroke_components.get(key) {
+                match stroke.as_ref() {
+                    Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) => {
+                        // First check if eraser even intersects stroke bounds, avoiding unnecessary work
+                        if eraser_bounds.intersects(&stroke.bounds()) {
+                            for hitbox in stroke.hitboxes().into_iter() {
+                                if eraser_bounds.intersects(&hitbox) {
+                                    trash_current_stroke = true;
 
-                                        break;
-                                    }
+                                    break;
                                 }
                             }
                         }
-                        // Ignore other strokes when trashing with the Eraser
-                        Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {
-                        }
                     }
+                    // Ignore other strokes when trashing with the Eraser
+                    Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {}
                 }
+            }
 
-                if trash_current_stroke {
-                    self.set_trashed(key, true);
-                    widget_flags.store_modified = true;
-                    widget_flags.resize = true;
-                }
-            });
+            if trash_current_stroke {
+                self.set_trashed(key, true);
+                widget_flags.store_modified = true;
+                widget_flags.resize = true;
+            }
+        });
 
         widget_flags
     }
@@ -123,6 +127,9 @@ impl StrokeStore {
     /// Remove colliding stroke segments with the given bounds.
     /// The stroke is then split. Strokes that don't have segments are trashed completely.
     ///
+    /// Shape outlines are split into partial shapes and colliding graphemes are removed from texts.
+    /// Bitmap images are erased separately with `erase_colliding_bitmap_pixels()`.
+    ///
     /// Returns the keys of all created or modified strokes.
     ///
     /// The returned strokes need to update their rendering.
@@ -134,10 +141,23 @@ impl StrokeStore {
         let mut widget_flags = WidgetFlags::default();
         let mut modified_keys = vec![];
 
-        let new_strokes = self
+        // Only the strokes colliding with the eraser can be modified
+        let keys = self
             .stroke_keys_as_rendered_intersecting_bounds(viewport)
+            .into_iter()
+            .filter(|&key| {
+                self.layer_editable(key)
+                    && self
+                        .stroke_components
+                        .get(key)
+                        .is_some_and(|stroke| eraser_bounds.intersects(&stroke.bounds()))
+            })
+            .collect::<Vec<StrokeKey>>();
+
+        let new_strokes = keys
             .into_iter()
             .flat_map(|key| {
+                self.track_modified(key);
                 let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                     .get_mut(key)
                     .map(Arc::make_mut)
@@ -211,18 +231,43 @@ impl StrokeStore {
                             }
                         }
                     }
-                    Stroke::ShapeStroke(_) => {
-                        if eraser_bounds.intersects(&stroke_bounds) {
-                            for hitbox_elem in stroke.hitboxes().iter() {
-                                if eraser_bounds.intersects(hitbox_elem) {
-                                    trash_current_stroke = true;
-                                    modified_keys.push(key);
+                    Stroke::ShapeStroke(shapestroke) => {
+                        if let Some(parts) = shapestroke
+                            .shape
+                            .split_by_bounds(&eraser_bounds, shapestroke.style.stroke_width() * 0.5)
+                        {
+                            let mut parts = parts.into_iter();
+
+                            if let Some(first_part) = parts.next() {
+                                for part in parts {
+                                    new_strokes.push((
+                                        Stroke::ShapeStroke(ShapeStroke::new(
+                                            part,
+                                            shapestroke.style.clone(),
+                                        )),
+                                        chrono_comp.layer,
+                                    ));
                                 }
+                                // The original stroke is modified to become the first part
+                                shapestroke.replace_shape(first_part);
+                            } else {
+                                trash_current_stroke = true;
                             }
+
+                            modified_keys.push(key);
                         }
                     }
-                    // Ignore other strokes when trashing with the Eraser
-                    Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {}
+                    Stroke::TextStroke(textstroke) => {
+                        if textstroke.remove_graphemes_colliding_w_bounds(eraser_bounds) {
+                            if textstroke.text.trim().is_empty() {
+                                trash_current_stroke = true;
+                            }
+
+                            modified_keys.push(key);
+                        }
+                    }
+                    // Vector images can't be split, bitmap images are erased when the eraser stroke is finished
+                    Stroke::VectorImage(_) | Stroke::BitmapImage(_) => {}
                 }
 
                 if trash_current_stroke {
@@ -249,4 +294,72 @@ impl StrokeStore {
 
         (modified_keys, widget_flags)
     }
+
+    /// Erase the pixels of inserted bitmap images that collide with any of the given bounds of an eraser stroke.
+    ///
+    /// Only images on the image layer are erased, imported Pdf pages are kept.
+    /// Images that become fully transparent are trashed.
+    ///
+    /// Returns the keys of all modified strokes, which then need to update their rendering.
+    pub(crate) fn erase_colliding_bitmap_pixels(
+        &mut self,
+        eraser_bounds: &[Aabb],
+    ) -> (Vec<StrokeKey>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        let mut modified_keys = vec![];
+        let Some(eraser_bounds_union) = eraser_bounds
+            .iter()
+            .copied()
+            .reduce(|union, bounds| union.merged(&bounds))
+        else {
+            return (modified_keys, widget_flags);
+        };
+
+        let keys = self
+            .stroke_keys_as_rendered_intersecting_bounds(eraser_bounds_union)
+            .into_iter()
+            .filter(|&key| {
+                self.layer_editable(key)
+                    && self
+                        .chrono_components
+                        .get(key)
+                        .is_some_and(|chrono_comp| chrono_comp.layer == StrokeLayer::Image)
+                    && matches!(
+                        self.stroke_components.get(key).map(|stroke| stroke.as_ref()),
+                        Some(Stroke::BitmapImage(bitmapimage)) if bitmapimage.source_pdf_page.is_none()
+                    )
+            })
+            .collect::<Vec<StrokeKey>>();
+
+        for key in keys {
+            // Only tracked when pixels are actually erased, so that the stroke is not copied otherwise
+            let Some(Stroke::BitmapImage(bitmapimage)) = self
+                .stroke_components
+                .get(key)
+                .map(|stroke| stroke.as_ref())
+            else {
+                continue;
+            };
+            let mut bitmapimage = bitmapimage.clone();
+            if !bitmapimage.erase_pixels_colliding_w_bounds(eraser_bounds) {
+                continue;
+            }
+            self.track_modified(key);
+            let trash_current_stroke = bitmapimage.is_fully_transparent();
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components).get_mut(key) {
+                *stroke = Arc::new(Stroke::BitmapImage(bitmapimage));
+            }
+            if trash_current_stroke {
+                self.set_trashed(key, true);
+            }
+            modified_keys.push(key);
+        }
+
+        if !modified_keys.is_empty() {
+            widget_flags.store_modified = true;
+            widget_flags.resize = true;
+        }
+
+        (modified_keys, widget_flags)
+    }
 }
@@ -3,7 +3,7 @@ use super::resize::{ImageSizeOption, calculate_resize_ratio};
 use super::{Content, Stroke};
 use crate::Drawable;
 use crate::Image;
-use crate::document::Format;
+use crate::document::{Format, SourcePdfPage};
 use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
 use anyhow::anyhow;
 use hayro::{hayro_interpret, hayro_syntax, vello_cpu};
@@ -30,6 +30,9 @@ pub struct BitmapImage {
     pub image: Image,
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    /// The page of the source Pdf the image was generated from.
+    #[serde(rename = "source_pdf_page")]
+    pub source_pdf_page: Option<SourcePdfPage>,
 }
 
 impl Default for BitmapImage {
@@ -37,6 +40,7 @@ impl Default for BitmapImage {
         Self {
             image: Image::default(),
             rectangle: Rectangle::default(),
+            source_pdf_page: None,
         }
     }
 }
@@ -124,7 +128,11 @@ impl BitmapImage {
             cuboid: p2d::shape::Cuboid::new(size * 0.5),
             transform,
         };
-        Ok(Self { image, rectangle })
+        Ok(Self {
+            image,
+            rectangle,
+            source_pdf_page: None,
+        })
     }
 
     pub fn from_pdf_bytes(
@@ -134,6 +142,7 @@ impl BitmapImage {
         page_range: Option<Range<usize>>,
         format: &Format,
         password: Option<String>,
+        source_pdf_id: Option<u32>,
     ) -> Result<Vec<Self>, anyhow::Error> {
         // TODO: how to avoid this allocation without lifetime issues?
         let data = Arc::new(to_be_read.to_vec());
@@ -146,7 +155,18 @@ impl BitmapImage {
         };
         let interpreter_settings = hayro_interpret::InterpreterSettings::default();
         let pages = pdf.pages();
-        let page_range = page_range.unwrap_or(0..pages.len());
+        // Ranges past the last page are clamped, but they need to start on an existing page
+        let page_range = match page_range {
+            Some(range) if range.start >= pages.len() => {
+                return Err(anyhow!(
+                    "Pdf page range starts at page {}, but the Pdf only has {} pages.",
+                    range.start + 1,
+                    pages.len()
+                ));
+            }
+            Some(range) => range.start..range.end.min(pages.len()),
+            None => 0..pages.len(),
+        };
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
@@ -201,14 +221,93 @@ impl BitmapImage {
                     };
                 }
 
-                Ok((png_data, image_pos, image_size))
+                Ok((page_i, png_data, image_pos, image_size))
             })
-            .collect::<anyhow::Result<Vec<(Vec<u8>, na::Vector2<f64>, na::Vector2<f64>)>>>()?;
+            .collect::<anyhow::Result<Vec<(usize, Vec<u8>, na::Vector2<f64>, na::Vector2<f64>)>>>(
+            )?;
 
         pngs.into_par_iter()
-            .map(|(png_data, pos, size)| {
-                Self::from_image_bytes(&png_data, pos, ImageSizeOption::ImposeSize(size))
+            .map(|(page_i, png_data, pos, size)| {
+                let mut bitmapimage =
+                    Self::from_image_bytes(&png_data, pos, ImageSizeOption::ImposeSize(size))?;
+                bitmapimage.source_pdf_page =
+                    source_pdf_id.map(|pdf_id| SourcePdfPage { pdf_id, page_i });
+                Ok(bitmapimage)
             })
             .collect()
     }
+
+    /// Erases the pixels that collide with any of the given bounds by making them transparent.
+    ///
+    /// The image data is copied at most once, so all bounds of an eraser stroke should be passed together.
+    /// Images of a source Pdf page are not erased, because they would no longer match their page.
+    ///
+    /// Returns true if any pixels were erased.
+    pub fn erase_pixels_colliding_w_bounds(&mut self, bounds: &[Aabb]) -> bool {
+        if self.source_pdf_page.is_some() {
+            return false;
+        }
+        let (pixel_width, pixel_height) = (
+            self.image.pixel_width as usize,
+            self.image.pixel_height as usize,
+        );
+        let half_extents = self.rectangle.cuboid.half_extents;
+        if pixel_width == 0 || pixel_height == 0 || half_extents.min() <= 0.0 {
+            return false;
+        }
+        let Some(inverse) = self.rectangle.transform.affine.try_inverse() else {
+            return false;
+        };
+        let pixel_size = na::vector![
+            2.0 * half_extents[0] / pixel_width as f64,
+            2.0 * half_extents[1] / pixel_height as f64
+        ];
+        let pixel_range = |min: f64, max: f64, i: usize, len: usize| {
+            let start = ((min + half_extents[i]) / pixel_size[i]).floor().max(0.0) as usize;
+            let end = ((max + half_extents[i]) / pixel_size[i]).ceil().max(0.0) as usize;
+            start.min(len)..end.min(len)
+        };
+
+        // Copied when the first pixel is erased
+        let mut data: Option<Vec<u8>> = None;
+        for bounds in bounds {
+            // Only the pixels inside the bounds transformed into the local coordinate space of the image are considered.
+            let local_bounds = Transform::new(inverse).transform_aabb(*bounds);
+            let x_range = pixel_range(local_bounds.mins[0], local_bounds.maxs[0], 0, pixel_width);
+            let y_range = pixel_range(local_bounds.mins[1], local_bounds.maxs[1], 1, pixel_height);
+
+            for (x, y) in y_range.flat_map(|y| x_range.clone().map(move |x| (x, y))) {
+                let pixel_center = na::point![
+                    (x as f64 + 0.5) * pixel_size[0] - half_extents[0],
+                    (y as f64 + 0.5) * pixel_size[1] - half_extents[1]
+                ];
+                if !bounds
+                    .contains_local_point(&self.rectangle.transform.transform_point(pixel_center))
+                {
+                    continue;
+                }
+                // The memory format is rgba8
+                let i = (y * pixel_width + x) * 4;
+                let current = data.as_deref().unwrap_or(&self.image.data);
+                if current.get(i + 3).is_none_or(|&alpha| alpha == 0) {
+                    continue;
+                }
+                // Premultiplied, so all channels are zeroed
+                data.get_or_insert_with(|| self.image.data.to_vec())[i..i + 4].fill(0);
+            }
+        }
+
+        match data {
+            Some(data) => {
+                self.image.data = glib::Bytes::from_owned(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether all pixels of the image are fully transparent.
+    pub fn is_fully_transparent(&self) -> bool {
+        self.image.data.chunks_exact(4).all(|pixel| pixel[3] == 0)
+    }
 }
@@ -9,7 +9,7 @@ use rnote_compose::ext::AabbExt;
 use rnote_compose::penpath::{Element, Segment};
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::style::Composer;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{FlipDirection, Transformable};
 use rnote_compose::{PenPath, Style};
 use serde::{Deserialize, Serialize};
 use tracing::error;
@@ -255,6 +255,10 @@ impl Transformable for BrushStroke {
         self.style
             .set_stroke_width(self.style.stroke_width() * scale_scalar);
     }
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        // Mirroring keeps the stroke width.
+        self.path.flip(direction, center);
+    }
 }
 
 impl BrushStroke {
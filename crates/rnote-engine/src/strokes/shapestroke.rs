@@ -7,7 +7,7 @@ use rnote_compose::ext::AabbExt;
 use rnote_compose::shapes::Shape;
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::style::Composer;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{FlipDirection, Transformable};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +98,10 @@ impl Transformable for ShapeStroke {
         self.style
             .set_stroke_width(self.style.stroke_width() * scale_scalar);
     }
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        // Mirroring keeps the stroke width.
+        self.shape.flip(direction, center);
+    }
 }
 
 impl ShapeStroke {
@@ -112,6 +116,11 @@ impl ShapeStroke {
         shapestroke
     }
 
+    pub fn replace_shape(&mut self, shape: Shape) {
+        self.shape = shape;
+        self.update_geometry();
+    }
+
     fn gen_hitboxes_int(&self) -> Vec<Aabb> {
         let width = self.style.stroke_width();
 
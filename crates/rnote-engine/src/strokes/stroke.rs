@@ -8,6 +8,7 @@ use super::{Content, TextStroke};
 use crate::Engine;
 use crate::Image;
 use crate::Svg;
+use crate::document::SourcePdfPage;
 use crate::fileformats::xoppformat::{self, XoppColor};
 use crate::store::chrono_comp::StrokeLayer;
 use crate::strokes::textstroke::TextStyle;
@@ -18,7 +19,7 @@ use rnote_compose::penpath::Element;
 use rnote_compose::shapes::{Rectangle, Shapeable};
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::transform::Transform;
-use rnote_compose::transform::Transformable;
+use rnote_compose::transform::{FlipDirection, Transformable};
 use rnote_compose::{Color, PenPath, Style};
 use serde::{Deserialize, Serialize};
 use tracing::error;
@@ -202,6 +203,26 @@ impl Transformable for Stroke {
             }
         }
     }
+
+    fn flip(&mut self, direction: FlipDirection, center: na::Point2<f64>) {
+        match self {
+            Self::BrushStroke(brushstroke) => {
+                brushstroke.flip(direction, center);
+            }
+            Self::ShapeStroke(shapestroke) => {
+                shapestroke.flip(direction, center);
+            }
+            Self::TextStroke(textstroke) => {
+                textstroke.flip(direction, center);
+            }
+            Self::VectorImage(vectorimage) => {
+                vectorimage.flip(direction, center);
+            }
+            Self::BitmapImage(bitmapimage) => {
+                bitmapimage.flip(direction, center);
+            }
+        }
+    }
 }
 
 impl Stroke {
@@ -217,6 +238,15 @@ impl Stroke {
         }
     }
 
+    /// The page of the source Pdf the stroke was generated from, if it is an imported Pdf page.
+    pub fn source_pdf_page(&self) -> Option<SourcePdfPage> {
+        match self {
+            Stroke::VectorImage(vectorimage) => vectorimage.source_pdf_page,
+            Stroke::BitmapImage(bitmapimage) => bitmapimage.source_pdf_page,
+            _ => None,
+        }
+    }
+
     /// Invert the brightness of all colors of the stroke.
     ///
     /// Returns true if the stroke was modified and needs to update its rendering.
@@ -427,7 +457,11 @@ impl Stroke {
         };
         let image = Image::try_from_encoded_bytes(&bytes)?;
 
-        Ok(Stroke::BitmapImage(BitmapImage { image, rectangle }))
+        Ok(Stroke::BitmapImage(BitmapImage {
+            image,
+            rectangle,
+            source_pdf_page: None,
+        }))
     }
 
     pub fn from_xopptext(
@@ -3,7 +3,7 @@ use super::Content;
 use crate::{Camera, Drawable};
 use itertools::Itertools;
 use kurbo::Shape;
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use piet::{RenderContext, TextLayout, TextLayoutBuilder};
 use rnote_compose::ext::{AabbExt, Affine2Ext, Vector2Ext};
 use rnote_compose::shapes::Shapeable;
@@ -195,6 +195,9 @@ pub struct TextStyle {
     max_width: Option<f64>,
     #[serde(rename = "alignment")]
     pub alignment: TextAlignment,
+    /// Section headings are written into the outline of exported Pdf documents.
+    #[serde(rename = "section_heading")]
+    pub section_heading: bool,
 
     #[serde(rename = "ranged_text_attributes")]
     pub ranged_text_attributes: Vec<RangedTextAttribute>,
@@ -210,6 +213,7 @@ impl Default for TextStyle {
             color: Self::FONT_COLOR_DEFAULT,
             max_width: None,
             alignment: TextAlignment::Start,
+            section_heading: false,
             ranged_text_attributes: vec![],
         }
     }
@@ -695,6 +699,70 @@ impl TextStroke {
         );
     }
 
+    /// Removes the graphemes that collide with the given bounds, used when erasing.
+    ///
+    /// Returns true if any graphemes were removed.
+    pub fn remove_graphemes_colliding_w_bounds(&mut self, bounds: Aabb) -> bool {
+        let text_layout = match self
+            .text_style
+            .build_text_layout(&mut piet_cairo::CairoText::new(), self.text.clone())
+        {
+            Ok(text_layout) => text_layout,
+            Err(e) => {
+                error!(
+                    "Building text layout failed while removing colliding graphemes, Err: {e:?}"
+                );
+                return false;
+            }
+        };
+
+        let colliding = self
+            .text
+            .grapheme_indices(true)
+            .filter(|(_, grapheme)| !grapheme.trim().is_empty())
+            .map(|(start, grapheme)| start..start + grapheme.len())
+            .filter(|range| {
+                text_layout
+                    .rects_for_range(range.clone())
+                    .into_iter()
+                    .any(|rect| {
+                        self.transform
+                            .transform_aabb(Aabb::from_kurbo_rect(rect))
+                            .intersects(&bounds)
+                    })
+            })
+            .collect::<Vec<Range<usize>>>();
+
+        // Removing from the back keeps the ranges in front valid
+        for range in colliding.iter().rev() {
+            self.remove_text_range(range.clone());
+        }
+
+        !colliding.is_empty()
+    }
+
+    /// Removes the text in the given range and adjusts the ranged text attributes.
+    fn remove_text_range(&mut self, range: Range<usize>) {
+        let len = range.len();
+        let map_pos = |pos: usize| {
+            if pos <= range.start {
+                pos
+            } else if pos < range.end {
+                range.start
+            } else {
+                pos - len
+            }
+        };
+
+        self.text.replace_range(range.clone(), "");
+        for attr in self.text_style.ranged_text_attributes.iter_mut() {
+            attr.range = map_pos(attr.range.start)..map_pos(attr.range.end);
+        }
+        self.text_style
+            .ranged_text_attributes
+            .retain(|attr| !attr.range.is_empty());
+    }
+
     /// Translate the ranged text attributes after the given cursor.
     ///
     /// Overlapping ranges are extended / shrunk
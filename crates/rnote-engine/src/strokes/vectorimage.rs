@@ -3,7 +3,7 @@ use super::content::GeneratedContentImages;
 use super::resize::{ImageSizeOption, calculate_resize_ratio};
 use super::{Content, Stroke};
 use crate::Image;
-use crate::document::Format;
+use crate::document::{Format, SourcePdfPage};
 use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
 use crate::svg::USVG_FONTDB;
 use crate::{Drawable, Svg};
@@ -33,6 +33,9 @@ pub struct VectorImage {
     pub intrinsic_size: na::Vector2<f64>,
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    /// The page of the source Pdf the image was generated from.
+    #[serde(rename = "source_pdf_page")]
+    pub source_pdf_page: Option<SourcePdfPage>,
 }
 
 impl Default for VectorImage {
@@ -41,6 +44,7 @@ impl Default for VectorImage {
             svg_data: String::default(),
             intrinsic_size: na::Vector2::zeros(),
             rectangle: Rectangle::default(),
+            source_pdf_page: None,
         }
     }
 }
@@ -201,9 +205,22 @@ impl VectorImage {
             svg_data,
             intrinsic_size,
             rectangle,
+            source_pdf_page: None,
         })
     }
 
+    /// Asserts that the Svg data can be parsed.
+    pub fn assert_valid(&self) -> anyhow::Result<()> {
+        usvg::Tree::from_str(
+            &self.svg_data,
+            &usvg::Options {
+                fontdb: Arc::clone(&USVG_FONTDB),
+                ..Default::default()
+            },
+        )?;
+        Ok(())
+    }
+
     pub fn from_pdf_bytes(
         to_be_read: &[u8],
         pdf_import_prefs: PdfImportPrefs,
@@ -211,6 +228,7 @@ impl VectorImage {
         page_range: Option<Range<usize>>,
         format: &Format,
         password: Option<String>,
+        source_pdf_id: Option<u32>,
     ) -> Result<Vec<Self>, anyhow::Error> {
         // TODO: how to avoid this allocation without lifetime issues?
         let data = Arc::new(to_be_read.to_vec());
@@ -226,7 +244,18 @@ impl VectorImage {
             bg_color: [255, 255, 255, 255],
         };
         let pages = pdf.pages();
-        let page_range = page_range.unwrap_or(0..pages.len());
+        // Ranges past the last page are clamped, but they need to start on an existing page
+        let page_range = match page_range {
+            Some(range) if range.start >= pages.len() => {
+                return Err(anyhow!(
+                    "Pdf page range starts at page {}, but the Pdf only has {} pages.",
+                    range.start + 1,
+                    pages.len()
+                ));
+            }
+            Some(range) => range.start..range.end.min(pages.len()),
+            None => 0..pages.len(),
+        };
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
@@ -267,17 +296,20 @@ impl VectorImage {
                 let svg_data = hayro_svg::convert(page, &interpreter_settings, &render_settings);
                 let svg = Svg { svg_data, bounds };
 
-                Some(svg)
+                Some((page_i, svg))
             })
-            .collect::<Vec<Svg>>();
+            .collect::<Vec<(usize, Svg)>>();
 
         svgs.into_par_iter()
-            .map(|svg| {
-                Self::from_svg_str(
+            .map(|(page_i, svg)| {
+                let mut vectorimage = Self::from_svg_str(
                     svg.svg_data.as_str(),
                     svg.bounds.mins.coords,
                     ImageSizeOption::ImposeSize(svg.bounds.extents()),
-                )
+                )?;
+                vectorimage.source_pdf_page =
+                    source_pdf_id.map(|pdf_id| SourcePdfPage { pdf_id, page_i });
+                Ok(vectorimage)
             })
             .collect()
     }
@@ -10,9 +10,12 @@ use gtk4::{
 use p2d::bounding_volume::BoundingVolume;
 use rnote_compose::SplitOrder;
 use rnote_compose::penevent::ShortcutKey;
+use rnote_compose::transform::FlipDirection;
+use rnote_engine::alignment::{Alignment, AlignmentReference, Distribution};
 use rnote_engine::engine::StrokeContent;
 use rnote_engine::ext::GraphenePointExt;
 use rnote_engine::pens::PenStyle;
+use rnote_engine::store::ZOrderChange;
 use rnote_engine::strokes::resize::{ImageSizeOption, Resize};
 use rnote_engine::strokes::textstroke::TextAttribute;
 use rnote_engine::{Camera, Engine};
@@ -72,10 +75,14 @@ impl RnAppWindow {
         self.add_action(&action_zoomin);
         let action_zoomout = gio::SimpleAction::new("zoom-out", None);
         self.add_action(&action_zoomout);
+        let action_rotation_reset = gio::SimpleAction::new("rotation-reset", None);
+        self.add_action(&action_rotation_reset);
         let action_add_page_to_doc = gio::SimpleAction::new("add-page-to-doc", None);
         self.add_action(&action_add_page_to_doc);
         let action_remove_page_from_doc = gio::SimpleAction::new("remove-page-from-doc", None);
         self.add_action(&action_remove_page_from_doc);
+        let action_page_properties = gio::SimpleAction::new("page-properties", None);
+        self.add_action(&action_page_properties);
         let action_resize_to_fit_content = gio::SimpleAction::new("resize-to-fit-content", None);
         self.add_action(&action_resize_to_fit_content);
         let action_return_origin_page = gio::SimpleAction::new("return-origin-page", None);
@@ -84,6 +91,35 @@ impl RnAppWindow {
         self.add_action(&action_selection_trash);
         let action_selection_duplicate = gio::SimpleAction::new("selection-duplicate", None);
         self.add_action(&action_selection_duplicate);
+        let action_selection_group = gio::SimpleAction::new("selection-group", None);
+        self.add_action(&action_selection_group);
+        let action_selection_ungroup = gio::SimpleAction::new("selection-ungroup", None);
+        self.add_action(&action_selection_ungroup);
+        let action_selection_z_order =
+            gio::SimpleAction::new("selection-z-order", Some(&String::static_variant_type()));
+        self.add_action(&action_selection_z_order);
+        let action_selection_align =
+            gio::SimpleAction::new("selection-align", Some(&String::static_variant_type()));
+        self.add_action(&action_selection_align);
+        // Changes its state to the activated target, which is then read when aligning
+        let action_selection_align_reference = gio::SimpleAction::new_stateful(
+            "selection-align-reference",
+            Some(&String::static_variant_type()),
+            &String::from("selection").to_variant(),
+        );
+        self.add_action(&action_selection_align_reference);
+        let action_selection_distribute =
+            gio::SimpleAction::new("selection-distribute", Some(&String::static_variant_type()));
+        self.add_action(&action_selection_distribute);
+        let action_selection_flip =
+            gio::SimpleAction::new("selection-flip", Some(&String::static_variant_type()));
+        self.add_action(&action_selection_flip);
+        // The target is the angle in degrees
+        let action_selection_rotate =
+            gio::SimpleAction::new("selection-rotate", Some(&f64::static_variant_type()));
+        self.add_action(&action_selection_rotate);
+        let action_selection_transform = gio::SimpleAction::new("selection-transform", None);
+        self.add_action(&action_selection_transform);
         let action_selection_invert_color = gio::SimpleAction::new("selection-invert-color", None);
         self.add_action(&action_selection_invert_color);
         let action_selection_select_all = gio::SimpleAction::new("selection-select-all", None);
@@ -156,6 +192,9 @@ impl RnAppWindow {
         let action_snap_positions =
             gio::PropertyAction::new("snap-positions", self, "snap-positions");
         self.add_action(&action_snap_positions);
+        let action_snap_to_strokes =
+            gio::PropertyAction::new("snap-to-strokes", self, "snap-to-strokes");
+        self.add_action(&action_snap_to_strokes);
         let action_block_pinch_zoom =
             gio::PropertyAction::new("block-pinch-zoom", self, "block-pinch-zoom");
         self.add_action(&action_block_pinch_zoom);
@@ -470,6 +509,167 @@ impl RnAppWindow {
             }
         ));
 
+        // Group Selection
+        action_selection_group.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().group_selection();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Ungroup Selection
+        action_selection_ungroup.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().ungroup_selection();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Align the selection
+        action_selection_align.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            #[weak]
+            action_selection_align_reference,
+            move |_, target| {
+                let alignment = match target.unwrap().str().unwrap() {
+                    "left" => Alignment::Left,
+                    "horizontal-center" => Alignment::HorizontalCenter,
+                    "right" => Alignment::Right,
+                    "top" => Alignment::Top,
+                    "vertical-center" => Alignment::VerticalCenter,
+                    "bottom" => Alignment::Bottom,
+                    other => {
+                        error!("Activated selection-align action with invalid target '{other}'");
+                        return;
+                    }
+                };
+                let reference = match action_selection_align_reference
+                    .state()
+                    .and_then(|state| state.get::<String>())
+                    .as_deref()
+                {
+                    Some("page") => AlignmentReference::Page,
+                    Some("first-selected") => AlignmentReference::FirstSelected,
+                    _ => AlignmentReference::Selection,
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().align_selection(alignment, reference);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Distribute the selection
+        action_selection_distribute.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let distribution = match target.unwrap().str().unwrap() {
+                    "horizontal" => Distribution::Horizontal,
+                    "vertical" => Distribution::Vertical,
+                    other => {
+                        error!(
+                            "Activated selection-distribute action with invalid target '{other}'"
+                        );
+                        return;
+                    }
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().distribute_selection(distribution);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Change the z-order of the selection
+        action_selection_z_order.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let change = match target.unwrap().str().unwrap() {
+                    "bring-to-front" => ZOrderChange::BringToFront,
+                    "send-to-back" => ZOrderChange::SendToBack,
+                    "bring-forward" => ZOrderChange::BringForward,
+                    "send-backward" => ZOrderChange::SendBackward,
+                    other => {
+                        error!("Activated selection-z-order action with invalid target '{other}'");
+                        return;
+                    }
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().change_selection_z_order(change);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Flip the selection
+        action_selection_flip.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let direction = match target.unwrap().str().unwrap() {
+                    "horizontal" => FlipDirection::Horizontal,
+                    "vertical" => FlipDirection::Vertical,
+                    other => {
+                        error!("Activated selection-flip action with invalid target '{other}'");
+                        return;
+                    }
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().flip_selection(direction);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Rotate the selection by an exact angle
+        action_selection_rotate.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let angle = target.unwrap().get::<f64>().unwrap().to_radians();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().rotate_selection(angle);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Set the selection position, size and rotation numerically
+        action_selection_transform.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::dialog_transform_selection(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
         // invert color brightness of selection
         action_selection_invert_color.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -702,6 +902,20 @@ impl RnAppWindow {
             }
         ));
 
+        // Rotation reset
+        action_rotation_reset.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let mut widget_flags = canvas.engine_mut().camera_rotate_to(0.0);
+                widget_flags |= canvas.engine_mut().update_rendering_current_viewport();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
         // Add page to doc in fixed size mode
         action_add_page_to_doc.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -728,6 +942,44 @@ impl RnAppWindow {
             }
         ));
 
+        // Edit the title, format and background of the page under the context menu, or else in the viewport center
+        action_page_properties.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(wrapper) = appwindow.active_tab_wrapper() else {
+                    return;
+                };
+                let canvas = wrapper.canvas();
+
+                let page_i = {
+                    let engine = canvas.engine_ref();
+                    let pos = wrapper
+                        .last_contextmenu_pos()
+                        .map(|vec2| {
+                            (engine.camera.transform().inverse() * na::Point2::from(vec2)).coords
+                        })
+                        .unwrap_or_else(|| engine.camera.viewport().center().coords);
+                    engine.document.page_index_at(pos)
+                };
+                let Some(page_i) = page_i else {
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("No page at this position"));
+                    return;
+                };
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::dialog_page_properties(&appwindow, &canvas, page_i).await;
+                    }
+                ));
+            }
+        ));
+
         // Resize to fit content
         action_resize_to_fit_content.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -1129,12 +1381,15 @@ impl RnAppWindow {
         );
         app.set_accels_for_action("win.zoom-reset", &["<Ctrl>0", "<Ctrl>KP_0"]);
         app.set_accels_for_action("win.zoom-out", &["<Ctrl>minus", "<Ctrl>KP_Subtract"]);
+        app.set_accels_for_action("win.rotation-reset", &["<Ctrl><Alt>0", "<Ctrl><Alt>KP_0"]);
         app.set_accels_for_action("win.import-file", &["<Ctrl><Shift>i"]);
         app.set_accels_for_action("win.undo", &["<Ctrl>z"]);
         app.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
         app.set_accels_for_action("win.clipboard-copy", &["<Ctrl>c"]);
         app.set_accels_for_action("win.clipboard-cut", &["<Ctrl>x"]);
         app.set_accels_for_action("win.clipboard-paste", &["<Ctrl>v"]);
+        app.set_accels_for_action("win.selection-group", &["<Ctrl>g"]);
+        app.set_accels_for_action("win.selection-ungroup", &["<Ctrl><Shift>g"]);
         app.set_accels_for_action("win.text-bold", &["<Ctrl>b"]);
         app.set_accels_for_action("win.text-italic", &["<Ctrl>i"]);
         app.set_accels_for_action("win.text-underline", &["<Ctrl>u"]);
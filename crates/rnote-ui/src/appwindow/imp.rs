@@ -21,6 +21,7 @@ pub(crate) struct RnAppWindow {
     pub(crate) document_config_preset: RefCell<DocumentConfig>,
     pub(crate) pen_sounds: Cell<bool>,
     pub(crate) snap_positions: Cell<bool>,
+    pub(crate) snap_to_strokes: Cell<bool>,
     pub(crate) pen_style: Cell<PenStyle>,
     pub(crate) autosave: Cell<bool>,
     pub(crate) autosave_interval_secs: Cell<u32>,
@@ -60,6 +61,7 @@ impl Default for RnAppWindow {
             document_config_preset: RefCell::new(DocumentConfig::default()),
             pen_sounds: Cell::new(true),
             snap_positions: Cell::new(true),
+            snap_to_strokes: Cell::new(false),
             pen_style: Cell::new(PenStyle::default()),
             autosave: Cell::new(true),
             autosave_interval_secs: Cell::new(super::RnAppWindow::AUTOSAVE_INTERVAL_DEFAULT),
@@ -146,6 +148,9 @@ impl ObjectImpl for RnAppWindow {
                 glib::ParamSpecBoolean::builder("snap-positions")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecBoolean::builder("snap-to-strokes")
+                    .default_value(false)
+                    .build(),
                 glib::ParamSpecVariant::builder("pen-style", &PenStyle::static_variant_type())
                     .default_value(Some(&PenStyle::default().to_variant()))
                     .build(),
@@ -190,6 +195,7 @@ impl ObjectImpl for RnAppWindow {
         match pspec.name() {
             "pen-sounds" => self.pen_sounds.get().to_value(),
             "snap-positions" => self.snap_positions.get().to_value(),
+            "snap-to-strokes" => self.snap_to_strokes.get().to_value(),
             "pen-style" => self.pen_style.get().to_variant().to_value(),
             "autosave" => self.autosave.get().to_value(),
             "autosave-interval-secs" => self.autosave_interval_secs.get().to_value(),
@@ -223,6 +229,12 @@ impl ObjectImpl for RnAppWindow {
                 self.snap_positions.replace(snap_positions);
                 self.engine_config.write().snap_positions = snap_positions;
             }
+            "snap-to-strokes" => {
+                let snap_to_strokes: bool =
+                    value.get().expect("The value needs to be of type `bool`");
+                self.snap_to_strokes.replace(snap_to_strokes);
+                self.engine_config.write().snap_to_strokes = snap_to_strokes;
+            }
             "pen-style" => {
                 let pen_style = PenStyle::from_variant(
                     &value
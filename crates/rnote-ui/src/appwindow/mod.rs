@@ -70,6 +70,16 @@ impl RnAppWindow {
         self.set_property("snap-positions", snap_positions.to_value());
     }
 
+    #[allow(unused)]
+    pub(crate) fn snap_to_strokes(&self) -> bool {
+        self.property::<bool>("snap-to-strokes")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_snap_to_strokes(&self, snap_to_strokes: bool) {
+        self.set_property("snap-to-strokes", snap_to_strokes.to_value());
+    }
+
     #[allow(unused)]
     pub(crate) fn pen_style(&self) -> PenStyle {
         PenStyle::from_variant(&self.property::<glib::Variant>("pen-style")).unwrap()
@@ -716,6 +726,7 @@ impl RnAppWindow {
             let pen_style = canvas.engine_ref().current_pen_style_w_override();
             let pen_sounds = canvas.engine_ref().pen_sounds();
             let snap_positions = self.engine_config().read().snap_positions;
+            let snap_to_strokes = self.engine_config().read().snap_to_strokes;
             let total_zoom = canvas.engine_ref().camera.total_zoom();
             let can_undo = canvas.engine_ref().can_undo();
             let can_redo = canvas.engine_ref().can_redo();
@@ -735,6 +746,7 @@ impl RnAppWindow {
             self.set_pen_style(pen_style);
             self.set_pen_sounds(pen_sounds);
             self.set_snap_positions(snap_positions);
+            self.set_snap_to_strokes(snap_to_strokes);
             self.set_visual_debug(visual_debug);
 
             // Current pen
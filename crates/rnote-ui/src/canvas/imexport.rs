@@ -5,12 +5,14 @@ use futures::channel::oneshot;
 use gtk4::{gio, prelude::*};
 use rnote_compose::ext::Vector2Ext;
 use rnote_engine::WidgetFlags;
-use rnote_engine::engine::export::{DocExportPrefs, DocPagesExportPrefs, SelectionExportPrefs};
+use rnote_engine::engine::export::{
+    DocExportPrefs, DocPagesExportPrefs, PageSelection, SelectionExportPrefs,
+};
 use rnote_engine::engine::{EngineSnapshot, StrokeContent};
 use rnote_engine::strokes::Stroke;
 use rnote_engine::strokes::resize::ImageSizeOption;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{debug, error};
 
 impl RnCanvas {
@@ -63,6 +65,7 @@ impl RnCanvas {
         &self,
         appwindow: &RnAppWindow,
         bytes: Vec<u8>,
+        file_path: Option<PathBuf>,
     ) -> anyhow::Result<()> {
         let xopp_import_prefs = appwindow
             .engine_config()
@@ -70,7 +73,7 @@ impl RnCanvas {
             .import_prefs
             .xopp_import_prefs;
         let engine_snapshot =
-            EngineSnapshot::load_from_xopp_bytes(bytes, xopp_import_prefs).await?;
+            EngineSnapshot::load_from_xopp_bytes(bytes, xopp_import_prefs, file_path).await?;
         let widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
         self.emit_handle_widget_flags(widget_flags);
 
@@ -279,8 +282,13 @@ impl RnCanvas {
         file: &gio::File,
         title: String,
         export_prefs_override: Option<DocExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> anyhow::Result<()> {
-        let export_bytes = self.engine_ref().export_doc(title, export_prefs_override);
+        let export_bytes = self.engine_ref().export_doc_w_page_selection(
+            title,
+            export_prefs_override,
+            page_selection,
+        );
 
         crate::utils::create_replace_file_future(export_bytes.await??, file).await?;
 
@@ -298,6 +306,7 @@ impl RnCanvas {
         dir: &gio::File,
         file_stem_name: String,
         export_prefs_override: Option<DocPagesExportPrefs>,
+        page_selection: Option<&PageSelection>,
     ) -> anyhow::Result<()> {
         if dir.query_file_type(gio::FileQueryInfoFlags::NONE, gio::Cancellable::NONE)
             != gio::FileType::Directory
@@ -315,15 +324,28 @@ impl RnCanvas {
         );
         let file_ext = export_prefs.export_format.file_ext();
 
-        let export_bytes_recv = self.engine_ref().export_doc_pages(export_prefs_override);
+        let export_bytes_recv = self
+            .engine_ref()
+            .export_doc_pages_w_page_selection(export_prefs_override, page_selection);
         let export_bytes = export_bytes_recv.await??;
+        // The exported pages keep their page numbers
+        let pages_amount = self
+            .engine_ref()
+            .pages_bounds_w_content(export_prefs.page_order)
+            .len();
+        let pages_indices = match page_selection {
+            Some(page_selection) => page_selection.indices(pages_amount),
+            None => (0..pages_amount).collect(),
+        };
 
-        for (i, page_bytes) in export_bytes.into_iter().enumerate() {
+        for (page_i, page_bytes) in pages_indices.into_iter().zip(export_bytes) {
             crate::utils::create_replace_file_future(
                 page_bytes,
                 &dir.child(
-                    &(rnote_engine::utils::doc_pages_files_names(file_stem_name.clone(), i + 1)
-                        + "."
+                    &(rnote_engine::utils::doc_pages_files_names(
+                        file_stem_name.clone(),
+                        page_i + 1,
+                    ) + "."
                         + &file_ext),
                 ),
             )
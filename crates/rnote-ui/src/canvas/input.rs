@@ -395,7 +395,18 @@ fn retrieve_pointer_elements(
                 Element::PRESSURE_DEFAULT
             };
 
-            entries.push((Element::new(pos, pressure), entry_time));
+            let element = Element::new(pos, pressure);
+            let element = if is_stylus {
+                element_w_stylus_axes(element, entry.time(), |axis_use| {
+                    available_axes
+                        .contains(crate::utils::axis_use_flag(axis_use))
+                        .then(|| axes[crate::utils::axis_use_idx(axis_use)])
+                })
+            } else {
+                element
+            };
+
+            entries.push((element, entry_time));
         }
 
         elements.extend(entries.into_iter().rev());
@@ -411,11 +422,43 @@ fn retrieve_pointer_elements(
         Element::PRESSURE_DEFAULT
     };
 
-    elements.push((Element::new(pos, pressure), now));
+    let element = Element::new(pos, pressure);
+    let element = if is_stylus {
+        element_w_stylus_axes(element, event_time, |axis_use| event.axis(axis_use))
+    } else {
+        element
+    };
+
+    elements.push((element, now));
 
     Some(elements)
 }
 
+/// Converts a gdk event timestamp in milliseconds to seconds.
+fn event_time_secs(time: u32) -> f64 {
+    f64::from(time) / 1000.0
+}
+
+/// Sets the event timestamp and the stylus tilt and rotation on the element, if they are reported by `axis`.
+///
+/// The timestamp is only needed for the velocity dynamics of stylus input, so it is not set for mouse and touch input.
+///
+/// Gdk normalizes the tilt to the range [-1.0, 1.0] and the rotation to [0.0, 1.0] for a full turn.
+fn element_w_stylus_axes(
+    element: Element,
+    time: u32,
+    axis: impl Fn(gdk::AxisUse) -> Option<f64>,
+) -> Element {
+    let mut element = element.with_time(event_time_secs(time));
+    if let (Some(xtilt), Some(ytilt)) = (axis(gdk::AxisUse::Xtilt), axis(gdk::AxisUse::Ytilt)) {
+        element = element.with_tilt(na::vector![xtilt, ytilt]);
+    }
+    if let Some(rotation) = axis(gdk::AxisUse::Rotation) {
+        element = element.with_rotation(rotation * std::f64::consts::TAU);
+    }
+    element
+}
+
 pub(crate) fn retrieve_button_shortcut_key(
     gdk_button: u32,
     is_stylus: bool,
@@ -3,8 +3,8 @@ use crate::{RnAppWindow, RnCanvas, RnContextMenu, canvas::reject_pointer_input};
 use gtk4::{
     CompositeTemplate, CornerType, EventControllerMotion, EventControllerScroll,
     EventControllerScrollFlags, EventSequenceState, GestureClick, GestureDrag, GestureLongPress,
-    GestureZoom, PropagationPhase, ScrolledWindow, Widget, gdk, glib, glib::clone, graphene,
-    prelude::*, subclass::prelude::*,
+    GestureRotate, GestureZoom, PropagationPhase, ScrolledWindow, Widget, gdk, glib, glib::clone,
+    graphene, prelude::*, subclass::prelude::*,
 };
 use once_cell::sync::Lazy;
 use rnote_compose::penevent::ShortcutKey;
@@ -39,6 +39,7 @@ mod imp {
         pub(crate) pointer_motion_controller: EventControllerMotion,
         pub(crate) canvas_drag_gesture: GestureDrag,
         pub(crate) canvas_zoom_gesture: GestureZoom,
+        pub(crate) canvas_rotate_gesture: GestureRotate,
         pub(crate) canvas_multi_press_gesture: GestureClick,
         pub(crate) canvas_zoom_scroll_controller: EventControllerScroll,
         pub(crate) canvas_mouse_drag_middle_gesture: GestureDrag,
@@ -76,6 +77,11 @@ mod imp {
                 .propagation_phase(PropagationPhase::Capture)
                 .build();
 
+            let canvas_rotate_gesture = GestureRotate::builder()
+                .name("canvas_rotate_gesture")
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
             let canvas_multi_press_gesture = GestureClick::builder()
                 .name("canvas_multi_press_gesture")
                 .button(gdk::BUTTON_PRIMARY)
@@ -138,6 +144,7 @@ mod imp {
                 pointer_motion_controller,
                 canvas_drag_gesture,
                 canvas_zoom_gesture,
+                canvas_rotate_gesture,
                 canvas_multi_press_gesture,
                 canvas_zoom_scroll_controller,
                 canvas_mouse_drag_middle_gesture,
@@ -180,6 +187,8 @@ mod imp {
                 .add_controller(self.canvas_drag_gesture.clone());
             self.scroller
                 .add_controller(self.canvas_zoom_gesture.clone());
+            self.scroller
+                .add_controller(self.canvas_rotate_gesture.clone());
             self.scroller
                 .add_controller(self.canvas_multi_press_gesture.clone());
             self.scroller
@@ -198,6 +207,8 @@ mod imp {
             // group
             self.touch_two_finger_long_press_gesture
                 .group_with(&self.canvas_zoom_gesture);
+            self.canvas_rotate_gesture
+                .group_with(&self.canvas_zoom_gesture);
 
             self.setup_input();
 
@@ -299,9 +310,13 @@ mod imp {
             if !self.block_pinch_zoom.get() && !self.canvas.touch_drawing() {
                 self.canvas_zoom_gesture
                     .set_propagation_phase(PropagationPhase::Capture);
+                self.canvas_rotate_gesture
+                    .set_propagation_phase(PropagationPhase::Capture);
             } else {
                 self.canvas_zoom_gesture
                     .set_propagation_phase(PropagationPhase::None);
+                self.canvas_rotate_gesture
+                    .set_propagation_phase(PropagationPhase::None);
             }
         }
 
@@ -387,7 +402,10 @@ mod imp {
                                     let p = canvaswrapper
                                         .compute_point(&canvas, &graphene::Point::from_na_vec(p))
                                         .unwrap();
-                                    p.to_na_vec()
+                                    canvas
+                                        .engine_ref()
+                                        .camera
+                                        .unrotate_surface_pos(p.to_na_vec())
                                 })
                                 .unwrap_or_else(|| camera_size * 0.5);
                             let new_camera_offset = (((camera_offset + screen_offset) / old_zoom)
@@ -429,7 +447,11 @@ mod imp {
                     obj,
                     move |_, x, y| {
                         let canvas = canvaswrapper.canvas();
-                        let new_offset = touch_drag_start.get() - na::vector![x, y];
+                        let new_offset = touch_drag_start.get()
+                            - canvas
+                                .engine_ref()
+                                .camera
+                                .unrotate_surface_vec(na::vector![x, y]);
                         let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
                         canvas.emit_handle_widget_flags(widget_flags);
                     }
@@ -472,7 +494,11 @@ mod imp {
                         obj,
                         move |_, x, y| {
                             let canvas = canvaswrapper.canvas();
-                            let new_offset = mouse_drag_start.get() - na::vector![x, y];
+                            let new_offset = mouse_drag_start.get()
+                                - canvas
+                                    .engine_ref()
+                                    .camera
+                                    .unrotate_surface_vec(na::vector![x, y]);
                             let widget_flags =
                                 canvas.engine_mut().camera_set_offset_expand(new_offset);
                             canvas.emit_handle_widget_flags(widget_flags);
@@ -527,11 +553,15 @@ mod imp {
                         new_zoom.set(current_zoom);
                         prev_scale.set(1.0);
 
-                        bbcenter_begin.set(
-                            gesture
-                                .bounding_box_center()
-                                .map(|(x, y)| na::vector![x, y]),
-                        );
+                        // The centers are kept in unrotated surface coordinates,
+                        // so that the content under the fingers follows when the view is rotated at the same time.
+                        bbcenter_begin.set(gesture.bounding_box_center().map(|(x, y)| {
+                            canvaswrapper
+                                .canvas()
+                                .engine_ref()
+                                .camera
+                                .unrotate_surface_pos(na::vector![x, y])
+                        }));
                         offset_begin.set(canvaswrapper.canvas().engine_ref().camera.offset());
                     }
                 ));
@@ -561,9 +591,13 @@ mod imp {
 
                         let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom.get());
 
-                        if let Some(bbcenter_current) = gesture
-                            .bounding_box_center()
-                            .map(|(x, y)| na::vector![x, y])
+                        if let Some(bbcenter_current) =
+                            gesture.bounding_box_center().map(|(x, y)| {
+                                canvas
+                                    .engine_ref()
+                                    .camera
+                                    .unrotate_surface_pos(na::vector![x, y])
+                            })
                         {
                             let bbcenter_begin = if let Some(bbcenter_begin) = bbcenter_begin.get()
                             {
@@ -621,6 +655,71 @@ mod imp {
                 ));
             }
 
+            // Canvas gesture rotating with two fingers
+            {
+                let rotation_begin = Rc::new(Cell::new(0_f64));
+
+                self.canvas_rotate_gesture.connect_begin(clone!(
+                    #[strong]
+                    rotation_begin,
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_, _| {
+                        rotation_begin.set(canvaswrapper.canvas().engine_ref().camera.rotation());
+                    }
+                ));
+
+                self.canvas_rotate_gesture.connect_angle_changed(clone!(
+                    #[strong]
+                    rotation_begin,
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_, _angle, angle_delta| {
+                        // Snap to the upright and sideways orientations
+                        const SNAP_ANGLE: f64 = std::f64::consts::FRAC_PI_2;
+                        const SNAP_TOLERANCE: f64 = 5.0_f64.to_radians();
+
+                        let canvas = canvaswrapper.canvas();
+                        let mut new_rotation = rotation_begin.get() + angle_delta;
+                        let snapped = (new_rotation / SNAP_ANGLE).round() * SNAP_ANGLE;
+                        if (new_rotation - snapped).abs() < SNAP_TOLERANCE {
+                            new_rotation = snapped;
+                        }
+
+                        let widget_flags = canvas.engine_mut().camera_rotate_to(new_rotation);
+                        canvas.emit_handle_widget_flags(widget_flags);
+                    }
+                ));
+
+                self.canvas_rotate_gesture.connect_end(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_gesture, _event_sequence| {
+                        let widget_flags = canvaswrapper
+                            .canvas()
+                            .engine_mut()
+                            .update_rendering_current_viewport();
+                        canvaswrapper
+                            .canvas()
+                            .emit_handle_widget_flags(widget_flags);
+                    }
+                ));
+
+                self.canvas_rotate_gesture.connect_cancel(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |_gesture, _event_sequence| {
+                        let widget_flags = canvaswrapper
+                            .canvas()
+                            .engine_mut()
+                            .update_rendering_current_viewport();
+                        canvaswrapper
+                            .canvas()
+                            .emit_handle_widget_flags(widget_flags);
+                    }
+                ));
+            }
+
             // Pan with alt + drag
             {
                 let offset_start = Rc::new(Cell::new(na::Vector2::<f64>::zeros()));
@@ -652,7 +751,11 @@ mod imp {
                     obj,
                     move |_, offset_x, offset_y| {
                         let canvas = canvaswrapper.canvas();
-                        let new_offset = offset_start.get() - na::vector![offset_x, offset_y];
+                        let new_offset = offset_start.get()
+                            - canvas
+                                .engine_ref()
+                                .camera
+                                .unrotate_surface_vec(na::vector![offset_x, offset_y]);
                         let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
                         canvas.emit_handle_widget_flags(widget_flags);
                     }
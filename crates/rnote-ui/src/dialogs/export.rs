@@ -11,7 +11,7 @@ use num_traits::ToPrimitive;
 use rnote_compose::SplitOrder;
 use rnote_engine::document::Layout;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, PageSelection,
     SelectionExportFormat, SelectionExportPrefs,
 };
 use std::cell::RefCell;
@@ -103,8 +103,12 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     let with_pattern_row: adw::SwitchRow = builder.object("export_doc_with_pattern_row").unwrap();
     let optimize_printing_row: adw::SwitchRow =
         builder.object("export_doc_optimize_printing_row").unwrap();
+    let with_outline_row: adw::SwitchRow = builder.object("export_doc_with_outline_row").unwrap();
     let export_format_row: adw::ComboRow = builder.object("export_doc_export_format_row").unwrap();
     let page_order_row: adw::ComboRow = builder.object("export_doc_page_order_row").unwrap();
+    let page_selection_entryrow: adw::EntryRow = builder
+        .object("export_doc_page_selection_entryrow")
+        .unwrap();
     let export_file_label: Label = builder.object("export_doc_export_file_label").unwrap();
     let export_file_button: Button = builder.object("export_doc_export_file_button").unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_doc_preview").unwrap();
@@ -123,6 +127,7 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     with_background_row.set_active(initial_doc_export_prefs.with_background);
     with_pattern_row.set_active(initial_doc_export_prefs.with_pattern);
     optimize_printing_row.set_active(initial_doc_export_prefs.optimize_printing);
+    with_outline_row.set_active(initial_doc_export_prefs.with_outline);
     preview.set_draw_background(initial_doc_export_prefs.with_background);
     preview.set_draw_pattern(initial_doc_export_prefs.with_pattern);
     preview.set_optimize_printing(initial_doc_export_prefs.optimize_printing);
@@ -136,6 +141,10 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     export_file_label.set_label(&gettext("- no file selected -"));
     page_order_row
         .set_sensitive(doc_layout == Layout::SemiInfinite || doc_layout == Layout::Infinite);
+    with_outline_row.set_sensitive(initial_doc_export_prefs.export_format == DocExportFormat::Pdf);
+    // Annotated Pdfs keep all pages of the source Pdf
+    page_selection_entryrow
+        .set_sensitive(initial_doc_export_prefs.export_format != DocExportFormat::AnnotatedPdf);
     button_confirm.set_sensitive(false);
 
     // Update prefs
@@ -229,6 +238,19 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         }
     ));
 
+    with_outline_row.connect_active_notify(clone!(
+        #[weak]
+        appwindow,
+        move |with_outline_row| {
+            appwindow
+                .engine_config()
+                .write()
+                .export_prefs
+                .doc_export_prefs
+                .with_outline = with_outline_row.is_active();
+        }
+    ));
+
     export_format_row.connect_selected_notify(clone!(
         #[strong]
         selected_file,
@@ -237,6 +259,10 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         #[weak]
         button_confirm,
         #[weak]
+        with_outline_row,
+        #[weak]
+        page_selection_entryrow,
+        #[weak]
         appwindow,
         move |row| {
             let export_format = DocExportFormat::try_from(row.selected()).unwrap();
@@ -246,6 +272,8 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
                 .export_prefs
                 .doc_export_prefs
                 .export_format = export_format;
+            with_outline_row.set_sensitive(export_format == DocExportFormat::Pdf);
+            page_selection_entryrow.set_sensitive(export_format != DocExportFormat::AnnotatedPdf);
 
             // force the user to pick another file
             export_file_label.set_label(&gettext("- no file selected -"));
@@ -274,6 +302,8 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         }
     ));
 
+    page_selection_entryrow.connect_changed(update_page_selection_entryrow_error);
+
     // Listen to responses
 
     export_doc_button_cancel.connect_clicked(clone!(
@@ -284,7 +314,21 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         }
     ));
 
-    export_doc_button_confirm.connect_clicked(clone!(#[weak] dialog, #[weak] canvas, #[weak] appwindow , move |_| {
+    export_doc_button_confirm.connect_clicked(clone!(#[weak] page_selection_entryrow, #[weak] dialog, #[weak] canvas, #[weak] appwindow , move |_| {
+        let page_selection = if page_selection_entryrow.is_sensitive() {
+            match parse_page_selection(&page_selection_entryrow) {
+                Ok(page_selection) => page_selection,
+                Err(e) => {
+                    debug!("Invalid page selection in export doc dialog, Err: {e:?}");
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("Invalid page selection"));
+                    return;
+                }
+            }
+        } else {
+            None
+        };
         dialog.close();
 
         let Some(file) = selected_file.take() else {
@@ -303,7 +347,7 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
                 None,
             );
 
-            if let Err(e) = canvas.export_doc(&file, file_title, None).await {
+            if let Err(e) = canvas.export_doc(&file, file_title, None, page_selection.as_ref()).await {
                 error!("Exporting document failed, Err: `{e:?}`");
                 appwindow.overlays().dispatch_toast_error(&gettext("Exporting document failed"));
                 appwindow.overlays().progressbar_abort();
@@ -367,7 +411,7 @@ fn create_filedialog_export_doc(
             }
             filter.set_name(Some(&gettext("Svg")));
         }
-        DocExportFormat::Pdf => {
+        DocExportFormat::Pdf | DocExportFormat::AnnotatedPdf => {
             if cfg!(target_os = "windows") {
                 filter.add_pattern("*.pdf");
             } else {
@@ -426,6 +470,9 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         .object("export_doc_pages_export_format_row")
         .unwrap();
     let page_order_row: adw::ComboRow = builder.object("export_doc_pages_page_order_row").unwrap();
+    let page_selection_entryrow: adw::EntryRow = builder
+        .object("export_doc_pages_page_selection_entryrow")
+        .unwrap();
     let bitmap_scalefactor_row: adw::SpinRow = builder
         .object("export_doc_pages_bitmap_scalefactor_row")
         .unwrap();
@@ -657,6 +704,8 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         }
     ));
 
+    page_selection_entryrow.connect_changed(update_page_selection_entryrow_error);
+
     bitmap_scalefactor_row.connect_value_notify(clone!(
         #[weak]
         appwindow,
@@ -718,7 +767,17 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         }
     ));
 
-    export_doc_pages_button_confirm.connect_clicked(clone!(#[weak] export_files_stemname_entryrow, #[weak] dialog, #[weak] canvas, #[weak] appwindow,  move |_| {
+    export_doc_pages_button_confirm.connect_clicked(clone!(#[weak] export_files_stemname_entryrow, #[weak] page_selection_entryrow, #[weak] dialog, #[weak] canvas, #[weak] appwindow,  move |_| {
+        let page_selection = match parse_page_selection(&page_selection_entryrow) {
+            Ok(page_selection) => page_selection,
+            Err(e) => {
+                debug!("Invalid page selection in export doc pages dialog, Err: {e:?}");
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Invalid page selection"));
+                return;
+            }
+        };
         dialog.close();
 
         let Some(dir) = selected_file.take() else {
@@ -733,7 +792,7 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
 
             let file_stem_name = export_files_stemname_entryrow.text().to_string();
 
-            if let Err(e) = canvas.export_doc_pages(&appwindow, &dir, file_stem_name, None).await {
+            if let Err(e) = canvas.export_doc_pages(&appwindow, &dir, file_stem_name, None, page_selection.as_ref()).await {
                 error!("Exporting document pages failed, Err: {e:?}");
                 appwindow.overlays().dispatch_toast_error(&gettext("Exporting document pages failed"));
                 appwindow.overlays().progressbar_abort();
@@ -764,6 +823,24 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
     dialog.present(appwindow.root().as_ref());
 }
 
+/// Parses the page selection of the entry row. All pages are selected when it is empty.
+fn parse_page_selection(entryrow: &adw::EntryRow) -> anyhow::Result<Option<PageSelection>> {
+    let text = entryrow.text();
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    text.parse::<PageSelection>().map(Some)
+}
+
+/// Marks the page selection entry row as erroneous when its text is not a valid page selection.
+fn update_page_selection_entryrow_error(entryrow: &adw::EntryRow) {
+    if parse_page_selection(entryrow).is_ok() {
+        entryrow.remove_css_class("error");
+    } else {
+        entryrow.add_css_class("error");
+    }
+}
+
 fn create_filedialog_export_doc_pages(
     appwindow: &RnAppWindow,
     canvas: &RnCanvas,
@@ -669,7 +669,9 @@ pub(crate) async fn dialog_import_xopp_w_prefs(
                                 return;
                             }
                         };
-                        if let Err(e) = canvas.load_in_xopp_bytes(&appwindow, bytes.to_vec()).await
+                        if let Err(e) = canvas
+                            .load_in_xopp_bytes(&appwindow, bytes.to_vec(), input_file.path())
+                            .await
                         {
                             if let Err(e) = tx_import.unbounded_send(Err(e)) {
                                 error!(
@@ -15,8 +15,13 @@ use gettextrs::{gettext, pgettext};
 use gtk4::ShortcutsWindow;
 use gtk4::{
     Builder, Button, CheckButton, ColorDialogButton, FileDialog, Label, MenuButton, StringList,
-    gio, glib, glib::clone,
+    gdk, gio, glib, glib::clone,
 };
+use num_traits::ToPrimitive;
+use rnote_engine::document::format::MeasureUnit;
+use rnote_engine::ext::GdkRGBAExt;
+use std::cell::Cell;
+use std::rc::Rc;
 use tracing::{debug, error, warn};
 
 // About Dialog
@@ -589,6 +594,255 @@ pub(crate) async fn dialog_edit_selected_workspace(appwindow: &RnAppWindow) {
     dialog.present(appwindow.root().as_ref());
 }
 
+pub(crate) async fn dialog_transform_selection(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_transform_selection").unwrap();
+    let unit_row: adw::ComboRow = builder.object("transform_selection_unit_row").unwrap();
+    let x_row: adw::SpinRow = builder.object("transform_selection_x_row").unwrap();
+    let y_row: adw::SpinRow = builder.object("transform_selection_y_row").unwrap();
+    let width_row: adw::SpinRow = builder.object("transform_selection_width_row").unwrap();
+    let height_row: adw::SpinRow = builder.object("transform_selection_height_row").unwrap();
+    let angle_row: adw::SpinRow = builder.object("transform_selection_angle_row").unwrap();
+    let transform_selection_button_cancel: Button =
+        builder.object("transform_selection_button_cancel").unwrap();
+    let transform_selection_button_apply: Button =
+        builder.object("transform_selection_button_apply").unwrap();
+
+    let Some((pos, size)) = canvas
+        .engine_ref()
+        .selection_bounds_in_unit(MeasureUnit::Px)
+    else {
+        warn!("Tried to transform the selection in dialog, but nothing is selected.");
+        return;
+    };
+    let dpi = canvas.engine_ref().document.config.format.dpi();
+    let unit = Rc::new(Cell::new(MeasureUnit::Px));
+
+    // set initial dialog UI on popup
+    unit_row.set_selected(MeasureUnit::Px.to_u32().unwrap());
+    x_row.set_value(pos[0]);
+    y_row.set_value(pos[1]);
+    width_row.set_value(size[0]);
+    height_row.set_value(size[1]);
+
+    // Convert the entered values when the unit changes
+    unit_row.connect_selected_notify(clone!(
+        #[strong]
+        unit,
+        #[weak]
+        x_row,
+        #[weak]
+        y_row,
+        #[weak]
+        width_row,
+        #[weak]
+        height_row,
+        move |row| {
+            let Ok(new_unit) = MeasureUnit::try_from(row.selected()) else {
+                return;
+            };
+            for spin_row in [&x_row, &y_row, &width_row, &height_row] {
+                spin_row.set_value(MeasureUnit::convert_measurement(
+                    spin_row.value(),
+                    unit.get(),
+                    dpi,
+                    new_unit,
+                    dpi,
+                ));
+            }
+            unit.set(new_unit);
+        }
+    ));
+
+    // Listen to responses
+
+    transform_selection_button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    transform_selection_button_apply.connect_clicked(clone!(
+        #[strong]
+        unit,
+        #[weak]
+        x_row,
+        #[weak]
+        y_row,
+        #[weak]
+        width_row,
+        #[weak]
+        height_row,
+        #[weak]
+        angle_row,
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |_| {
+            dialog.close();
+
+            let pos = na::vector![x_row.value(), y_row.value()];
+            let size = na::vector![width_row.value(), height_row.value()];
+            let angle = angle_row.value().to_radians();
+            let widget_flags =
+                canvas
+                    .engine_mut()
+                    .transform_selection(pos, size, unit.get(), angle);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+pub(crate) async fn dialog_page_properties(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    page_i: usize,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_page_properties").unwrap();
+    let title_entryrow: adw::EntryRow = builder.object("page_properties_title_entryrow").unwrap();
+    let custom_format_row: adw::SwitchRow =
+        builder.object("page_properties_custom_format_row").unwrap();
+    let width_row: adw::SpinRow = builder.object("page_properties_width_row").unwrap();
+    let height_row: adw::SpinRow = builder.object("page_properties_height_row").unwrap();
+    let custom_background_row: adw::SwitchRow = builder
+        .object("page_properties_custom_background_row")
+        .unwrap();
+    let background_color_row: adw::ActionRow = builder
+        .object("page_properties_background_color_row")
+        .unwrap();
+    let background_color_button: ColorDialogButton = builder
+        .object("page_properties_background_color_button")
+        .unwrap();
+    let pattern_color_row: adw::ActionRow =
+        builder.object("page_properties_pattern_color_row").unwrap();
+    let pattern_color_button: ColorDialogButton = builder
+        .object("page_properties_pattern_color_button")
+        .unwrap();
+    let page_properties_button_cancel: Button =
+        builder.object("page_properties_button_cancel").unwrap();
+    let page_properties_button_apply: Button =
+        builder.object("page_properties_button_apply").unwrap();
+
+    let (
+        initial_format,
+        initial_background,
+        initial_title,
+        has_custom_format,
+        has_custom_background,
+    ) = {
+        let engine = canvas.engine_ref();
+        let Some(page) = engine.document.pages.get(page_i) else {
+            warn!("Tried to edit the properties of page {page_i}, but it does not exist.");
+            return;
+        };
+        (
+            engine.document.page_format(page_i),
+            engine.document.page_background(page_i),
+            page.title.clone(),
+            page.format.is_some(),
+            page.background.is_some(),
+        )
+    };
+
+    // set initial dialog UI on popup
+    title_entryrow.set_text(initial_title.as_deref().unwrap_or_default());
+    custom_format_row.set_active(has_custom_format);
+    width_row.set_value(initial_format.width());
+    height_row.set_value(initial_format.height());
+    custom_background_row.set_active(has_custom_background);
+    background_color_button.set_rgba(&gdk::RGBA::from_compose_color(initial_background.color));
+    pattern_color_button.set_rgba(&gdk::RGBA::from_compose_color(
+        initial_background.pattern_color,
+    ));
+    custom_format_row
+        .bind_property("active", &width_row, "sensitive")
+        .sync_create()
+        .build();
+    custom_format_row
+        .bind_property("active", &height_row, "sensitive")
+        .sync_create()
+        .build();
+    custom_background_row
+        .bind_property("active", &background_color_row, "sensitive")
+        .sync_create()
+        .build();
+    custom_background_row
+        .bind_property("active", &pattern_color_row, "sensitive")
+        .sync_create()
+        .build();
+
+    // Listen to responses
+
+    page_properties_button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    page_properties_button_apply.connect_clicked(clone!(
+        #[weak]
+        title_entryrow,
+        #[weak]
+        custom_format_row,
+        #[weak]
+        width_row,
+        #[weak]
+        height_row,
+        #[weak]
+        custom_background_row,
+        #[weak]
+        background_color_button,
+        #[weak]
+        pattern_color_button,
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        move |_| {
+            dialog.close();
+
+            let title = Some(title_entryrow.text().trim().to_string()).filter(|t| !t.is_empty());
+            let format = custom_format_row.is_active().then(|| {
+                let mut format = initial_format;
+                format.set_width(width_row.value());
+                format.set_height(height_row.value());
+                format
+            });
+            let background = custom_background_row.is_active().then(|| {
+                let mut background = initial_background;
+                background.color = background_color_button.rgba().into_compose_color();
+                background.pattern_color = pattern_color_button.rgba().into_compose_color();
+                background
+            });
+
+            let mut widget_flags = canvas.engine_mut().doc_set_page_title(page_i, title);
+            widget_flags |= canvas
+                .engine_mut()
+                .doc_set_page_background(page_i, background);
+            widget_flags |= canvas.engine_mut().doc_set_page_format(page_i, format);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
 pub(crate) async fn dialog_trash_file(appwindow: &RnAppWindow, current_file: &gio::File) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
@@ -7,8 +7,8 @@ use gtk4::{
 };
 use num_traits::cast::ToPrimitive;
 use rnote_compose::builders::PenPathBuilderType;
-use rnote_compose::style::PressureCurve;
 use rnote_compose::style::textured::{TexturedDotsDistribution, TexturedOptions};
+use rnote_compose::style::{PressureCurve, StrokeDynamics};
 use rnote_engine::pens::pensconfig::BrushConfig;
 use rnote_engine::pens::pensconfig::brushconfig::{BrushStyle, SolidOptions};
 
@@ -47,8 +47,14 @@ mod imp {
         #[template_child]
         pub(crate) brush_buildertype_modeled: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) shape_recognition_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) solidstyle_pressure_curves_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) dynamics_tilt_sensitivity_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) dynamics_velocity_sensitivity_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) texturedstyle_density_row: TemplateChild<adw::SpinRow>,
         #[template_child]
         pub(crate) texturedstyle_distribution_row: TemplateChild<adw::ComboRow>,
@@ -363,6 +369,22 @@ impl RnBrushPage {
             }
         ));
 
+        // Shape recognition
+        imp.shape_recognition_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .brush_config
+                        .shape_recognition = row.is_active();
+                }
+            ));
+
         // Solid style
         // Pressure curve
         imp.solidstyle_pressure_curves_row
@@ -383,6 +405,42 @@ impl RnBrushPage {
                 }
             ));
 
+        // Stroke dynamics
+        imp.dynamics_tilt_sensitivity_row.get().set_range(
+            StrokeDynamics::SENSITIVITY_MIN,
+            StrokeDynamics::SENSITIVITY_MAX,
+        );
+        imp.dynamics_velocity_sensitivity_row.get().set_range(
+            StrokeDynamics::SENSITIVITY_MIN,
+            StrokeDynamics::SENSITIVITY_MAX,
+        );
+
+        imp.dynamics_tilt_sensitivity_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let mut engine_config = appwindow.engine_config().write();
+                    let brush_config = &mut engine_config.pens_config.brush_config;
+                    brush_config.solid_options.dynamics.tilt_sensitivity = row.value();
+                    brush_config.textured_options.dynamics.tilt_sensitivity = row.value();
+                }
+            ));
+
+        imp.dynamics_velocity_sensitivity_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let mut engine_config = appwindow.engine_config().write();
+                    let brush_config = &mut engine_config.pens_config.brush_config;
+                    brush_config.solid_options.dynamics.velocity_sensitivity = row.value();
+                    brush_config.textured_options.dynamics.velocity_sensitivity = row.value();
+                }
+            ));
+
         // Textured style
         // Density
         imp.texturedstyle_density_row
@@ -436,7 +494,13 @@ impl RnBrushPage {
             .brush_config
             .clone();
 
+        imp.shape_recognition_row
+            .set_active(brush_config.shape_recognition);
         self.set_solidstyle_pressure_curve(brush_config.solid_options.pressure_curve);
+        imp.dynamics_tilt_sensitivity_row
+            .set_value(brush_config.solid_options.dynamics.tilt_sensitivity);
+        imp.dynamics_velocity_sensitivity_row
+            .set_value(brush_config.solid_options.dynamics.velocity_sensitivity);
         imp.texturedstyle_density_row
             .set_value(brush_config.textured_options.density);
         self.set_texturedstyle_distribution_variant(brush_config.textured_options.distribution);
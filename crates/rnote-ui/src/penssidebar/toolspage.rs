@@ -21,6 +21,12 @@ mod imp {
         #[template_child]
         pub(crate) toolstyle_laser_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) toolstyle_ruler_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) toolstyle_protractor_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) toolstyle_setsquare_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) verticalspace_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) verticalspace_popover: TemplateChild<Popover>,
@@ -31,6 +37,16 @@ mod imp {
         #[template_child]
         pub(crate) verticalspace_limit_movement_horizontal_bordersrow:
             TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) drawingaids_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) drawingaids_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) drawingaids_show_ruler_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) drawingaids_show_protractor_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) drawingaids_show_setsquare_row: TemplateChild<adw::SwitchRow>,
     }
 
     #[glib::object_subclass]
@@ -93,6 +109,12 @@ impl RnToolsPage {
             Some(ToolStyle::Zoom)
         } else if imp.toolstyle_laser_toggle.is_active() {
             Some(ToolStyle::Laser)
+        } else if imp.toolstyle_ruler_toggle.is_active() {
+            Some(ToolStyle::Ruler)
+        } else if imp.toolstyle_protractor_toggle.is_active() {
+            Some(ToolStyle::Protractor)
+        } else if imp.toolstyle_setsquare_toggle.is_active() {
+            Some(ToolStyle::SetSquare)
         } else {
             None
         }
@@ -112,6 +134,9 @@ impl RnToolsPage {
             ToolStyle::OffsetCamera => imp.toolstyle_offsetcamera_toggle.set_active(true),
             ToolStyle::Zoom => imp.toolstyle_zoom_toggle.set_active(true),
             ToolStyle::Laser => imp.toolstyle_laser_toggle.set_active(true),
+            ToolStyle::Ruler => imp.toolstyle_ruler_toggle.set_active(true),
+            ToolStyle::Protractor => imp.toolstyle_protractor_toggle.set_active(true),
+            ToolStyle::SetSquare => imp.toolstyle_setsquare_toggle.set_active(true),
         }
     }
 
@@ -119,6 +144,7 @@ impl RnToolsPage {
         let imp = self.imp();
         // for now doesn't do anything but for the close button later
         let verticalspace_popover = imp.verticalspace_popover.get();
+        let drawingaids_popover = imp.drawingaids_popover.get();
 
         imp.toolstyle_verticalspace_toggle.connect_toggled(clone!(
             #[weak]
@@ -204,6 +230,81 @@ impl RnToolsPage {
             }
         ));
 
+        imp.toolstyle_ruler_toggle.connect_toggled(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                {
+                    let mut engine_config = appwindow.engine_config().write();
+                    engine_config.pens_config.tools_config.style = ToolStyle::Ruler;
+                    engine_config.pens_config.tools_config.show_ruler = true;
+                }
+                toolspage.imp().drawingaids_show_ruler_row.set_active(true);
+
+                if let Some(canvas) = appwindow.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                };
+            }
+        ));
+
+        imp.toolstyle_protractor_toggle.connect_toggled(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                {
+                    let mut engine_config = appwindow.engine_config().write();
+                    engine_config.pens_config.tools_config.style = ToolStyle::Protractor;
+                    engine_config.pens_config.tools_config.show_protractor = true;
+                }
+                toolspage
+                    .imp()
+                    .drawingaids_show_protractor_row
+                    .set_active(true);
+
+                if let Some(canvas) = appwindow.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                };
+            }
+        ));
+
+        imp.toolstyle_setsquare_toggle.connect_toggled(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            #[weak]
+            appwindow,
+            move |toggle| {
+                if !toggle.is_active() {
+                    return;
+                }
+                {
+                    let mut engine_config = appwindow.engine_config().write();
+                    engine_config.pens_config.tools_config.style = ToolStyle::SetSquare;
+                    engine_config.pens_config.tools_config.show_setsquare = true;
+                }
+                toolspage
+                    .imp()
+                    .drawingaids_show_setsquare_row
+                    .set_active(true);
+
+                if let Some(canvas) = appwindow.active_tab_canvas() {
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                };
+            }
+        ));
+
         imp.verticalspace_menubutton.connect_active_notify(clone!(
             #[weak(rename_to=toolspage)]
             self,
@@ -223,6 +324,66 @@ impl RnToolsPage {
                 }
             ));
 
+        imp.drawingaids_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            drawingaids_popover,
+            move |_| {
+                drawingaids_popover.popdown();
+            }
+        ));
+
+        imp.drawingaids_show_ruler_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .tools_config
+                        .show_ruler = row.is_active();
+                    if let Some(canvas) = appwindow.active_tab_canvas() {
+                        canvas.queue_draw();
+                    }
+                }
+            ));
+        imp.drawingaids_show_protractor_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .tools_config
+                        .show_protractor = row.is_active();
+                    if let Some(canvas) = appwindow.active_tab_canvas() {
+                        canvas.queue_draw();
+                    }
+                }
+            ));
+        imp.drawingaids_show_setsquare_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .tools_config
+                        .show_setsquare = row.is_active();
+                    if let Some(canvas) = appwindow.active_tab_canvas() {
+                        canvas.queue_draw();
+                    }
+                }
+            ));
+
         imp.verticalspace_limit_movement_vertical_bordersrow
             .get()
             .connect_active_notify(clone!(
@@ -278,5 +439,11 @@ impl RnToolsPage {
                     .verticalspace_tool_config
                     .limit_movement_vertical_borders,
             );
+        imp.drawingaids_show_ruler_row
+            .set_active(tools_config.show_ruler);
+        imp.drawingaids_show_protractor_row
+            .set_active(tools_config.show_protractor);
+        imp.drawingaids_show_setsquare_row
+            .set_active(tools_config.show_setsquare);
     }
 }
@@ -29,6 +29,8 @@ mod imp {
         #[template_child]
         pub(crate) text_strikethrough_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) text_section_heading_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) text_align_start_togglebutton: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) text_align_center_togglebutton: TemplateChild<ToggleButton>,
@@ -210,6 +212,30 @@ impl RnTypewriterPage {
             }
         ));
 
+        // Section heading
+        imp.text_section_heading_togglebutton
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |togglebutton| {
+                    let section_heading = togglebutton.is_active();
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    appwindow
+                        .engine_config()
+                        .write()
+                        .pens_config
+                        .typewriter_config
+                        .text_style
+                        .section_heading = section_heading;
+                    let widget_flags = canvas.engine_mut().text_selection_change_style(|style| {
+                        style.section_heading = section_heading
+                    });
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
         // Alignment
         imp.text_align_start_togglebutton
             .connect_active_notify(clone!(
@@ -326,5 +352,7 @@ impl RnTypewriterPage {
             .set_value(typewriter_config.text_style.font_size);
 
         self.set_alignment(typewriter_config.text_style.alignment);
+        imp.text_section_heading_togglebutton
+            .set_active(typewriter_config.text_style.section_heading);
     }
 }
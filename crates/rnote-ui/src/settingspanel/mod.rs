@@ -39,6 +39,8 @@ mod imp {
         #[template_child]
         pub(crate) general_autosave_interval_secs_row: TemplateChild<adw::SpinRow>,
         #[template_child]
+        pub(crate) general_save_history_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) general_show_scrollbars_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub(crate) general_optimize_epd_row: TemplateChild<adw::SwitchRow>,
@@ -93,6 +95,8 @@ mod imp {
         #[template_child]
         pub(crate) doc_document_layout_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) doc_two_page_spread_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) doc_show_format_borders_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub(crate) doc_format_border_color_button: TemplateChild<ColorDialogButton>,
@@ -419,6 +423,8 @@ impl RnSettingsPanel {
         let imp = self.imp();
         let canvas = appwindow.active_tab_canvas();
 
+        let save_history = appwindow.engine_config().read().save_history;
+        imp.general_save_history_row.set_active(save_history);
         let optimize_epd = appwindow.engine_config().read().optimize_epd;
         imp.general_optimize_epd_row.set_active(optimize_epd);
 
@@ -482,6 +488,10 @@ impl RnSettingsPanel {
             imp.doc_background_pattern_height_unitentry
                 .set_value_in_px(background.pattern_size[1]);
             self.set_document_layout(&document_layout);
+            imp.doc_two_page_spread_row
+                .set_active(canvas.engine_ref().document.config.two_page_spread);
+            imp.doc_two_page_spread_row
+                .set_sensitive(document_layout == Layout::FixedSize);
             imp.doc_show_origin_indicator_row
                 .set_active(show_origin_indicator);
         }
@@ -606,6 +616,15 @@ impl RnSettingsPanel {
             }
         ));
 
+        imp.general_save_history_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let save_history = row.is_active();
+                appwindow.engine_config().write().save_history = save_history;
+            }
+        ));
+
         // Regular cursor picker
         imp.general_regular_cursor_picker.set_list(
             StringList::new(CURSORS_LIST),
@@ -772,6 +791,7 @@ impl RnSettingsPanel {
 
                 let doc_config = canvas.engine_ref().document.config.clone();
                 appwindow.document_config_preset_mut().layout = doc_config.layout;
+                appwindow.document_config_preset_mut().two_page_spread = doc_config.two_page_spread;
                 appwindow.document_config_preset_mut().format.border_color =
                     doc_config.format.border_color;
                 appwindow.document_config_preset_mut().background.color =
@@ -811,6 +831,7 @@ impl RnSettingsPanel {
 
                 let doc_config = appwindow.document_config_preset_ref().clone();
                 canvas.engine_mut().document.config.layout = doc_config.layout;
+                canvas.engine_mut().document.config.two_page_spread = doc_config.two_page_spread;
                 canvas.engine_mut().document.config.format.border_color =
                     doc_config.format.border_color;
                 canvas.engine_mut().document.config.background.color = doc_config.background.color;
@@ -836,6 +857,25 @@ impl RnSettingsPanel {
             }
         ));
 
+        imp.doc_two_page_spread_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let two_page_spread = row.is_active();
+
+                if canvas.engine_ref().document.config.two_page_spread != two_page_spread {
+                    let mut widget_flags =
+                        canvas.engine_mut().set_doc_two_page_spread(two_page_spread);
+                    widget_flags |= canvas.engine_mut().background_rendering_regenerate();
+                    widget_flags.store_modified = true;
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            }
+        ));
+
         imp.doc_show_format_borders_row
             .connect_active_notify(clone!(
                 #[weak]
@@ -933,6 +973,10 @@ impl RnSettingsPanel {
                         .canvasmenu()
                         .fixedsize_quickactions_box()
                         .set_sensitive(document_layout == Layout::FixedSize);
+                    settings_panel
+                        .imp()
+                        .doc_two_page_spread_row
+                        .set_sensitive(document_layout == Layout::FixedSize);
 
                     if canvas.engine_ref().document.config.layout != document_layout {
                         let mut widget_flags = canvas.engine_mut().set_doc_layout(document_layout);
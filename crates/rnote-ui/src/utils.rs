@@ -86,6 +86,23 @@ pub(crate) fn axis_use_idx(a: gdk::AxisUse) -> usize {
     }
 }
 
+pub(crate) fn axis_use_flag(a: gdk::AxisUse) -> gdk::AxisFlags {
+    match a {
+        gdk::AxisUse::X => gdk::AxisFlags::X,
+        gdk::AxisUse::Y => gdk::AxisFlags::Y,
+        gdk::AxisUse::DeltaX => gdk::AxisFlags::DELTA_X,
+        gdk::AxisUse::DeltaY => gdk::AxisFlags::DELTA_Y,
+        gdk::AxisUse::Pressure => gdk::AxisFlags::PRESSURE,
+        gdk::AxisUse::Xtilt => gdk::AxisFlags::XTILT,
+        gdk::AxisUse::Ytilt => gdk::AxisFlags::YTILT,
+        gdk::AxisUse::Wheel => gdk::AxisFlags::WHEEL,
+        gdk::AxisUse::Distance => gdk::AxisFlags::DISTANCE,
+        gdk::AxisUse::Rotation => gdk::AxisFlags::ROTATION,
+        gdk::AxisUse::Slider => gdk::AxisFlags::SLIDER,
+        _ => unreachable!(),
+    }
+}
+
 pub(crate) fn default_file_title_for_export(
     output_file: Option<gio::File>,
     fallback: Option<&str>,